@@ -1,9 +1,22 @@
+pub mod aggregates;
 pub mod box_score;
+pub mod builder;
+#[cfg(feature = "polars")]
+pub mod dataframe;
+pub mod derived;
 pub mod game_state;
 pub mod info;
+pub mod known_issues;
 pub mod misc;
+pub mod parks;
 pub mod parser;
 pub mod pitch_sequence;
 pub mod play;
+pub mod roster;
+pub mod rules;
 pub mod schemas;
+pub mod scorecard;
+pub mod summary;
+pub mod tables;
 pub mod traits;
+pub mod writer;
@@ -0,0 +1,2342 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use itertools::Itertools;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::event_file::game_state::{
+    EnteredGameAs, Event, EventBattedBallInfo, EventId, GameContext, GameFieldingAppearance,
+    GameLineupAppearance, GameSetting, PlateAppearanceResultType,
+};
+use crate::event_file::info::{DayNight, DoubleheaderStatus, Park, Team};
+use crate::event_file::misc::Hand;
+use crate::event_file::pitch_sequence::PitchSequence;
+use crate::event_file::play::{
+    Base, BaseRunner, BaserunningPlayType, BattedBallLocationGeneral, InningFrame, Trajectory,
+};
+use crate::event_file::roster::Rosters;
+use crate::event_file::rules::RulesEra;
+use crate::event_file::schemas::GameIdString;
+use crate::event_file::traits::{
+    FieldingPlayType, FieldingPosition, LineupPosition, Matchup, Pitcher, Player, Side,
+};
+
+/// Accumulates rows across every play-by-play game in the run. Unlike the per-game
+/// schemas, these can only be written once all files have been processed.
+pub trait Aggregate: Default + Send {
+    type Row: Serialize + Deserialize<'static>;
+
+    fn add_game(&mut self, gc: &GameContext);
+
+    fn rows(&self) -> Vec<Self::Row>;
+}
+
+/// Pairs each event with the score at the start of the event.
+pub fn events_with_score(gc: &GameContext) -> impl Iterator<Item = (&Event, Matchup<u8>)> {
+    gc.events.iter().scan(Matchup::new(0_u8, 0_u8), |score, e| {
+        let score_before = *score;
+        let runs = score.get_mut(e.context.batting_side);
+        *runs = runs.saturating_add(u8::try_from(e.results.runs.len()).unwrap_or_default());
+        Some((e, score_before))
+    })
+}
+
+/// Signed run differential from the batting team's perspective.
+fn batting_team_lead(score: Matchup<u8>, batting_side: Side) -> i16 {
+    i16::from(*score.get(batting_side)) - i16::from(*score.get(batting_side.flip()))
+}
+
+/// The player credited with the plate appearance, accounting for mid-PA substitutions.
+fn responsible_batter(e: &Event) -> Player {
+    match e.results.plate_appearance {
+        Some(PlateAppearanceResultType::StrikeOut) => e
+            .context
+            .rare_attributes
+            .strikeout_responsible_batter
+            .unwrap_or(e.context.batter_id),
+        _ => e.context.batter_id,
+    }
+}
+
+/// Standard counting stats for a set of plate appearances
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone, Deserialize)]
+pub struct BattingTotals {
+    plate_appearances: u32,
+    at_bats: u32,
+    hits: u32,
+    doubles: u32,
+    triples: u32,
+    home_runs: u32,
+    walks: u32,
+    intentional_walks: u32,
+    hit_by_pitches: u32,
+    strikeouts: u32,
+    sacrifice_hits: u32,
+    sacrifice_flies: u32,
+    runs_batted_in: u32,
+}
+
+impl BattingTotals {
+    const FIELD_COUNT: usize = 13;
+
+    fn add_event(&mut self, e: &Event, rules: RulesEra) {
+        let Some(pa) = e.results.plate_appearance else {
+            return;
+        };
+        self.plate_appearances += 1;
+        self.at_bats += u32::from(rules.is_at_bat(pa));
+        self.hits += u32::from(pa.is_hit());
+        match pa {
+            PlateAppearanceResultType::Double | PlateAppearanceResultType::GroundRuleDouble => {
+                self.doubles += 1;
+            }
+            PlateAppearanceResultType::Triple => self.triples += 1,
+            PlateAppearanceResultType::HomeRun
+            | PlateAppearanceResultType::InsideTheParkHomeRun => self.home_runs += 1,
+            PlateAppearanceResultType::Walk => self.walks += 1,
+            PlateAppearanceResultType::IntentionalWalk => {
+                self.walks += 1;
+                self.intentional_walks += 1;
+            }
+            PlateAppearanceResultType::HitByPitch => self.hit_by_pitches += 1,
+            PlateAppearanceResultType::StrikeOut => self.strikeouts += 1,
+            PlateAppearanceResultType::SacrificeHit => self.sacrifice_hits += 1,
+            PlateAppearanceResultType::SacrificeFly => self.sacrifice_flies += 1,
+            _ => {}
+        }
+        let rbi = e.results.runs.iter().filter(|r| r.rbi_flag).count();
+        self.runs_batted_in += u32::try_from(rbi).unwrap_or_default();
+    }
+
+    // The csv crate can't serialize nested structs with headers, so rows that
+    // embed the totals write the fields out inline.
+    fn serialize_fields<S: SerializeStruct>(&self, state: &mut S) -> Result<(), S::Error> {
+        state.serialize_field("plate_appearances", &self.plate_appearances)?;
+        state.serialize_field("at_bats", &self.at_bats)?;
+        state.serialize_field("hits", &self.hits)?;
+        state.serialize_field("doubles", &self.doubles)?;
+        state.serialize_field("triples", &self.triples)?;
+        state.serialize_field("home_runs", &self.home_runs)?;
+        state.serialize_field("walks", &self.walks)?;
+        state.serialize_field("intentional_walks", &self.intentional_walks)?;
+        state.serialize_field("hit_by_pitches", &self.hit_by_pitches)?;
+        state.serialize_field("strikeouts", &self.strikeouts)?;
+        state.serialize_field("sacrifice_hits", &self.sacrifice_hits)?;
+        state.serialize_field("sacrifice_flies", &self.sacrifice_flies)?;
+        state.serialize_field("runs_batted_in", &self.runs_batted_in)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum SituationalSplit {
+    RunnersInScoringPosition,
+    BasesEmpty,
+    /// Seventh inning or later with the batting team tied, ahead by one,
+    /// or with the tying run on base, at bat, or on deck.
+    LateAndClose,
+}
+
+impl SituationalSplit {
+    fn from_event(e: &Event, score: Matchup<u8>) -> Vec<Self> {
+        let mut splits = Vec::with_capacity(2);
+        let base_state = e.context.starting_base_state.get_base_state();
+        if base_state == 0 {
+            splits.push(Self::BasesEmpty);
+        } else if base_state & 0b110 > 0 {
+            splits.push(Self::RunnersInScoringPosition);
+        }
+        let lead = batting_team_lead(score, e.context.batting_side);
+        let runners_on = i16::from(u8::try_from(base_state.count_ones()).unwrap_or_default());
+        if e.context.inning >= 7 && lead <= 1 && lead >= -(runners_on + 2) {
+            splits.push(Self::LateAndClose);
+        }
+        splits
+    }
+}
+
+type SplitKey = (Player, u16, SituationalSplit);
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Deserialize)]
+pub struct PlayerSituationalSplits {
+    player_id: Player,
+    season: u16,
+    split: SituationalSplit,
+    totals: BattingTotals,
+}
+
+impl Serialize for PlayerSituationalSplits {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer
+            .serialize_struct("PlayerSituationalSplits", 3 + BattingTotals::FIELD_COUNT)?;
+        state.serialize_field("player_id", &self.player_id)?;
+        state.serialize_field("season", &self.season)?;
+        state.serialize_field("split", &self.split)?;
+        self.totals.serialize_fields(&mut state)?;
+        state.end()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SituationalSplits {
+    totals: HashMap<SplitKey, BattingTotals>,
+}
+
+impl Aggregate for SituationalSplits {
+    type Row = PlayerSituationalSplits;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        let season = gc.setting.season.0;
+        for (e, score) in events_with_score(gc) {
+            if e.results.plate_appearance.is_none() {
+                continue;
+            }
+            let batter = responsible_batter(e);
+            for split in SituationalSplit::from_event(e, score) {
+                self.totals
+                    .entry((batter, season, split))
+                    .or_default()
+                    .add_event(e, gc.rules);
+            }
+        }
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        let mut rows = self
+            .totals
+            .iter()
+            .map(
+                |(&(player_id, season, split), &totals)| PlayerSituationalSplits {
+                    player_id,
+                    season,
+                    split,
+                    totals,
+                },
+            )
+            .collect::<Vec<_>>();
+        rows.sort_by_key(|r| (r.player_id, r.season, r.split));
+        rows
+    }
+}
+
+/// Replays the pitches of a plate appearance, which can be spread across several events,
+/// keeping track of every count the batter reached along the way.
+#[derive(Debug, Clone)]
+struct CountReplay {
+    balls: u8,
+    strikes: u8,
+    pitches: usize,
+    reached: Vec<(u8, u8)>,
+    is_complete: bool,
+}
+
+impl Default for CountReplay {
+    fn default() -> Self {
+        Self {
+            balls: 0,
+            strikes: 0,
+            pitches: 0,
+            reached: vec![(0, 0)],
+            is_complete: true,
+        }
+    }
+}
+
+impl CountReplay {
+    fn add_pitches(&mut self, pitch_sequence: &PitchSequence) {
+        for pitch in pitch_sequence.iter().map(|p| p.pitch_type) {
+            if pitch.is_unknown() {
+                self.is_complete = false;
+            } else if pitch.is_ball() {
+                self.balls += 1;
+            } else if pitch.is_strike() || (pitch.is_foul() && self.strikes < 2) {
+                self.strikes += 1;
+            } else if !pitch.is_foul() {
+                // Balls in play, HBP, pickoffs, etc. don't change the count
+                continue;
+            }
+            self.pitches += 1;
+            if self.balls <= 3
+                && self.strikes <= 2
+                && !self.reached.contains(&(self.balls, self.strikes))
+            {
+                self.reached.push((self.balls, self.strikes));
+            }
+        }
+    }
+
+    /// Counts reached in the plate appearance, or None if the sequence is missing or incomplete.
+    fn counts_reached(&self) -> Option<&[(u8, u8)]> {
+        if self.is_complete && self.pitches > 0 {
+            Some(&self.reached)
+        } else {
+            None
+        }
+    }
+}
+
+type CountKey = (u16, u8, u8);
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Deserialize)]
+pub struct SeasonCountSplits {
+    season: u16,
+    balls: u8,
+    strikes: u8,
+    totals: BattingTotals,
+}
+
+impl Serialize for SeasonCountSplits {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state =
+            serializer.serialize_struct("SeasonCountSplits", 3 + BattingTotals::FIELD_COUNT)?;
+        state.serialize_field("season", &self.season)?;
+        state.serialize_field("balls", &self.balls)?;
+        state.serialize_field("strikes", &self.strikes)?;
+        self.totals.serialize_fields(&mut state)?;
+        state.end()
+    }
+}
+
+/// Plate appearance outcomes by every count reached during the PA (so a PA that went
+/// 0-1, 1-1, 1-2 counts towards each of those as well as 0-0). Only PAs with a complete
+/// pitch sequence are included.
+#[derive(Debug, Default)]
+pub struct CountSplits {
+    totals: HashMap<CountKey, BattingTotals>,
+}
+
+impl Aggregate for CountSplits {
+    type Row = SeasonCountSplits;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        let season = gc.setting.season.0;
+        let mut replay = CountReplay::default();
+        let mut half_inning = None;
+        for e in &gc.events {
+            // A PA interrupted by the third out resumes with a fresh count next inning
+            let current_half_inning = Some((e.context.inning, e.context.frame));
+            if half_inning != current_half_inning {
+                replay = CountReplay::default();
+                half_inning = current_half_inning;
+            }
+            replay.add_pitches(&e.results.pitch_sequence);
+            if e.results.plate_appearance.is_none() {
+                continue;
+            }
+            for &(balls, strikes) in replay.counts_reached().unwrap_or_default() {
+                self.totals
+                    .entry((season, balls, strikes))
+                    .or_default()
+                    .add_event(e, gc.rules);
+            }
+            replay = CountReplay::default();
+        }
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        let mut rows = self
+            .totals
+            .iter()
+            .map(|(&(season, balls, strikes), &totals)| SeasonCountSplits {
+                season,
+                balls,
+                strikes,
+                totals,
+            })
+            .collect::<Vec<_>>();
+        rows.sort_by_key(|r| (r.season, r.balls, r.strikes));
+        rows
+    }
+}
+
+/// The player occupying a base at the start of the event
+fn runner_id(gc: &GameContext, e: &Event, baserunner: BaseRunner) -> Option<Player> {
+    let runner = e.context.starting_base_state.get_runner(baserunner)?;
+    GameLineupAppearance::get_at_event(
+        &gc.lineup_appearances,
+        runner.lineup_position,
+        e.event_id,
+        e.context.batting_side,
+    )
+    .ok()
+    .map(|la| la.player_id)
+}
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum ExtraBaseOpportunity {
+    FirstToThirdOnSingle,
+    SecondToHomeOnSingle,
+    FirstToHomeOnDouble,
+    /// Runner on second or third with fewer than two outs on a fly ball caught by an outfielder
+    AdvanceOnFlyOut,
+}
+
+impl ExtraBaseOpportunity {
+    fn from_event(e: &Event, baserunner: BaseRunner) -> Option<Self> {
+        let bases = &e.context.starting_base_state;
+        // The lead runner has to be out of the way for the opportunity to count
+        let lead_runner_clears = |lead: BaseRunner| {
+            bases.get_runner(lead).is_none()
+                || e.results
+                    .baserunning_advances
+                    .iter()
+                    .any(|a| a.baserunner == lead && a.scored())
+        };
+        let is_outfield_fly = e.results.batted_ball_info.as_ref().is_some_and(|bb| {
+            matches!(bb.trajectory, Trajectory::Fly | Trajectory::LineDrive)
+                && matches!(
+                    bb.hit_to_fielder,
+                    Some(
+                        FieldingPosition::LeftFielder
+                            | FieldingPosition::CenterFielder
+                            | FieldingPosition::RightFielder
+                    )
+                )
+        });
+        match (e.results.plate_appearance?, baserunner) {
+            (PlateAppearanceResultType::Single, BaseRunner::First)
+                if lead_runner_clears(BaseRunner::Second) =>
+            {
+                Some(Self::FirstToThirdOnSingle)
+            }
+            (PlateAppearanceResultType::Single, BaseRunner::Second)
+                if lead_runner_clears(BaseRunner::Third) =>
+            {
+                Some(Self::SecondToHomeOnSingle)
+            }
+            (PlateAppearanceResultType::Double, BaseRunner::First) => {
+                Some(Self::FirstToHomeOnDouble)
+            }
+            (
+                PlateAppearanceResultType::InPlayOut | PlateAppearanceResultType::SacrificeFly,
+                BaseRunner::Second | BaseRunner::Third,
+            ) if is_outfield_fly && e.context.outs < 2 && e.results.out_on_play.len() == 1 => {
+                Some(Self::AdvanceOnFlyOut)
+            }
+            _ => None,
+        }
+    }
+
+    /// The base a runner has to reach for the advance to count as an extra base
+    const fn target_base(self) -> Base {
+        match self {
+            Self::FirstToThirdOnSingle => Base::Third,
+            Self::SecondToHomeOnSingle | Self::FirstToHomeOnDouble => Base::Home,
+            // Any advance at all is extra on a caught fly
+            Self::AdvanceOnFlyOut => Base::Second,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+struct ExtraBaseTotals {
+    opportunities: u32,
+    extra_bases_taken: u32,
+    outs_advancing: u32,
+}
+
+type ExtraBaseKey = (Player, u16, ExtraBaseOpportunity);
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct PlayerExtraBasesTaken {
+    player_id: Player,
+    season: u16,
+    opportunity_type: ExtraBaseOpportunity,
+    opportunities: u32,
+    extra_bases_taken: u32,
+    outs_advancing: u32,
+}
+
+/// Runner advancement beyond station-to-station on singles, doubles and outfield flies.
+/// Advances on errors are counted as opportunities but not as extra bases taken.
+#[derive(Debug, Default)]
+pub struct ExtraBasesTaken {
+    totals: HashMap<ExtraBaseKey, ExtraBaseTotals>,
+}
+
+impl Aggregate for ExtraBasesTaken {
+    type Row = PlayerExtraBasesTaken;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        let season = gc.setting.season.0;
+        let runners = [BaseRunner::First, BaseRunner::Second, BaseRunner::Third];
+        for e in &gc.events {
+            for baserunner in runners {
+                let Some(opportunity) = ExtraBaseOpportunity::from_event(e, baserunner) else {
+                    continue;
+                };
+                let Some(player_id) = runner_id(gc, e, baserunner) else {
+                    continue;
+                };
+                let advance = e
+                    .results
+                    .baserunning_advances
+                    .iter()
+                    .find(|a| a.baserunner == baserunner);
+                let baserunner_base = baserunner.to_current_base();
+                let totals = self
+                    .totals
+                    .entry((player_id, season, opportunity))
+                    .or_default();
+                totals.opportunities += 1;
+                match advance {
+                    Some(a) if !a.is_successful => totals.outs_advancing += 1,
+                    Some(a)
+                        if !a.advanced_on_error_flag
+                            && a.attempted_advance_to >= opportunity.target_base()
+                            && Some(a.attempted_advance_to) > baserunner_base =>
+                    {
+                        totals.extra_bases_taken += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        let mut rows = self
+            .totals
+            .iter()
+            .map(
+                |(&(player_id, season, opportunity_type), totals)| PlayerExtraBasesTaken {
+                    player_id,
+                    season,
+                    opportunity_type,
+                    opportunities: totals.opportunities,
+                    extra_bases_taken: totals.extra_bases_taken,
+                    outs_advancing: totals.outs_advancing,
+                },
+            )
+            .collect::<Vec<_>>();
+        rows.sort_by_key(|r| (r.player_id, r.season, r.opportunity_type));
+        rows
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+struct CatcherTotals {
+    stolen_bases_allowed: u32,
+    caught_stealing: u32,
+    picked_off_caught_stealing: u32,
+    pickoffs: u32,
+    catcher_pickoff_attempts: u32,
+    passed_balls: u32,
+    wild_pitches: u32,
+}
+
+impl CatcherTotals {
+    fn add_event(&mut self, e: &Event) {
+        for play in &e.results.plays_at_base {
+            let is_out = play
+                .baserunner
+                .is_some_and(|br| e.results.out_on_play.contains(&br));
+            let catcher_assist = play.baserunner.is_some_and(|br| {
+                e.results.out_fielders(br).iter().any(|fp| {
+                    fp.fielding_position == FieldingPosition::Catcher
+                        && fp.fielding_play_type == FieldingPlayType::Assist
+                })
+            });
+            match play.baserunning_play_type {
+                BaserunningPlayType::StolenBase => self.stolen_bases_allowed += 1,
+                // A CS negated by an error is neither a SB nor a CS
+                BaserunningPlayType::CaughtStealing if is_out => self.caught_stealing += 1,
+                BaserunningPlayType::PickedOffCaughtStealing if is_out => {
+                    self.picked_off_caught_stealing += 1;
+                }
+                BaserunningPlayType::PickedOff if is_out && catcher_assist => self.pickoffs += 1,
+                BaserunningPlayType::PassedBall => self.passed_balls += 1,
+                BaserunningPlayType::WildPitch => self.wild_pitches += 1,
+                _ => {}
+            }
+        }
+        let pickoff_attempts = e
+            .results
+            .pitch_sequence
+            .iter()
+            .filter(|p| p.catcher_pickoff_attempt.is_some())
+            .count();
+        self.catcher_pickoff_attempts += u32::try_from(pickoff_attempts).unwrap_or_default();
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct CatcherBaserunningControl {
+    player_id: Player,
+    season: u16,
+    stolen_bases_allowed: u32,
+    caught_stealing: u32,
+    picked_off_caught_stealing: u32,
+    pickoffs: u32,
+    catcher_pickoff_attempts: u32,
+    passed_balls: u32,
+    wild_pitches: u32,
+}
+
+/// Running game events attributed to whoever was catching at the time of the event,
+/// according to the fielding appearance intervals.
+#[derive(Debug, Default)]
+pub struct CatcherControl {
+    totals: HashMap<(Player, u16), CatcherTotals>,
+}
+
+impl Aggregate for CatcherControl {
+    type Row = CatcherBaserunningControl;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        let season = gc.setting.season.0;
+        for e in &gc.events {
+            let Ok(catcher) = GameFieldingAppearance::get_at_event(
+                &gc.fielding_appearances,
+                FieldingPosition::Catcher,
+                e.event_id,
+                e.context.batting_side.flip(),
+            ) else {
+                continue;
+            };
+            self.totals
+                .entry((catcher.player_id, season))
+                .or_default()
+                .add_event(e);
+        }
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        let mut rows = self
+            .totals
+            .iter()
+            .map(|(&(player_id, season), t)| CatcherBaserunningControl {
+                player_id,
+                season,
+                stolen_bases_allowed: t.stolen_bases_allowed,
+                caught_stealing: t.caught_stealing,
+                picked_off_caught_stealing: t.picked_off_caught_stealing,
+                pickoffs: t.pickoffs,
+                catcher_pickoff_attempts: t.catcher_pickoff_attempts,
+                passed_balls: t.passed_balls,
+                wild_pitches: t.wild_pitches,
+            })
+            .collect::<Vec<_>>();
+        rows.sort_by_key(|r| (r.player_id, r.season));
+        rows
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+struct PitcherGame {
+    game_id: GameIdString,
+    date: NaiveDate,
+    doubleheader_status: DoubleheaderStatus,
+    team_id: Team,
+    pitches: Option<u32>,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct PitcherUsage {
+    game_id: GameIdString,
+    date: NaiveDate,
+    player_id: Pitcher,
+    team_id: Team,
+    pitches: Option<u32>,
+    previous_game_id: Option<GameIdString>,
+    /// Zero for the second game of a doubleheader, one for consecutive days, etc.
+    days_since_previous_appearance: Option<i64>,
+    consecutive_days_appeared: u32,
+    previous_appearance_pitches: Option<u32>,
+}
+
+/// One row per pitcher per game, with rest and workload relative to the pitcher's
+/// previous appearances. Requires every game to have been added before computing rows.
+#[derive(Debug, Default)]
+pub struct PitcherUsagePatterns {
+    appearances: HashMap<Pitcher, Vec<PitcherGame>>,
+}
+
+impl Aggregate for PitcherUsagePatterns {
+    type Row = PitcherUsage;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        let game_pitchers = gc
+            .fielding_appearances
+            .iter()
+            .filter(|fa| fa.fielding_position == FieldingPosition::Pitcher)
+            .map(|fa| (fa.player_id, fa.side))
+            .unique();
+        for (pitcher, side) in game_pitchers {
+            let pitches = gc
+                .events
+                .iter()
+                .filter(|e| e.context.pitcher_id == pitcher)
+                .flat_map(|e| e.results.pitch_sequence.iter())
+                .filter(|p| p.pitch_type.is_pitch())
+                .count();
+            self.appearances
+                .entry(pitcher)
+                .or_default()
+                .push(PitcherGame {
+                    game_id: gc.game_id.id,
+                    date: gc.setting.date,
+                    doubleheader_status: gc.setting.doubleheader_status,
+                    team_id: *gc.teams.get(side),
+                    // No pitch sequence data doesn't mean no pitches
+                    pitches: u32::try_from(pitches).ok().filter(|p| *p > 0),
+                });
+        }
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        let mut rows = Vec::with_capacity(self.appearances.len() * 30);
+        for (&player_id, appearances) in self.appearances.iter().sorted_by_key(|(p, _)| **p) {
+            let appearances = appearances
+                .iter()
+                .sorted_by_key(|g| (g.date, g.doubleheader_status, g.game_id))
+                .collect_vec();
+            let mut consecutive_days_appeared = 0;
+            for (i, current) in appearances.iter().enumerate() {
+                let previous = i.checked_sub(1).and_then(|p| appearances.get(p));
+                let days_since = previous.map(|p| (current.date - p.date).num_days());
+                consecutive_days_appeared = match days_since {
+                    Some(0) => consecutive_days_appeared,
+                    Some(1) => consecutive_days_appeared + 1,
+                    _ => 1,
+                };
+                rows.push(PitcherUsage {
+                    game_id: current.game_id,
+                    date: current.date,
+                    player_id,
+                    team_id: current.team_id,
+                    pitches: current.pitches,
+                    previous_game_id: previous.map(|p| p.game_id),
+                    days_since_previous_appearance: days_since,
+                    consecutive_days_appeared,
+                    previous_appearance_pitches: previous.and_then(|p| p.pitches),
+                });
+            }
+        }
+        rows
+    }
+}
+
+/// The pitcher charged with the plate appearance, accounting for mid-PA substitutions.
+fn responsible_pitcher(e: &Event) -> Pitcher {
+    match e.results.plate_appearance {
+        Some(PlateAppearanceResultType::Walk | PlateAppearanceResultType::IntentionalWalk) => e
+            .context
+            .rare_attributes
+            .walk_responsible_pitcher
+            .unwrap_or(e.context.pitcher_id),
+        _ => e.context.pitcher_id,
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Deserialize)]
+pub struct BatterPitcherMatchups {
+    batter_id: Player,
+    pitcher_id: Pitcher,
+    totals: BattingTotals,
+}
+
+impl Serialize for BatterPitcherMatchups {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state =
+            serializer.serialize_struct("BatterPitcherMatchups", 2 + BattingTotals::FIELD_COUNT)?;
+        state.serialize_field("batter_id", &self.batter_id)?;
+        state.serialize_field("pitcher_id", &self.pitcher_id)?;
+        self.totals.serialize_fields(&mut state)?;
+        state.end()
+    }
+}
+
+/// Career totals for every batter-pitcher pair across all parsed seasons
+#[derive(Debug, Default)]
+pub struct Matchups {
+    totals: HashMap<(Player, Pitcher), BattingTotals>,
+}
+
+impl Aggregate for Matchups {
+    type Row = BatterPitcherMatchups;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        for e in gc
+            .events
+            .iter()
+            .filter(|e| e.results.plate_appearance.is_some())
+        {
+            self.totals
+                .entry((responsible_batter(e), responsible_pitcher(e)))
+                .or_default()
+                .add_event(e, gc.rules);
+        }
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        let mut rows = self
+            .totals
+            .iter()
+            .map(
+                |(&(batter_id, pitcher_id), &totals)| BatterPitcherMatchups {
+                    batter_id,
+                    pitcher_id,
+                    totals,
+                },
+            )
+            .collect::<Vec<_>>();
+        rows.sort_by_key(|r| (r.batter_id, r.pitcher_id));
+        rows
+    }
+}
+
+/// Score at the end of the game
+pub fn final_score(gc: &GameContext) -> Matchup<u8> {
+    events_with_score(gc)
+        .last()
+        .map_or_else(Matchup::default, |(e, mut score)| {
+            let runs = score.get_mut(e.context.batting_side);
+            *runs = runs.saturating_add(u8::try_from(e.results.runs.len()).unwrap_or_default());
+            score
+        })
+}
+
+const WIN_EXPECTANCY_ERA_LENGTH: u16 = 10;
+const WIN_EXPECTANCY_MAX_INNING: u8 = 10;
+const WIN_EXPECTANCY_MAX_RUN_DIFFERENTIAL: i16 = 10;
+
+type WinExpectancyKey = (u16, u8, InningFrame, usize, u8, i16);
+
+/// Runs ahead for the team batting in the bottom of each inning. This is the home team
+/// except in the games marked `htbf`, mostly from the 19th century, where it's the
+/// visitors. Win expectancy is keyed on batting order rather than on who's at home,
+/// since the states of those games would otherwise be mixed in with the wrong side's.
+fn bat_last_lead(gc: &GameContext, score: Matchup<u8>) -> i16 {
+    batting_team_lead(score, gc.setting.bat_first_side.flip())
+}
+
+/// The bucketed game state at the start of the event, given the score at that point
+fn win_expectancy_key(gc: &GameContext, e: &Event, score: Matchup<u8>) -> WinExpectancyKey {
+    let era = gc.setting.season.0 / WIN_EXPECTANCY_ERA_LENGTH * WIN_EXPECTANCY_ERA_LENGTH;
+    let differential = bat_last_lead(gc, score).clamp(
+        -WIN_EXPECTANCY_MAX_RUN_DIFFERENTIAL,
+        WIN_EXPECTANCY_MAX_RUN_DIFFERENTIAL,
+    );
+    (
+        era,
+        e.context.inning.min(WIN_EXPECTANCY_MAX_INNING),
+        e.context.frame,
+        e.context.outs.get(),
+        e.context.starting_base_state.get_base_state(),
+        differential,
+    )
+}
+
+/// Where a game went from one state, either to the next state or to the final result
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+enum StateTransition {
+    State(WinExpectancyKey),
+    GameOver { bat_last_win: bool },
+}
+
+/// Each state a game passed through, paired with the state that followed it or, for the
+/// last one, the final result. Games that ended tied have no result to credit, so they
+/// have no transitions.
+fn win_expectancy_transitions(gc: &GameContext) -> Vec<(WinExpectancyKey, StateTransition)> {
+    let final_lead = bat_last_lead(gc, final_score(gc));
+    if final_lead == 0 {
+        return vec![];
+    }
+    let keys = events_with_score(gc)
+        .map(|(e, score)| win_expectancy_key(gc, e, score))
+        .collect_vec();
+    let next_states = keys
+        .iter()
+        .skip(1)
+        .map(|k| StateTransition::State(*k))
+        .chain([StateTransition::GameOver {
+            bat_last_win: final_lead > 0,
+        }]);
+    keys.iter().copied().zip(next_states).collect()
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+struct WinExpectancyTotals {
+    occurrences: u32,
+    bat_last_wins: u32,
+}
+
+impl WinExpectancyTotals {
+    fn bat_last_win_probability(self) -> f64 {
+        f64::from(self.bat_last_wins) / f64::from(self.occurrences)
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct WinExpectancy {
+    era_start_season: u16,
+    era_end_season: u16,
+    /// Innings beyond the cap are grouped into the last inning
+    inning: u8,
+    frame: InningFrame,
+    outs: usize,
+    base_state: u8,
+    /// Runs of the team batting in the bottom of the inning minus those of the team
+    /// batting in the top, clamped to the max differential. The team batting last is
+    /// the home team except in `htbf` games.
+    bat_last_run_differential: i16,
+    occurrences: u32,
+    bat_last_wins: u32,
+    bat_last_win_probability: f64,
+}
+
+/// Empirical win probability of the team batting last by game state at the start of
+/// each event, bucketed into eras. Games that ended tied are excluded.
+#[derive(Debug, Default)]
+pub struct WinExpectancyTable {
+    totals: HashMap<WinExpectancyKey, WinExpectancyTotals>,
+}
+
+impl WinExpectancyTable {
+    fn add_transitions(&mut self, transitions: &[(WinExpectancyKey, StateTransition)]) {
+        let Some((_, StateTransition::GameOver { bat_last_win })) = transitions.last() else {
+            return;
+        };
+        for (key, _) in transitions {
+            let totals = self.totals.entry(*key).or_default();
+            totals.occurrences += 1;
+            totals.bat_last_wins += u32::from(*bat_last_win);
+        }
+    }
+
+    /// The chances of the team batting last winning from the given state, or from the
+    /// result if the game is over
+    fn bat_last_win_probability(&self, state: &StateTransition) -> Option<f64> {
+        match state {
+            StateTransition::State(key) => {
+                self.totals.get(key).map(|t| t.bat_last_win_probability())
+            }
+            StateTransition::GameOver { bat_last_win } => Some(f64::from(u8::from(*bat_last_win))),
+        }
+    }
+}
+
+impl Aggregate for WinExpectancyTable {
+    type Row = WinExpectancy;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        self.add_transitions(&win_expectancy_transitions(gc));
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.totals
+            .iter()
+            .sorted_by_key(|(k, _)| **k)
+            .map(
+                |(&(era, inning, frame, outs, base_state, bat_last_run_differential), t)| {
+                    WinExpectancy {
+                        era_start_season: era,
+                        era_end_season: era + WIN_EXPECTANCY_ERA_LENGTH - 1,
+                        inning,
+                        frame,
+                        outs,
+                        base_state,
+                        bat_last_run_differential,
+                        occurrences: t.occurrences,
+                        bat_last_wins: t.bat_last_wins,
+                        bat_last_win_probability: t.bat_last_win_probability(),
+                    }
+                },
+            )
+            .collect()
+    }
+}
+
+const MIN_STREAK_LENGTH: u32 = 5;
+
+/// Sorts games chronologically within a day's doubleheader
+type GameOrder = (NaiveDate, DoubleheaderStatus, GameIdString);
+
+const fn game_order(gc: &GameContext) -> GameOrder {
+    (
+        gc.setting.date,
+        gc.setting.doubleheader_status,
+        gc.game_id.id,
+    )
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+struct PlayerGameLine {
+    order: GameOrder,
+    team_id: Team,
+    plate_appearances: u8,
+    at_bats: u8,
+    sacrifice_flies: u8,
+    hits: u8,
+    times_on_base: u8,
+}
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum StreakType {
+    /// Games without an official at-bat or sac fly neither extend nor break the streak
+    Hitting,
+    /// Games without a plate appearance neither extend nor break the streak
+    OnBase,
+    /// Consecutive games of the player's team, so changing teams ends the streak
+    GamesPlayed,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct PlayerStreaks {
+    player_id: Player,
+    streak_type: StreakType,
+    length: u32,
+    start_game_id: GameIdString,
+    end_game_id: GameIdString,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+}
+
+impl PlayerStreaks {
+    fn new(player_id: Player, streak_type: StreakType, games: &[&PlayerGameLine]) -> Option<Self> {
+        let (first, last) = (games.first()?, games.last()?);
+        let length = u32::try_from(games.len()).ok()?;
+        (length >= MIN_STREAK_LENGTH).then_some(Self {
+            player_id,
+            streak_type,
+            length,
+            start_game_id: first.order.2,
+            end_game_id: last.order.2,
+            start_date: first.order.0,
+            end_date: last.order.0,
+        })
+    }
+}
+
+/// Streaks of consecutive games, with games in chronological order across all parsed seasons.
+#[derive(Debug, Default)]
+pub struct Streaks {
+    player_games: HashMap<Player, Vec<PlayerGameLine>>,
+    team_games: HashMap<Team, Vec<GameOrder>>,
+}
+
+impl Streaks {
+    /// Generic streak detection, where each game can extend the streak, break it,
+    /// or be skipped over (`None`)
+    fn find_streaks<F>(
+        player_id: Player,
+        streak_type: StreakType,
+        games: &[&PlayerGameLine],
+        extends_streak: F,
+    ) -> Vec<PlayerStreaks>
+    where
+        F: Fn(&PlayerGameLine) -> Option<bool>,
+    {
+        let mut streaks = vec![];
+        let mut current = vec![];
+        for &game in games {
+            match extends_streak(game) {
+                Some(true) => current.push(game),
+                Some(false) => {
+                    streaks.extend(PlayerStreaks::new(player_id, streak_type, &current));
+                    current.clear();
+                }
+                None => {}
+            }
+        }
+        streaks.extend(PlayerStreaks::new(player_id, streak_type, &current));
+        streaks
+    }
+
+    fn games_played_streaks(
+        player_id: Player,
+        games: &[&PlayerGameLine],
+        team_schedules: &HashMap<Team, HashMap<GameIdString, usize>>,
+    ) -> Vec<PlayerStreaks> {
+        let mut streaks = vec![];
+        let mut current: Vec<&PlayerGameLine> = vec![];
+        let schedule_index = |g: &PlayerGameLine| {
+            team_schedules
+                .get(&g.team_id)
+                .and_then(|s| s.get(&g.order.2))
+                .copied()
+        };
+        for &game in games {
+            let is_consecutive = current.last().is_some_and(|&prev| {
+                prev.team_id == game.team_id
+                    && schedule_index(prev).map(|i| i + 1) == schedule_index(game)
+            });
+            if !is_consecutive {
+                streaks.extend(PlayerStreaks::new(
+                    player_id,
+                    StreakType::GamesPlayed,
+                    &current,
+                ));
+                current.clear();
+            }
+            current.push(game);
+        }
+        streaks.extend(PlayerStreaks::new(
+            player_id,
+            StreakType::GamesPlayed,
+            &current,
+        ));
+        streaks
+    }
+}
+
+impl Aggregate for Streaks {
+    type Row = PlayerStreaks;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        let order = game_order(gc);
+        self.team_games
+            .entry(gc.teams.away)
+            .or_default()
+            .push(order);
+        self.team_games
+            .entry(gc.teams.home)
+            .or_default()
+            .push(order);
+
+        // A pitcher in a DH game never enters the lineup, but still played in the game
+        let appearances = gc
+            .lineup_appearances
+            .iter()
+            .map(|la| (la.player_id, la.side))
+            .chain(
+                gc.fielding_appearances
+                    .iter()
+                    .map(|fa| (fa.player_id, fa.side)),
+            );
+        let mut lines: HashMap<Player, PlayerGameLine> = HashMap::with_capacity(50);
+        for (player_id, side) in appearances {
+            lines.entry(player_id).or_insert_with(|| PlayerGameLine {
+                order,
+                team_id: *gc.teams.get(side),
+                plate_appearances: 0,
+                at_bats: 0,
+                sacrifice_flies: 0,
+                hits: 0,
+                times_on_base: 0,
+            });
+        }
+        for e in &gc.events {
+            let (Some(pa), Some(line)) = (
+                e.results.plate_appearance,
+                lines.get_mut(&responsible_batter(e)),
+            ) else {
+                continue;
+            };
+            line.plate_appearances = line.plate_appearances.saturating_add(1);
+            line.at_bats = line
+                .at_bats
+                .saturating_add(u8::from(gc.rules.is_at_bat(pa)));
+            line.hits = line.hits.saturating_add(u8::from(pa.is_hit()));
+            line.sacrifice_flies = line
+                .sacrifice_flies
+                .saturating_add(u8::from(pa == PlateAppearanceResultType::SacrificeFly));
+            let on_base = pa.is_hit()
+                || matches!(
+                    pa,
+                    PlateAppearanceResultType::Walk
+                        | PlateAppearanceResultType::IntentionalWalk
+                        | PlateAppearanceResultType::HitByPitch
+                );
+            line.times_on_base = line.times_on_base.saturating_add(u8::from(on_base));
+        }
+        for (player, line) in lines {
+            self.player_games.entry(player).or_default().push(line);
+        }
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        let team_schedules: HashMap<Team, HashMap<GameIdString, usize>> = self
+            .team_games
+            .iter()
+            .map(|(team, games)| {
+                let index = games
+                    .iter()
+                    .sorted()
+                    .enumerate()
+                    .map(|(i, g)| (g.2, i))
+                    .collect();
+                (*team, index)
+            })
+            .collect();
+        let mut rows = vec![];
+        for (&player_id, games) in self.player_games.iter().sorted_by_key(|(p, _)| **p) {
+            let games = games.iter().sorted_by_key(|g| g.order).collect_vec();
+            rows.extend(Self::find_streaks(
+                player_id,
+                StreakType::Hitting,
+                &games,
+                |g| (g.at_bats > 0 || g.sacrifice_flies > 0).then_some(g.hits > 0),
+            ));
+            rows.extend(Self::find_streaks(
+                player_id,
+                StreakType::OnBase,
+                &games,
+                |g| (g.plate_appearances > 0).then_some(g.times_on_base > 0),
+            ));
+            rows.extend(Self::games_played_streaks(
+                player_id,
+                &games,
+                &team_schedules,
+            ));
+        }
+        rows
+    }
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+struct WinLossRecord {
+    wins: u32,
+    losses: u32,
+    ties: u32,
+}
+
+impl WinLossRecord {
+    fn add(&mut self, runs_for: u8, runs_against: u8) {
+        match runs_for.cmp(&runs_against) {
+            Ordering::Greater => self.wins += 1,
+            Ordering::Less => self.losses += 1,
+            Ordering::Equal => self.ties += 1,
+        }
+    }
+
+    const fn games(self) -> u32 {
+        self.wins + self.losses + self.ties
+    }
+
+    /// Ties don't count as decisions
+    fn win_pct(self) -> Option<f64> {
+        let decisions = self.wins + self.losses;
+        (decisions > 0).then(|| f64::from(self.wins) / f64::from(decisions))
+    }
+}
+
+/// Exponent from Baseball-Reference's version of the formula, which fits better than 2
+const PYTHAGOREAN_EXPONENT: f64 = 1.83;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+struct TeamGameResult {
+    season: u16,
+    teams: Matchup<Team>,
+    score: Matchup<u8>,
+    extra_innings: bool,
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+struct TeamSeasonTotals {
+    record: WinLossRecord,
+    one_run_record: WinLossRecord,
+    extra_inning_record: WinLossRecord,
+    runs_scored: u32,
+    runs_allowed: u32,
+}
+
+impl TeamSeasonTotals {
+    fn add(&mut self, runs_for: u8, runs_against: u8, extra_innings: bool) {
+        self.record.add(runs_for, runs_against);
+        if runs_for.abs_diff(runs_against) == 1 {
+            self.one_run_record.add(runs_for, runs_against);
+        }
+        if extra_innings {
+            self.extra_inning_record.add(runs_for, runs_against);
+        }
+        self.runs_scored += u32::from(runs_for);
+        self.runs_allowed += u32::from(runs_against);
+    }
+
+    /// Wins expected from runs scored and allowed, out of the games that had a decision
+    fn pythagorean_expected_wins(self) -> Option<f64> {
+        let scored = f64::from(self.runs_scored).powf(PYTHAGOREAN_EXPONENT);
+        let allowed = f64::from(self.runs_allowed).powf(PYTHAGOREAN_EXPONENT);
+        let decisions = f64::from(self.record.wins + self.record.losses);
+        (scored + allowed > 0.0).then(|| decisions * scored / (scored + allowed))
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct TeamSeasonRecords {
+    season: u16,
+    team_id: Team,
+    games: u32,
+    wins: u32,
+    losses: u32,
+    ties: u32,
+    /// Strength of schedule: the season winning percentage of the opponent in each game,
+    /// averaged over all games played
+    opponent_win_pct: Option<f64>,
+    runs_scored: u32,
+    runs_allowed: u32,
+    run_differential: i64,
+    pythagorean_expected_wins: Option<f64>,
+    one_run_wins: u32,
+    one_run_losses: u32,
+    extra_inning_wins: u32,
+    extra_inning_losses: u32,
+}
+
+/// Team-season records built from the final score of each game
+#[derive(Debug, Default)]
+pub struct TeamSeasons {
+    games: Vec<TeamGameResult>,
+}
+
+impl TeamSeasons {
+    fn totals(&self) -> HashMap<(u16, Team), TeamSeasonTotals> {
+        let mut totals: HashMap<(u16, Team), TeamSeasonTotals> = HashMap::new();
+        for game in &self.games {
+            for side in [Side::Away, Side::Home] {
+                totals
+                    .entry((game.season, *game.teams.get(side)))
+                    .or_default()
+                    .add(
+                        *game.score.get(side),
+                        *game.score.get(side.flip()),
+                        game.extra_innings,
+                    );
+            }
+        }
+        totals
+    }
+}
+
+impl Aggregate for TeamSeasons {
+    type Row = TeamSeasonRecords;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        self.games.push(TeamGameResult {
+            season: gc.setting.season.0,
+            teams: gc.teams,
+            score: final_score(gc),
+            extra_innings: gc
+                .events
+                .last()
+                .is_some_and(|e| e.context.inning > gc.rules.scheduled_innings),
+        });
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        let totals = self.totals();
+        // Sum and count of opponent winning percentages
+        let mut opponent_pcts: HashMap<(u16, Team), (f64, u32)> = HashMap::new();
+        for game in &self.games {
+            for side in [Side::Away, Side::Home] {
+                let opponent = (game.season, *game.teams.get(side.flip()));
+                if let Some(pct) = totals.get(&opponent).and_then(|t| t.record.win_pct()) {
+                    let (sum, count) = opponent_pcts
+                        .entry((game.season, *game.teams.get(side)))
+                        .or_default();
+                    *sum += pct;
+                    *count += 1;
+                }
+            }
+        }
+        totals
+            .iter()
+            .sorted_by_key(|(k, _)| **k)
+            .map(|(&(season, team_id), t)| {
+                let opponent_win_pct = opponent_pcts
+                    .get(&(season, team_id))
+                    .map(|(sum, count)| sum / f64::from(*count));
+                TeamSeasonRecords {
+                    season,
+                    team_id,
+                    games: t.record.games(),
+                    wins: t.record.wins,
+                    losses: t.record.losses,
+                    ties: t.record.ties,
+                    opponent_win_pct,
+                    runs_scored: t.runs_scored,
+                    runs_allowed: t.runs_allowed,
+                    run_differential: i64::from(t.runs_scored) - i64::from(t.runs_allowed),
+                    pythagorean_expected_wins: t.pythagorean_expected_wins(),
+                    one_run_wins: t.one_run_record.wins,
+                    one_run_losses: t.one_run_record.losses,
+                    extra_inning_wins: t.extra_inning_record.wins,
+                    extra_inning_losses: t.extra_inning_record.losses,
+                }
+            })
+            .collect()
+    }
+}
+
+const ELO_INITIAL_RATING: f64 = 1500.0;
+const ELO_K_FACTOR: f64 = 4.0;
+const ELO_HOME_FIELD_ADVANTAGE: f64 = 24.0;
+/// Share of the distance to the initial rating that a franchise regresses between seasons
+const ELO_SEASON_REVERSION: f64 = 1.0 / 3.0;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+struct EloGame {
+    order: GameOrder,
+    season: u16,
+    teams: Matchup<Team>,
+    score: Matchup<u8>,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct TeamElo {
+    game_id: GameIdString,
+    date: NaiveDate,
+    side: Side,
+    team_id: Team,
+    franchise_id: Team,
+    opponent_franchise_id: Team,
+    pre_game_rating: f64,
+    post_game_rating: f64,
+    win_probability: f64,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct FranchiseRating {
+    rating: f64,
+    season: u16,
+}
+
+/// Game-by-game Elo ratings for each franchise, replaying every parsed game in
+/// chronological order. Home teams get a fixed bonus and ties count as half a win.
+#[derive(Debug, Default)]
+pub struct EloRatings {
+    games: Vec<EloGame>,
+}
+
+impl EloRatings {
+    fn pre_game_rating(
+        ratings: &HashMap<Team, FranchiseRating>,
+        franchise: Team,
+        season: u16,
+    ) -> f64 {
+        ratings.get(&franchise).map_or(ELO_INITIAL_RATING, |r| {
+            if r.season == season {
+                r.rating
+            } else {
+                (r.rating - ELO_INITIAL_RATING).mul_add(-ELO_SEASON_REVERSION, r.rating)
+            }
+        })
+    }
+}
+
+impl Aggregate for EloRatings {
+    type Row = TeamElo;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        self.games.push(EloGame {
+            order: game_order(gc),
+            season: gc.setting.season.0,
+            teams: gc.teams,
+            score: final_score(gc),
+        });
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        let mut ratings: HashMap<Team, FranchiseRating> = HashMap::new();
+        let mut rows = Vec::with_capacity(self.games.len() * 2);
+        for game in self.games.iter().sorted_by_key(|g| g.order) {
+            let franchises = Matchup::new(
+                game.teams.away.franchise(),
+                game.teams.home.franchise(),
+            );
+            let pre_game = Matchup::new(
+                Self::pre_game_rating(&ratings, franchises.away, game.season),
+                Self::pre_game_rating(&ratings, franchises.home, game.season),
+            );
+            let home_win_probability = 1.0
+                / (1.0
+                    + 10_f64.powf(
+                        (pre_game.away - pre_game.home - ELO_HOME_FIELD_ADVANTAGE) / 400.0,
+                    ));
+            let home_result = match game.score.home.cmp(&game.score.away) {
+                Ordering::Greater => 1.0,
+                Ordering::Less => 0.0,
+                Ordering::Equal => 0.5,
+            };
+            let home_change = ELO_K_FACTOR * (home_result - home_win_probability);
+            for side in [Side::Away, Side::Home] {
+                let (change, win_probability) = match side {
+                    Side::Away => (-home_change, 1.0 - home_win_probability),
+                    Side::Home => (home_change, home_win_probability),
+                };
+                let post_game_rating = pre_game.get(side) + change;
+                ratings.insert(
+                    *franchises.get(side),
+                    FranchiseRating {
+                        rating: post_game_rating,
+                        season: game.season,
+                    },
+                );
+                rows.push(TeamElo {
+                    game_id: game.order.2,
+                    date: game.order.0,
+                    side,
+                    team_id: *game.teams.get(side),
+                    franchise_id: *franchises.get(side),
+                    opponent_franchise_id: *franchises.get(side.flip()),
+                    pre_game_rating: *pre_game.get(side),
+                    post_game_rating,
+                    win_probability,
+                });
+            }
+        }
+        rows
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum SubstitutionType {
+    PinchHitter,
+    PinchRunner,
+    DefensiveSubstitution,
+    /// A player already in the game moving to another fielding position
+    PositionChange,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct SubstitutionContext {
+    game_id: GameIdString,
+    /// The first event with the new player in place
+    event_id: EventId,
+    side: Side,
+    player_id: Player,
+    replaced_player_id: Option<Player>,
+    substitution_type: SubstitutionType,
+    lineup_position: Option<LineupPosition>,
+    fielding_position: Option<FieldingPosition>,
+    inning: u8,
+    frame: InningFrame,
+    outs: usize,
+    base_state: u8,
+    /// Runs ahead from the perspective of the substituting team
+    run_differential: i16,
+    /// The substituting team's chances of winning, from the win expectancy of the state
+    win_probability: Option<f64>,
+    /// Average swing in win expectancy on the next play from this state, relative to
+    /// the average across all states
+    leverage_index: Option<f64>,
+}
+
+/// Every substitution and position change, joined to the game state at the time.
+/// Win probability and leverage come from the empirical win expectancy of the
+/// games in the run, so they're only as reliable as the run is large.
+#[derive(Debug, Default)]
+pub struct SubstitutionContexts {
+    win_expectancy: WinExpectancyTable,
+    transitions: HashMap<(WinExpectancyKey, StateTransition), u32>,
+    /// Along with the state, whether the substituting team batted in the bottom
+    substitutions: Vec<(SubstitutionContext, WinExpectancyKey, bool)>,
+}
+
+impl SubstitutionContexts {
+    fn add_win_expectancy(&mut self, gc: &GameContext) {
+        let transitions = win_expectancy_transitions(gc);
+        self.win_expectancy.add_transitions(&transitions);
+        for transition in transitions {
+            *self.transitions.entry(transition).or_default() += 1;
+        }
+    }
+
+    fn add_substitutions(&mut self, gc: &GameContext) {
+        let scores: HashMap<EventId, (&Event, Matchup<u8>)> = events_with_score(gc)
+            .map(|(e, score)| (e.event_id, (e, score)))
+            .collect();
+        for sub in Substitution::from_game(gc) {
+            let Some((e, score)) = scores.get(&sub.event_id) else {
+                continue;
+            };
+            let row = SubstitutionContext {
+                game_id: gc.game_id.id,
+                event_id: sub.event_id,
+                side: sub.side,
+                player_id: sub.player_id,
+                replaced_player_id: sub.replaced_player_id,
+                substitution_type: sub.kind,
+                lineup_position: sub.lineup_position,
+                fielding_position: sub.fielding_position,
+                inning: e.context.inning,
+                frame: e.context.frame,
+                outs: e.context.outs.get(),
+                base_state: e.context.starting_base_state.get_base_state(),
+                run_differential: batting_team_lead(*score, sub.side),
+                win_probability: None,
+                leverage_index: None,
+            };
+            let bats_last = sub.side != gc.setting.bat_first_side;
+            self.substitutions
+                .push((row, win_expectancy_key(gc, e, *score), bats_last));
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+struct Substitution {
+    player_id: Player,
+    side: Side,
+    event_id: EventId,
+    kind: SubstitutionType,
+    lineup_position: Option<LineupPosition>,
+    fielding_position: Option<FieldingPosition>,
+    replaced_player_id: Option<Player>,
+}
+
+impl Substitution {
+    /// The last player to start in the spot before the event, other than the substitute
+    fn previous_occupant<T: Eq>(
+        appearances: impl Iterator<Item = (T, Player, Side, EventId)>,
+        sub: &Self,
+        spot: &T,
+    ) -> Option<Player> {
+        appearances
+            .filter(|(s, _, side, start)| s == spot && *side == sub.side && *start < sub.event_id)
+            .max_by_key(|(_, _, _, start)| *start)
+            .map(|(_, player, _, _)| player)
+            .filter(|player| *player != sub.player_id)
+    }
+
+    fn from_game(gc: &GameContext) -> Vec<Self> {
+        let lineup_spots = || {
+            gc.lineup_appearances
+                .iter()
+                .map(|la| (la.lineup_position, la.player_id, la.side, la.start_event_id))
+        };
+        let fielding_spots = || {
+            gc.fielding_appearances.iter().map(|fa| {
+                (
+                    fa.fielding_position,
+                    fa.player_id,
+                    fa.side,
+                    fa.start_event_id,
+                )
+            })
+        };
+        let mut subs = vec![];
+        // New players come in through the lineup, since pinch hitters and runners
+        // don't always get a fielding position
+        for la in &gc.lineup_appearances {
+            let kind = match la.entered_game_as {
+                EnteredGameAs::Starter => continue,
+                EnteredGameAs::PinchHitter => SubstitutionType::PinchHitter,
+                EnteredGameAs::PinchRunner => SubstitutionType::PinchRunner,
+                EnteredGameAs::DefensiveSubstitution => SubstitutionType::DefensiveSubstitution,
+            };
+            let mut sub = Self {
+                player_id: la.player_id,
+                side: la.side,
+                event_id: la.start_event_id,
+                kind,
+                lineup_position: Some(la.lineup_position),
+                fielding_position: gc
+                    .fielding_appearances
+                    .iter()
+                    .find(|fa| {
+                        fa.player_id == la.player_id && fa.start_event_id == la.start_event_id
+                    })
+                    .map(|fa| fa.fielding_position),
+                replaced_player_id: None,
+            };
+            sub.replaced_player_id =
+                Self::previous_occupant(lineup_spots(), &sub, &la.lineup_position);
+            subs.push(sub);
+        }
+        // Players who were already in the game taking a new position
+        for fa in &gc.fielding_appearances {
+            let already_in_game =
+                gc.lineup_appearances.iter().any(|la| {
+                    la.player_id == fa.player_id && la.start_event_id < fa.start_event_id
+                }) || gc.fielding_appearances.iter().any(|other| {
+                    other.player_id == fa.player_id && other.start_event_id < fa.start_event_id
+                });
+            let entered_now = subs
+                .iter()
+                .any(|s| (s.player_id, s.event_id) == (fa.player_id, fa.start_event_id));
+            if !already_in_game || entered_now {
+                continue;
+            }
+            let lineup_position = gc
+                .lineup_appearances
+                .iter()
+                .filter(|la| la.player_id == fa.player_id && la.start_event_id <= fa.start_event_id)
+                .max_by_key(|la| la.start_event_id)
+                .map(|la| la.lineup_position);
+            let mut sub = Self {
+                player_id: fa.player_id,
+                side: fa.side,
+                event_id: fa.start_event_id,
+                kind: SubstitutionType::PositionChange,
+                lineup_position,
+                fielding_position: Some(fa.fielding_position),
+                replaced_player_id: None,
+            };
+            sub.replaced_player_id =
+                Self::previous_occupant(fielding_spots(), &sub, &fa.fielding_position);
+            subs.push(sub);
+        }
+        subs
+    }
+}
+
+impl Aggregate for SubstitutionContexts {
+    type Row = SubstitutionContext;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        self.add_win_expectancy(gc);
+        self.add_substitutions(gc);
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        let bat_last_win_probability = |key: &WinExpectancyKey| {
+            self.win_expectancy
+                .bat_last_win_probability(&StateTransition::State(*key))
+        };
+        // Total swing and number of transitions out of each state
+        let mut swings: HashMap<WinExpectancyKey, (f64, u32)> = HashMap::new();
+        for ((key, next), count) in &self.transitions {
+            let (Some(before), Some(after)) = (
+                bat_last_win_probability(key),
+                self.win_expectancy.bat_last_win_probability(next),
+            ) else {
+                continue;
+            };
+            let (total, n) = swings.entry(*key).or_default();
+            *total += (after - before).abs() * f64::from(*count);
+            *n += count;
+        }
+        let (all_total, all_n) = swings
+            .values()
+            .fold((0.0, 0), |(t, n), (total, count)| (t + total, n + count));
+        let average_swing = (all_n > 0).then(|| all_total / f64::from(all_n));
+        self.substitutions
+            .iter()
+            .map(|(row, key, bats_last)| {
+                let win_probability =
+                    bat_last_win_probability(key).map(|p| if *bats_last { p } else { 1.0 - p });
+                let leverage_index = swings
+                    .get(key)
+                    .zip(average_swing)
+                    .filter(|(_, average)| *average > 0.0)
+                    .map(|((total, n), average)| total / f64::from(*n) / average);
+                SubstitutionContext {
+                    win_probability,
+                    leverage_index,
+                    ..*row
+                }
+            })
+            .sorted_by_key(|r| (r.game_id, r.event_id, r.side))
+            .collect()
+    }
+}
+
+/// Parks that hosted at least this many of a team's home games in a season count as
+/// home parks, alongside the one that hosted the most. This covers teams that moved
+/// mid-season without flagging one-off games elsewhere.
+const HOME_PARK_MIN_GAMES: u32 = 10;
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum ParkUsageStatus {
+    /// The game has no valid `site` info record
+    MissingPark,
+    /// The park is a home park of the visiting team, e.g. a game moved after a rainout
+    AwayTeamPark,
+    /// The park is a home park of a team that wasn't playing
+    OtherTeamPark,
+    /// The park isn't a home park of any team that season
+    NeutralSite,
+}
+
+/// Games that weren't played at one of the home team's home parks for the season.
+/// Home parks are inferred from where each team played its home games.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct ParkUsageFindings {
+    game_id: GameIdString,
+    date: NaiveDate,
+    season: u16,
+    home_team_id: Team,
+    away_team_id: Team,
+    park_id: Option<Park>,
+    home_team_park_id: Option<Park>,
+    status: ParkUsageStatus,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct ParkGame {
+    order: GameOrder,
+    season: u16,
+    teams: Matchup<Team>,
+    park_id: Option<Park>,
+}
+
+#[derive(Debug, Default)]
+pub struct ParkUsage {
+    games: Vec<ParkGame>,
+}
+
+impl ParkUsage {
+    /// Each team-season's home parks, most used first
+    fn home_parks(&self) -> HashMap<(u16, Team), Vec<Park>> {
+        let mut counts: HashMap<(u16, Team), HashMap<Park, u32>> = HashMap::new();
+        for game in &self.games {
+            if let Some(park_id) = game.park_id {
+                *counts
+                    .entry((game.season, game.teams.home))
+                    .or_default()
+                    .entry(park_id)
+                    .or_default() += 1;
+            }
+        }
+        counts
+            .into_iter()
+            .map(|(key, parks)| {
+                let home_parks = parks
+                    .into_iter()
+                    .sorted_by_key(|&(park, count)| (Reverse(count), park))
+                    .enumerate()
+                    .filter(|&(i, (_, count))| i == 0 || count >= HOME_PARK_MIN_GAMES)
+                    .map(|(_, (park, _))| park)
+                    .collect();
+                (key, home_parks)
+            })
+            .collect()
+    }
+}
+
+impl Aggregate for ParkUsage {
+    type Row = ParkUsageFindings;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        self.games.push(ParkGame {
+            order: game_order(gc),
+            season: gc.setting.season.0,
+            teams: gc.teams,
+            park_id: Some(gc.setting.park_id).filter(|p| *p != Park::default()),
+        });
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        let home_parks = self.home_parks();
+        let parks_of = |season: u16, team: Team| {
+            home_parks
+                .get(&(season, team))
+                .map_or(&[][..], Vec::as_slice)
+        };
+        self.games
+            .iter()
+            .sorted_by_key(|g| g.order)
+            .filter_map(|game| {
+                let home_team_parks = parks_of(game.season, game.teams.home);
+                let status = match game.park_id {
+                    None => ParkUsageStatus::MissingPark,
+                    Some(park) if home_team_parks.contains(&park) => return None,
+                    Some(park) if parks_of(game.season, game.teams.away).contains(&park) => {
+                        ParkUsageStatus::AwayTeamPark
+                    }
+                    Some(park)
+                        if home_parks.iter().any(|(&(season, _), parks)| {
+                            season == game.season && parks.contains(&park)
+                        }) =>
+                    {
+                        ParkUsageStatus::OtherTeamPark
+                    }
+                    Some(_) => ParkUsageStatus::NeutralSite,
+                };
+                Some(ParkUsageFindings {
+                    game_id: game.order.2,
+                    date: game.order.0,
+                    season: game.season,
+                    home_team_id: game.teams.home,
+                    away_team_id: game.teams.away,
+                    park_id: game.park_id,
+                    home_team_park_id: home_team_parks.first().copied(),
+                    status,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct Doubleheaders {
+    date: NaiveDate,
+    home_team_id: Team,
+    away_team_id: Team,
+    park_id: Option<Park>,
+    same_park_flag: bool,
+    game_1_id: Option<GameIdString>,
+    game_2_id: Option<GameIdString>,
+    game_3_id: Option<GameIdString>,
+    game_4_id: Option<GameIdString>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct DoubleheaderGame {
+    game_id: GameIdString,
+    status: DoubleheaderStatus,
+    away_team_id: Team,
+    park_id: Park,
+}
+
+/// Games played by the same teams on the same day, keyed by date and home team. A game
+/// is left empty when the file containing it wasn't part of the run.
+#[derive(Debug, Default)]
+pub struct DoubleheaderPairing {
+    games: HashMap<(NaiveDate, Team), Vec<DoubleheaderGame>>,
+}
+
+impl Aggregate for DoubleheaderPairing {
+    type Row = Doubleheaders;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        if gc.setting.doubleheader_status == DoubleheaderStatus::SingleGame {
+            return;
+        }
+        self.games
+            .entry((gc.setting.date, gc.teams.home))
+            .or_default()
+            .push(DoubleheaderGame {
+                game_id: gc.game_id.id,
+                status: gc.setting.doubleheader_status,
+                away_team_id: gc.teams.away,
+                park_id: gc.setting.park_id,
+            });
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.games
+            .iter()
+            .sorted_by_key(|(k, _)| **k)
+            .map(|(&(date, home_team_id), games)| {
+                let games = games.iter().sorted_by_key(|g| g.status).collect_vec();
+                let game_id = |status| {
+                    games
+                        .iter()
+                        .find(|g| g.status == status)
+                        .map(|g| g.game_id)
+                };
+                let park_id = Some(games[0].park_id).filter(|p| *p != Park::default());
+                Doubleheaders {
+                    date,
+                    home_team_id,
+                    away_team_id: games[0].away_team_id,
+                    park_id,
+                    same_park_flag: games.iter().all(|g| g.park_id == games[0].park_id),
+                    game_1_id: game_id(DoubleheaderStatus::DoubleHeaderGame1),
+                    game_2_id: game_id(DoubleheaderStatus::DoubleHeaderGame2),
+                    game_3_id: game_id(DoubleheaderStatus::DoubleHeaderGame3),
+                    game_4_id: game_id(DoubleheaderStatus::DoubleHeaderGame4),
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+struct AttendanceTotals {
+    games: u32,
+    games_with_attendance: u32,
+    attendance: u64,
+    games_with_temperature: u32,
+    temperature_fahrenheit: u64,
+}
+
+impl AttendanceTotals {
+    fn add(&mut self, attendance: Option<u32>, temperature_fahrenheit: Option<u8>) {
+        self.games += 1;
+        if let Some(attendance) = attendance {
+            self.games_with_attendance += 1;
+            self.attendance += u64::from(attendance);
+        }
+        if let Some(temperature) = temperature_fahrenheit {
+            self.games_with_temperature += 1;
+            self.temperature_fahrenheit += u64::from(temperature);
+        }
+    }
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+struct ParkSeasonTotals {
+    all: AttendanceTotals,
+    day: AttendanceTotals,
+    night: AttendanceTotals,
+}
+
+impl ParkSeasonTotals {
+    fn add(&mut self, setting: &GameSetting) {
+        self.all
+            .add(setting.attendance, setting.temperature_fahrenheit);
+        match setting.time_of_day {
+            DayNight::Day => self.day.add(setting.attendance, None),
+            DayNight::Night => self.night.add(setting.attendance, None),
+            DayNight::Unknown => {}
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct ParkSeasonAttendance {
+    season: u16,
+    /// Empty on the row for the whole season across every park
+    park_id: Option<Park>,
+    games: u32,
+    /// Attendance totals and averages only count games with a recorded attendance
+    games_with_attendance: u32,
+    total_attendance: u64,
+    average_attendance: Option<f64>,
+    games_with_temperature: u32,
+    average_temperature_fahrenheit: Option<f64>,
+    day_games: u32,
+    day_games_with_attendance: u32,
+    day_attendance: u64,
+    day_average_attendance: Option<f64>,
+    night_games: u32,
+    night_games_with_attendance: u32,
+    night_attendance: u64,
+    night_average_attendance: Option<f64>,
+}
+
+/// Attendance and temperature at each park over a season, with day and night games
+/// split out, followed by the same totals for the season as a whole. Games without a
+/// valid `site` only count toward the season's row.
+#[derive(Debug, Default)]
+pub struct AttendanceSummary {
+    totals: HashMap<(u16, Option<Park>), ParkSeasonTotals>,
+}
+
+impl Aggregate for AttendanceSummary {
+    type Row = ParkSeasonAttendance;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        let setting = &gc.setting;
+        let season = setting.season.0;
+        self.totals.entry((season, None)).or_default().add(setting);
+        if setting.park_id != Park::default() {
+            self.totals
+                .entry((season, Some(setting.park_id)))
+                .or_default()
+                .add(setting);
+        }
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.totals
+            .iter()
+            .sorted_by_key(|((season, park_id), _)| (*season, park_id.is_none(), *park_id))
+            .map(|(&(season, park_id), totals)| {
+                let ParkSeasonTotals { all, day, night } = *totals;
+                let average = |total: u64, games: u32| {
+                    #[allow(clippy::cast_precision_loss)]
+                    (games > 0).then(|| total as f64 / f64::from(games))
+                };
+                ParkSeasonAttendance {
+                    season,
+                    park_id,
+                    games: all.games,
+                    games_with_attendance: all.games_with_attendance,
+                    total_attendance: all.attendance,
+                    average_attendance: average(all.attendance, all.games_with_attendance),
+                    games_with_temperature: all.games_with_temperature,
+                    average_temperature_fahrenheit: average(
+                        all.temperature_fahrenheit,
+                        all.games_with_temperature,
+                    ),
+                    day_games: day.games,
+                    day_games_with_attendance: day.games_with_attendance,
+                    day_attendance: day.attendance,
+                    day_average_attendance: average(day.attendance, day.games_with_attendance),
+                    night_games: night.games,
+                    night_games_with_attendance: night.games_with_attendance,
+                    night_attendance: night.attendance,
+                    night_average_attendance: average(
+                        night.attendance,
+                        night.games_with_attendance,
+                    ),
+                }
+            })
+            .collect()
+    }
+}
+
+/// The third of the field a ball was hit to, as seen from home plate
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum FieldThird {
+    Left,
+    Center,
+    Right,
+}
+
+impl FieldThird {
+    /// Goes by the hit location when there is one, and by the fielder who handled the
+    /// ball otherwise. Balls hit up the middle count as center.
+    fn from_batted_ball(info: &EventBattedBallInfo) -> Option<Self> {
+        use BattedBallLocationGeneral as L;
+        match info.general_location {
+            L::PitcherThird
+            | L::CatcherThird
+            | L::Third
+            | L::ThirdShortstop
+            | L::Shortstop
+            | L::Left
+            | L::LeftCenter => Some(Self::Left),
+            L::Pitcher | L::Catcher | L::SecondShortstop | L::Center => Some(Self::Center),
+            L::PitcherFirst
+            | L::CatcherFirst
+            | L::First
+            | L::FirstSecond
+            | L::Second
+            | L::RightCenter
+            | L::Right => Some(Self::Right),
+            L::Unknown => match info.hit_to_fielder? {
+                FieldingPosition::ThirdBaseman
+                | FieldingPosition::Shortstop
+                | FieldingPosition::LeftFielder => Some(Self::Left),
+                FieldingPosition::Pitcher
+                | FieldingPosition::Catcher
+                | FieldingPosition::CenterFielder => Some(Self::Center),
+                FieldingPosition::FirstBaseman
+                | FieldingPosition::SecondBaseman
+                | FieldingPosition::RightFielder => Some(Self::Right),
+                _ => None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+struct SprayTotals {
+    pull: u32,
+    center: u32,
+    opposite: u32,
+}
+
+impl SprayTotals {
+    const fn batted_balls(self) -> u32 {
+        self.pull + self.center + self.opposite
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct BatterSprayTendencies {
+    season: u16,
+    batter_id: Player,
+    batter_hand: Hand,
+    /// Batted balls with a known direction, not counting bunts
+    batted_balls: u32,
+    pull: u32,
+    center: u32,
+    opposite: u32,
+    pull_rate: f64,
+    center_rate: f64,
+    opposite_rate: f64,
+}
+
+/// Where each batter hits the ball, relative to the side of the plate they bat from.
+/// Batted balls are only counted when the batter's hand is known, either from the play
+/// or from the rosters, so switch hitters get a separate row for each side.
+#[derive(Debug, Default)]
+pub struct SprayTendencies {
+    rosters: Option<&'static Rosters>,
+    totals: HashMap<(u16, Player, Hand), SprayTotals>,
+}
+
+impl SprayTendencies {
+    pub fn with_rosters(rosters: &'static Rosters) -> Self {
+        Self {
+            rosters: Some(rosters),
+            ..Self::default()
+        }
+    }
+
+    fn batter_hand(&self, gc: &GameContext, e: &Event) -> Option<Hand> {
+        let specified = |hand: Option<Hand>| hand.filter(|h| *h != Hand::Default);
+        let attributes = &e.context.rare_attributes;
+        let season = gc.setting.season.0;
+        let batting_team = *gc.teams.get(e.context.batting_side);
+        let fielding_team = *gc.teams.get(e.context.batting_side.flip());
+        specified(attributes.batter_hand).or_else(|| {
+            let rosters = self.rosters?;
+            let pitcher_hand = specified(attributes.pitcher_hand)
+                .or_else(|| rosters.pitcher_hand(fielding_team, e.context.pitcher_id, season));
+            rosters.batter_hand(batting_team, e.context.batter_id, season, pitcher_hand)
+        })
+    }
+}
+
+impl Aggregate for SprayTendencies {
+    type Row = BatterSprayTendencies;
+
+    fn add_game(&mut self, gc: &GameContext) {
+        let season = gc.setting.season.0;
+        for e in &gc.events {
+            let Some(info) = &e.results.batted_ball_info else {
+                continue;
+            };
+            let is_bunt = matches!(
+                info.trajectory,
+                Trajectory::UnspecifiedBunt
+                    | Trajectory::PopUpBunt
+                    | Trajectory::GroundBallBunt
+                    | Trajectory::FoulBunt
+                    | Trajectory::LineDriveBunt
+            );
+            let (false, Some(third)) = (is_bunt, FieldThird::from_batted_ball(info)) else {
+                continue;
+            };
+            let Some(hand) = self.batter_hand(gc, e) else {
+                continue;
+            };
+            let totals = self
+                .totals
+                .entry((season, e.context.batter_id, hand))
+                .or_default();
+            match (third, hand) {
+                (FieldThird::Center, _) => totals.center += 1,
+                (FieldThird::Left, Hand::Right) | (FieldThird::Right, Hand::Left) => {
+                    totals.pull += 1;
+                }
+                _ => totals.opposite += 1,
+            }
+        }
+    }
+
+    fn rows(&self) -> Vec<Self::Row> {
+        self.totals
+            .iter()
+            .sorted_by_key(|(k, _)| **k)
+            .map(|(&(season, batter_id, batter_hand), totals)| {
+                let batted_balls = totals.batted_balls();
+                let rate = |n: u32| f64::from(n) / f64::from(batted_balls);
+                BatterSprayTendencies {
+                    season,
+                    batter_id,
+                    batter_hand,
+                    batted_balls,
+                    pull: totals.pull,
+                    center: totals.center,
+                    opposite: totals.opposite,
+                    pull_rate: rate(totals.pull),
+                    center_rate: rate(totals.center),
+                    opposite_rate: rate(totals.opposite),
+                }
+            })
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_file::builder::GameBuilder;
+    use crate::event_file::traits::GameType;
+
+    /// Both sides use a DH batting ninth, so neither starting pitcher is in the batting order
+    fn dh_game(plays: &[(&str, &str)]) -> GameContext {
+        let mut game = GameBuilder::new("BOS202004010")
+            .info("visteam", "NYA")
+            .info("hometeam", "BOS")
+            .info("date", "2020/04/01");
+        for (side, team) in [(Side::Away, "nya"), (Side::Home, "bos")] {
+            for slot in 1..=9 {
+                let fielding_position = if slot == 9 { 10 } else { slot + 1 };
+                game = game.start(&format!("{team}{slot:05}"), side, slot, fielding_position);
+            }
+            game = game.start(&format!("{team}00010"), side, 0, 1);
+        }
+        for (batter_id, play) in plays {
+            game = game.play(1, Side::Away, batter_id, "00", "X", play);
+        }
+        game.build().unwrap()
+    }
+
+    fn player(player_id: &str) -> Player {
+        Player::from_raw(player_id).unwrap()
+    }
+
+    #[test]
+    fn streaks_include_pitchers_outside_the_batting_order() {
+        let gc = dh_game(&[("nya00001", "S7/L")]);
+        let mut streaks = Streaks::default();
+        streaks.add_game(&gc);
+        for pitcher in ["nya00010", "bos00010"] {
+            let lines = &streaks.player_games[&player(pitcher)];
+            assert_eq!(lines.len(), 1);
+            assert_eq!(lines[0].plate_appearances, 0);
+        }
+        assert_eq!(streaks.player_games[&player("nya00001")][0].hits, 1);
+    }
+
+    #[test]
+    fn catcher_pickoffs_need_the_catcher_on_that_runner() {
+        let gc = dh_game(&[
+            ("nya00001", "S7/L"),
+            ("nya00002", "S8/G.1-2"),
+            // The pitcher picks off the runner at second, and the catcher throws out
+            // the other runner
+            ("nya00003", "PO2(14).1X2(24)"),
+            ("nya00003", "S7/L"),
+            ("nya00004", "PO1(23)"),
+        ]);
+        let mut control = CatcherControl::default();
+        control.add_game(&gc);
+        assert_eq!(control.totals[&(player("bos00001"), 2020)].pickoffs, 1);
+    }
+
+    #[test]
+    fn sacrifice_flies_are_at_bats_in_seasons_without_the_exemption() {
+        let gc = dh_game(&[
+            ("nya00001", "S7/L"),
+            ("nya00002", "S8/G.1-3"),
+            ("nya00003", "8/SF.3-H"),
+        ]);
+        let sacrifice_fly = gc.events.last().unwrap();
+        for (season, at_bats) in [(1940, 1), (2020, 0)] {
+            let rules = RulesEra::new(
+                season,
+                GameType::RegularSeason,
+                DoubleheaderStatus::SingleGame,
+            );
+            let mut totals = BattingTotals::default();
+            totals.add_event(sacrifice_fly, rules);
+            assert_eq!(totals.plate_appearances, 1);
+            assert_eq!(totals.sacrifice_flies, 1);
+            assert_eq!(totals.at_bats, at_bats);
+        }
+    }
+
+    #[test]
+    fn attendance_has_a_season_row_and_day_night_averages() {
+        let mut summary = AttendanceSummary::default();
+        let park = Park::from_raw("BOS07").unwrap();
+        for (park_id, time_of_day, attendance) in [
+            (park, DayNight::Day, Some(30_000)),
+            (park, DayNight::Night, Some(20_000)),
+            (park, DayNight::Night, None),
+            (Park::default(), DayNight::Night, Some(10_000)),
+        ] {
+            let mut gc = dh_game(&[("nya00001", "S7/L")]);
+            gc.setting.park_id = park_id;
+            gc.setting.time_of_day = time_of_day;
+            gc.setting.attendance = attendance;
+            summary.add_game(&gc);
+        }
+        let rows = summary.rows();
+        assert_eq!(rows.len(), 2);
+        let (park_row, season_row) = (&rows[0], &rows[1]);
+        assert_eq!(park_row.park_id, Some(park));
+        assert_eq!(park_row.games, 3);
+        assert_eq!(park_row.night_games, 2);
+        assert_eq!(park_row.night_games_with_attendance, 1);
+        assert_eq!(park_row.night_average_attendance, Some(20_000.0));
+        assert_eq!(park_row.day_average_attendance, Some(30_000.0));
+        assert_eq!(season_row.park_id, None);
+        assert_eq!(season_row.games, 4);
+        assert_eq!(season_row.night_average_attendance, Some(15_000.0));
+        assert_eq!(season_row.average_attendance, Some(20_000.0));
+    }
+}
@@ -3,12 +3,13 @@ use std::str::FromStr;
 
 use anyhow::{bail, Context, Error, Result};
 use arrayref::array_ref;
-use arrayvec::ArrayString;
+use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize};
 
 use crate::event_file::misc::{parse_positive_int, str_to_tinystr, Defense, Lineup};
 use crate::event_file::traits::{
-    Batter, Fielder, FieldingPosition, Inning, LineupPosition, Pitcher, RetrosheetEventRecord, Side,
+    Batter, Fielder, FieldingPosition, Inning, LineupPosition, Person, Pitcher,
+    RetrosheetEventRecord, Side,
 };
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
@@ -32,6 +33,59 @@ pub struct BattingLineStats {
     pub reached_on_interference: Option<u8>,
 }
 
+impl BattingLineStats {
+    pub const FIELD_COUNT: usize = 17;
+
+    /// Writes the stats as top-level fields of a row struct, since CSV headers
+    /// can't be generated for nested structs
+    pub fn serialize_fields<S: SerializeStruct>(
+        &self,
+        state: &mut S,
+    ) -> std::result::Result<(), S::Error> {
+        state.serialize_field("at_bats", &self.at_bats)?;
+        state.serialize_field("runs", &self.runs)?;
+        state.serialize_field("hits", &self.hits)?;
+        state.serialize_field("doubles", &self.doubles)?;
+        state.serialize_field("triples", &self.triples)?;
+        state.serialize_field("home_runs", &self.home_runs)?;
+        state.serialize_field("rbi", &self.rbi)?;
+        state.serialize_field("sacrifice_hits", &self.sacrifice_hits)?;
+        state.serialize_field("sacrifice_flies", &self.sacrifice_flies)?;
+        state.serialize_field("hit_by_pitch", &self.hit_by_pitch)?;
+        state.serialize_field("walks", &self.walks)?;
+        state.serialize_field("intentional_walks", &self.intentional_walks)?;
+        state.serialize_field("strikeouts", &self.strikeouts)?;
+        state.serialize_field("stolen_bases", &self.stolen_bases)?;
+        state.serialize_field("caught_stealing", &self.caught_stealing)?;
+        state.serialize_field("grounded_into_double_plays", &self.grounded_into_double_plays)?;
+        state.serialize_field("reached_on_interference", &self.reached_on_interference)?;
+        Ok(())
+    }
+
+    /// Every stat by column name. All of them add up from player lines to team lines.
+    pub const fn summable_stats(&self) -> [(&'static str, Option<u8>); 17] {
+        [
+            ("at_bats", Some(self.at_bats)),
+            ("runs", Some(self.runs)),
+            ("hits", Some(self.hits)),
+            ("doubles", self.doubles),
+            ("triples", self.triples),
+            ("home_runs", self.home_runs),
+            ("rbi", self.rbi),
+            ("sacrifice_hits", self.sacrifice_hits),
+            ("sacrifice_flies", self.sacrifice_flies),
+            ("hit_by_pitch", self.hit_by_pitch),
+            ("walks", self.walks),
+            ("intentional_walks", self.intentional_walks),
+            ("strikeouts", self.strikeouts),
+            ("stolen_bases", self.stolen_bases),
+            ("caught_stealing", self.caught_stealing),
+            ("grounded_into_double_plays", self.grounded_into_double_plays),
+            ("reached_on_interference", self.reached_on_interference),
+        ]
+    }
+}
+
 impl From<BattingLineStats> for Vec<u8> {
     fn from(stats: BattingLineStats) -> Self {
         vec![
@@ -130,7 +184,7 @@ impl TryFrom<&RetrosheetEventRecord> for BattingLine {
         let arr = record.deserialize::<[&str; 23]>(None)?;
         let p = parse_positive_int::<u8>;
         Ok(Self {
-            batter_id: str_to_tinystr(arr[2])?,
+            batter_id: Person::from_raw(arr[2])?,
             side: Side::from_str(arr[3])?,
             lineup_position: LineupPosition::try_from(arr[4])?,
             nth_player_at_position: p(arr[5]).context("Invalid batting sequence position")?,
@@ -159,8 +213,8 @@ impl From<BattingLine> for RetrosheetEventRecord {
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub struct PinchHittingLine {
     pub pinch_hitter_id: Batter,
-    inning: Option<Inning>,
-    side: Side,
+    pub inning: Option<Inning>,
+    pub side: Side,
     pub batting_stats: BattingLineStats,
 }
 
@@ -199,7 +253,7 @@ impl TryFrom<&RetrosheetEventRecord> for PinchHittingLine {
         let arr = record.deserialize::<[&str; 22]>(None)?;
         let p = parse_positive_int::<u8>;
         Ok(Self {
-            pinch_hitter_id: str_to_tinystr(arr[2])?,
+            pinch_hitter_id: Person::from_raw(arr[2])?,
             inning: p(arr[3]),
             side: Side::from_str(arr[4])?,
             batting_stats: BattingLineStats::try_from(array_ref![arr, 5, 17])
@@ -212,8 +266,8 @@ impl TryFrom<&RetrosheetEventRecord> for PinchHittingLine {
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub struct PinchRunningLine {
     pub pinch_runner_id: Batter,
-    inning: Option<Inning>,
-    side: Side,
+    pub inning: Option<Inning>,
+    pub side: Side,
     pub runs: Option<u8>,
     pub stolen_bases: Option<u8>,
     pub caught_stealing: Option<u8>,
@@ -257,7 +311,7 @@ impl TryFrom<&RetrosheetEventRecord> for PinchRunningLine {
         let arr = record.deserialize::<[&str; 8]>(None)?;
         let p = { |i: usize| arr[i].parse::<u8>().ok() };
         Ok(Self {
-            pinch_runner_id: str_to_tinystr(arr[2])?,
+            pinch_runner_id: Person::from_raw(arr[2])?,
             inning: p(3),
             side: Side::from_str(arr[4])?,
             runs: p(5),
@@ -278,6 +332,35 @@ pub struct DefenseLineStats {
     pub passed_balls: Option<u8>,
 }
 
+impl DefenseLineStats {
+    pub const FIELD_COUNT: usize = 7;
+
+    pub fn serialize_fields<S: SerializeStruct>(
+        &self,
+        state: &mut S,
+    ) -> std::result::Result<(), S::Error> {
+        state.serialize_field("outs_played", &self.outs_played)?;
+        state.serialize_field("putouts", &self.putouts)?;
+        state.serialize_field("assists", &self.assists)?;
+        state.serialize_field("errors", &self.errors)?;
+        state.serialize_field("double_plays", &self.double_plays)?;
+        state.serialize_field("triple_plays", &self.triple_plays)?;
+        state.serialize_field("passed_balls", &self.passed_balls)?;
+        Ok(())
+    }
+
+    /// The stats that add up from player lines to team lines, by column name. Outs
+    /// played and double and triple plays are credited to every fielder involved.
+    pub const fn summable_stats(&self) -> [(&'static str, Option<u8>); 4] {
+        [
+            ("putouts", self.putouts),
+            ("assists", self.assists),
+            ("errors", self.errors),
+            ("passed_balls", self.passed_balls),
+        ]
+    }
+}
+
 impl From<DefenseLineStats> for Vec<u8> {
     fn from(stats: DefenseLineStats) -> Self {
         vec![
@@ -351,7 +434,7 @@ impl TryFrom<&RetrosheetEventRecord> for DefenseLine {
         let arr = record.deserialize::<[&str; 13]>(None)?;
         let p = parse_positive_int::<u8>;
         Ok(Self {
-            fielder_id: str_to_tinystr(arr[2])?,
+            fielder_id: Person::from_raw(arr[2])?,
             side: Side::from_str(arr[3])?,
             nth_position_played_by_player: p(arr[4])
                 .context("Invalid fielding sequence position")?,
@@ -401,6 +484,34 @@ pub struct PitchingLineStats {
     pub sacrifice_flies: Option<u8>,
 }
 
+impl PitchingLineStats {
+    pub const FIELD_COUNT: usize = 17;
+
+    pub fn serialize_fields<S: SerializeStruct>(
+        &self,
+        state: &mut S,
+    ) -> std::result::Result<(), S::Error> {
+        state.serialize_field("outs_recorded", &self.outs_recorded)?;
+        state.serialize_field("no_out_batters", &self.no_out_batters)?;
+        state.serialize_field("batters_faced", &self.batters_faced)?;
+        state.serialize_field("hits", &self.hits)?;
+        state.serialize_field("doubles", &self.doubles)?;
+        state.serialize_field("triples", &self.triples)?;
+        state.serialize_field("home_runs", &self.home_runs)?;
+        state.serialize_field("runs", &self.runs)?;
+        state.serialize_field("earned_runs", &self.earned_runs)?;
+        state.serialize_field("walks", &self.walks)?;
+        state.serialize_field("intentional_walks", &self.intentional_walks)?;
+        state.serialize_field("strikeouts", &self.strikeouts)?;
+        state.serialize_field("hit_batsmen", &self.hit_batsmen)?;
+        state.serialize_field("wild_pitches", &self.wild_pitches)?;
+        state.serialize_field("balks", &self.balks)?;
+        state.serialize_field("sacrifice_hits", &self.sacrifice_hits)?;
+        state.serialize_field("sacrifice_flies", &self.sacrifice_flies)?;
+        Ok(())
+    }
+}
+
 impl From<PitchingLineStats> for Vec<u8> {
     fn from(stats: PitchingLineStats) -> Self {
         vec![
@@ -463,7 +574,7 @@ impl TryFrom<&[&str; 17]> for PitchingLineStats {
 pub struct PitchingLine {
     pub pitcher_id: Pitcher,
     pub side: Side,
-    nth_pitcher: u8,
+    pub nth_pitcher: u8,
     pub pitching_stats: PitchingLineStats,
 }
 
@@ -492,7 +603,7 @@ impl TryFrom<&RetrosheetEventRecord> for PitchingLine {
         let arr = record.deserialize::<[&str; 22]>(None)?;
         let p = parse_positive_int::<u8>;
         Ok(Self {
-            pitcher_id: str_to_tinystr(arr[2])?,
+            pitcher_id: Person::from_raw(arr[2])?,
             side: Side::from_str(arr[3])?,
             nth_pitcher: p(arr[4]).context("Invalid fielding sequence position")?,
             pitching_stats: PitchingLineStats::try_from(array_ref![arr, 5, 17])?,
@@ -559,8 +670,8 @@ impl From<TeamMiscellaneousLine> for RetrosheetEventRecord {
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub struct TeamBattingLine {
-    side: Side,
-    batting_stats: BattingLineStats,
+    pub side: Side,
+    pub batting_stats: BattingLineStats,
 }
 
 impl TryFrom<&RetrosheetEventRecord> for TeamBattingLine {
@@ -673,7 +784,7 @@ impl TryFrom<&RetrosheetEventRecord> for LineScore {
 pub struct FieldingPlayLine {
     pub defense_side: Side,
     // Dashed sequence of numeric positions
-    fielders: String,
+    pub fielders: String,
 }
 
 pub type DoublePlayLine = FieldingPlayLine;
@@ -693,9 +804,9 @@ impl TryFrom<&RetrosheetEventRecord> for FieldingPlayLine {
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub struct HitByPitchLine {
-    pitching_side: Side,
-    pitcher_id: Option<Pitcher>,
-    batter_id: Batter,
+    pub pitching_side: Side,
+    pub pitcher_id: Option<Pitcher>,
+    pub batter_id: Batter,
 }
 
 impl HitByPitchLine {
@@ -716,19 +827,19 @@ impl TryFrom<&RetrosheetEventRecord> for HitByPitchLine {
         Ok(Self {
             pitching_side: Side::from_str(arr[2])?,
             pitcher_id: str_to_tinystr(arr[3]).ok(),
-            batter_id: str_to_tinystr(arr[4])?,
+            batter_id: Person::from_raw(arr[4])?,
         })
     }
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub struct HomeRunLine {
-    batting_side: Side,
-    batter_id: Batter,
-    pitcher_id: Pitcher,
-    inning: Option<Inning>,
-    runners_on: Option<u8>,
-    outs: Option<u8>,
+    pub batting_side: Side,
+    pub batter_id: Batter,
+    pub pitcher_id: Pitcher,
+    pub inning: Option<Inning>,
+    pub runners_on: Option<u8>,
+    pub outs: Option<u8>,
 }
 
 impl HomeRunLine {
@@ -759,8 +870,8 @@ impl TryFrom<&RetrosheetEventRecord> for HomeRunLine {
         let p = { |i: usize| arr[i].parse::<u8>().ok() };
         Ok(Self {
             batting_side: Side::from_str(arr[2])?,
-            batter_id: str_to_tinystr(arr[3])?,
-            pitcher_id: str_to_tinystr(arr[4])?,
+            batter_id: Person::from_raw(arr[3])?,
+            pitcher_id: Person::from_raw(arr[4])?,
             inning: p(5),
             runners_on: p(6),
             outs: p(7),
@@ -770,11 +881,11 @@ impl TryFrom<&RetrosheetEventRecord> for HomeRunLine {
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize)]
 pub struct StolenBaseAttemptLine {
-    running_side: Side,
-    runner_id: Batter,
-    pitcher_id: Option<Pitcher>,
-    catcher_id: Option<Fielder>,
-    inning: Option<Inning>,
+    pub running_side: Side,
+    pub runner_id: Batter,
+    pub pitcher_id: Option<Pitcher>,
+    pub catcher_id: Option<Fielder>,
+    pub inning: Option<Inning>,
 }
 
 impl StolenBaseAttemptLine {
@@ -805,7 +916,7 @@ impl TryFrom<&RetrosheetEventRecord> for StolenBaseAttemptLine {
         let arr = record.deserialize::<[&str; 7]>(None)?;
         Ok(Self {
             running_side: Side::from_str(arr[2])?,
-            runner_id: str_to_tinystr(arr[3])?,
+            runner_id: Person::from_raw(arr[3])?,
             pitcher_id: str_to_tinystr(arr[4]).ok(),
             catcher_id: str_to_tinystr(arr[5]).ok(),
             inning: arr[6].parse::<u8>().ok(),
@@ -827,7 +938,7 @@ pub enum BoxScoreEvent {
 
 impl From<BoxScoreEvent> for RetrosheetEventRecord {
     fn from(event: BoxScoreEvent) -> Self {
-        let opt_str = |o: Option<ArrayString<8>>| o.map(|s| s.to_string()).unwrap_or_default();
+        let opt_str = |o: Option<Person>| o.map(|s| s.to_string()).unwrap_or_default();
         let mut record = Self::with_capacity(64, 8);
         record.push_field("event");
         match event {
@@ -0,0 +1,252 @@
+use std::hash::Hasher;
+
+use anyhow::{bail, Context, Result};
+use arrayvec::ArrayString;
+use csv::StringRecord;
+
+use crate::event_file::game_state::GameContext;
+use crate::event_file::parser::{AccountType, FileInfo, MappedRecord, StableHasher};
+use crate::event_file::traits::{FieldingPosition, Side};
+
+/// Builds a `GameContext` from records written the way they would appear in an event
+/// file. Synthetic games go through the same parsing and state tracking as real ones,
+/// so a game that breaks an invariant (a batter out of order, a fourth out, a runner
+/// who isn't on base) fails to build with the same error a real file would give.
+///
+/// ```
+/// use baseball_computer::prelude::*;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut game = GameBuilder::new("BOS202004010")
+///     .info("visteam", "NYA")
+///     .info("hometeam", "BOS")
+///     .info("date", "2020/04/01");
+/// for (side, team) in [(Side::Away, "nya"), (Side::Home, "bos")] {
+///     for slot in 1..=9 {
+///         let player_id = format!("{team}{slot:05}");
+///         game = game.start(&player_id, side, slot, slot);
+///     }
+/// }
+/// let gc = game
+///     .play(1, Side::Away, "nya00001", "12", "BCX", "S7/L")
+///     .play(1, Side::Away, "nya00002", "00", "X", "64(1)3/GDP")
+///     .play(1, Side::Away, "nya00003", "32", "BBCBFB", "W")
+///     .play(1, Side::Away, "nya00004", "02", "CSS", "K")
+///     .build()?;
+/// assert_eq!(gc.events.len(), 4);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameBuilder {
+    game_id: String,
+    account_type: AccountType,
+    records: Vec<StringRecord>,
+}
+
+impl GameBuilder {
+    pub fn new(game_id: &str) -> Self {
+        Self {
+            game_id: game_id.to_string(),
+            account_type: AccountType::PlayByPlay,
+            records: vec![StringRecord::from(vec!["id", game_id])],
+        }
+    }
+
+    /// Treats the game as coming from a deduced or box score file instead of a
+    /// play-by-play one
+    #[must_use]
+    pub const fn account_type(mut self, account_type: AccountType) -> Self {
+        self.account_type = account_type;
+        self
+    }
+
+    /// Adds a record of any type, e.g. `["com", "Rain delay"]`
+    #[must_use]
+    pub fn record(mut self, fields: &[&str]) -> Self {
+        self.records.push(StringRecord::from(fields.to_vec()));
+        self
+    }
+
+    #[must_use]
+    pub fn info(self, key: &str, value: &str) -> Self {
+        self.record(&["info", key, value])
+    }
+
+    /// Adds a starter. The name is left blank, as it isn't used for anything.
+    #[must_use]
+    pub fn start(
+        self,
+        player_id: &str,
+        side: Side,
+        lineup_position: u8,
+        fielding_position: u8,
+    ) -> Self {
+        self.player_record("start", player_id, side, lineup_position, fielding_position)
+    }
+
+    /// Adds a substitution, which takes effect before the next play
+    #[must_use]
+    pub fn sub(
+        self,
+        player_id: &str,
+        side: Side,
+        lineup_position: u8,
+        fielding_position: u8,
+    ) -> Self {
+        self.player_record("sub", player_id, side, lineup_position, fielding_position)
+    }
+
+    fn player_record(
+        self,
+        record_type: &str,
+        player_id: &str,
+        side: Side,
+        lineup_position: u8,
+        fielding_position: u8,
+    ) -> Self {
+        self.record(&[
+            record_type,
+            player_id,
+            "",
+            side.as_ref(),
+            &lineup_position.to_string(),
+            &fielding_position.to_string(),
+        ])
+    }
+
+    /// Adds a play, with the count, pitches and play written in Retrosheet notation
+    #[must_use]
+    pub fn play(
+        self,
+        inning: u8,
+        side: Side,
+        batter_id: &str,
+        count: &str,
+        pitches: &str,
+        play: &str,
+    ) -> Self {
+        self.record(&[
+            "play",
+            &inning.to_string(),
+            side.as_ref(),
+            batter_id,
+            count,
+            pitches,
+            play,
+        ])
+    }
+
+    /// Parses the records into a game, checking that each side starts a full lineup
+    /// before handing them to `GameContext::new` for everything else
+    pub fn build(&self) -> Result<GameContext> {
+        let mut hasher = StableHasher::default();
+        let record_vec = self
+            .records
+            .iter()
+            .enumerate()
+            .map(|(i, record)| {
+                hasher.write_record(record);
+                MappedRecord::try_from(record).with_context(|| format!("Invalid record {i}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if self.account_type != AccountType::BoxScore {
+            for side in [Side::Away, Side::Home] {
+                let starters = record_vec
+                    .iter()
+                    .filter_map(|r| match r {
+                        MappedRecord::Start(s) if s.side == side => Some(s),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                let has_slot =
+                    |slot: u8| starters.iter().any(|s| u8::from(s.lineup_position) == slot);
+                if !(1..=9).all(has_slot) {
+                    bail!("The {side:?} side doesn't start a player in every lineup slot");
+                }
+                if !starters
+                    .iter()
+                    .any(|s| s.fielding_position == FieldingPosition::Pitcher)
+                {
+                    bail!("The {side:?} side doesn't start a pitcher");
+                }
+            }
+        }
+        let file_info = FileInfo {
+            filename: ArrayString::from("synthetic").unwrap_or_default(),
+            account_type: self.account_type,
+            file_index: 0,
+            game_type: None,
+            season: None,
+            file_hash: hasher.finish(),
+        };
+        GameContext::new(&record_vec, file_info, 0, 0, hasher.finish())
+            .with_context(|| format!("Invalid synthetic game {}", self.game_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game() -> GameBuilder {
+        GameBuilder::new("BOS202004010")
+            .info("visteam", "NYA")
+            .info("hometeam", "BOS")
+            .info("date", "2020/04/01")
+    }
+
+    fn with_lineups(mut game: GameBuilder, home_slots: u8) -> GameBuilder {
+        for slot in 1..=9 {
+            game = game.start(&format!("nya{slot:05}"), Side::Away, slot, slot);
+        }
+        for slot in 1..=home_slots {
+            game = game.start(&format!("bos{slot:05}"), Side::Home, slot, slot);
+        }
+        game
+    }
+
+    #[test]
+    fn missing_lineup_slot_is_an_error() {
+        let err = with_lineups(game(), 8).build().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The Home side doesn't start a player in every lineup slot"
+        );
+    }
+
+    #[test]
+    fn missing_starting_pitcher_is_an_error() {
+        let mut game = game();
+        for slot in 1..=9 {
+            game = game.start(&format!("nya{slot:05}"), Side::Away, slot, slot);
+        }
+        // Designated hitter in the ninth slot with nobody on the mound
+        for slot in 1..=9 {
+            game = game.start(&format!("bos{slot:05}"), Side::Home, slot, slot + 1);
+        }
+        let err = game.build().unwrap_err();
+        assert_eq!(err.to_string(), "The Home side doesn't start a pitcher");
+    }
+
+    #[test]
+    fn box_scores_skip_the_lineup_checks() {
+        let gc = game()
+            .account_type(AccountType::BoxScore)
+            .record(&["line", "0", "0", "0", "1"])
+            .record(&["line", "1", "0", "0", "0"])
+            .build()
+            .unwrap();
+        assert!(gc.events.is_empty());
+    }
+
+    #[test]
+    fn invalid_plays_fail_like_a_real_file() {
+        let err = with_lineups(game(), 9)
+            .play(1, Side::Away, "nya00001", "00", "X", "S8.2-H")
+            .build()
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Invalid synthetic game BOS202004010");
+        assert!(err.chain().count() > 1);
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::io::{self, Cursor, Write};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, bail, Result};
+use arrow_schema::Schema;
+use polars::prelude::{DataFrame, IpcReader, SerReader};
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+use crate::event_file::game_state::GameContext;
+use crate::event_file::parser::AccountType;
+use crate::event_file::schemas::{
+    ContextToVec, EventAudit, EventBaserunners, EventChargeReassignments, EventComments,
+    EventFieldingAlignments, EventFieldingPlays, EventOutfieldRunnerPlays, EventPitchSequences,
+    Events, GameEarnedRuns, Games, InheritedRunners, PinchAppearances, PitchingAppearances,
+};
+use crate::event_file::tables::EventFileSchema;
+use crate::event_file::writer::{ArrowIpcWriter, SchemaWriter};
+
+impl EventFileSchema {
+    /// Whether the table can be built from a single play-by-play or deduced game. Options
+    /// that the binary applies from outside the game, like roster names or team leagues,
+    /// are left out of these tables.
+    pub const fn is_per_game(self) -> bool {
+        matches!(
+            self,
+            Self::Games
+                | Self::GameLineupAppearances
+                | Self::GameFieldingAppearances
+                | Self::GameEarnedRuns
+                | Self::GameDhVacancies
+                | Self::Events
+                | Self::EventAudit
+                | Self::EventBaserunners
+                | Self::EventFieldingPlay
+                | Self::EventPitchSequences
+                | Self::EventFlags
+                | Self::EventComments
+                | Self::EventOutfieldRunnerPlays
+                | Self::EventFieldingAlignments
+                | Self::EventChargeReassignments
+                | Self::PinchAppearances
+                | Self::PitchingAppearances
+                | Self::InheritedRunners
+        )
+    }
+
+    fn write_game_rows(self, gc: &GameContext, w: &mut impl SchemaWriter) -> Result<()> {
+        match self {
+            Self::Games => w.serialize(Games::from(gc)),
+            Self::GameLineupAppearances => write_rows(w, &gc.lineup_appearances),
+            Self::GameFieldingAppearances => write_rows(w, &gc.fielding_appearances),
+            Self::GameEarnedRuns => write_rows(w, GameEarnedRuns::from_game_context(gc)),
+            Self::GameDhVacancies => write_rows(w, &gc.dh_vacancies),
+            Self::Events => write_rows(w, Events::from_game_context(gc)),
+            Self::EventAudit => write_rows(w, EventAudit::from_game_context(gc)),
+            Self::EventBaserunners => write_rows(w, EventBaserunners::from_game_context(gc)),
+            Self::EventFieldingPlay => write_rows(w, EventFieldingPlays::from_game_context(gc)),
+            Self::EventPitchSequences => write_rows(w, EventPitchSequences::from_game_context(gc)),
+            Self::EventFlags => write_rows(w, gc.events.iter().flat_map(|e| &e.results.play_info)),
+            Self::EventComments => write_rows(w, EventComments::from_game_context(gc)),
+            Self::EventOutfieldRunnerPlays => {
+                write_rows(w, EventOutfieldRunnerPlays::from_game_context(gc))
+            }
+            Self::EventFieldingAlignments => {
+                write_rows(w, EventFieldingAlignments::from_game_context(gc))
+            }
+            Self::EventChargeReassignments => {
+                write_rows(w, EventChargeReassignments::from_game_context(gc))
+            }
+            Self::PinchAppearances => write_rows(w, PinchAppearances::from_game_context(gc)),
+            Self::PitchingAppearances => write_rows(w, PitchingAppearances::from_game_context(gc)),
+            Self::InheritedRunners => write_rows(w, InheritedRunners::from_game_context(gc)),
+            _ => bail!("Table {self} can't be built from a single game"),
+        }
+    }
+}
+
+fn write_rows<R: Serialize>(
+    w: &mut impl SchemaWriter,
+    rows: impl IntoIterator<Item = R>,
+) -> Result<()> {
+    for row in rows {
+        w.serialize(row)?;
+    }
+    Ok(())
+}
+
+/// The Arrow file a table is written to before it's read into a `DataFrame`
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .map_err(|e| io::Error::other(e.to_string()))?
+            .write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Buffers a table's rows in memory as an Arrow file, typed the same as the binary's
+/// Arrow output, until they're read into a `DataFrame`
+pub struct DataFrameWriter {
+    rows: ArrowIpcWriter<SharedBuffer>,
+    buffer: SharedBuffer,
+}
+
+impl DataFrameWriter {
+    /// Starts a frame with the given columns, which it keeps even if no rows are written
+    pub fn try_new(schema: Schema) -> Result<Self> {
+        let buffer = SharedBuffer::default();
+        Ok(Self {
+            rows: ArrowIpcWriter::try_new(buffer.clone(), schema)?,
+            buffer,
+        })
+    }
+
+    pub fn finish(mut self) -> Result<DataFrame> {
+        self.rows.flush()?;
+        let bytes = std::mem::take(
+            &mut *self
+                .buffer
+                .0
+                .lock()
+                .map_err(|e| anyhow!("Failed to acquire buffer lock: {e}"))?,
+        );
+        Ok(IpcReader::new(Cursor::new(bytes)).finish()?)
+    }
+}
+
+impl SchemaWriter for DataFrameWriter {
+    fn serialize<R: Serialize>(&mut self, row: R) -> Result<()> {
+        self.rows.serialize(row)
+    }
+
+    /// Rows stay buffered until `finish`
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Builds every per-game table (see [`EventFileSchema::is_per_game`]) from the games, without writing anything to disk.
+/// Box score accounts are skipped, as none of these tables can be built from them.
+///
+/// ```no_run
+/// use baseball_computer::prelude::*;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let reader = RetrosheetReader::new(&"2023NYA.EVA".into(), 0)?;
+/// let file_info = reader.file_info;
+/// let games = reader
+///     .enumerate()
+///     .map(|(game_num, record_vec)| {
+///         let record_vec = record_vec?;
+///         GameContext::new(
+///             &record_vec.record_vec,
+///             file_info,
+///             record_vec.line_offset,
+///             game_num,
+///             record_vec.content_hash,
+///         )
+///     })
+///     .collect::<anyhow::Result<Vec<_>>>()?;
+/// let frames = to_dataframes(&games)?;
+/// println!("{}", frames[&EventFileSchema::Events]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_dataframes<'a>(
+    game_contexts: impl IntoIterator<Item = &'a GameContext>,
+) -> Result<HashMap<EventFileSchema, DataFrame>> {
+    let mut writers = EventFileSchema::iter()
+        .filter(|schema| schema.is_per_game())
+        .map(|schema| Ok((schema, DataFrameWriter::try_new(schema.columns()?)?)))
+        .collect::<Result<HashMap<_, _>>>()?;
+    for gc in game_contexts {
+        if gc.file_info.account_type == AccountType::BoxScore {
+            continue;
+        }
+        for (schema, writer) in &mut writers {
+            schema.write_game_rows(gc, writer)?;
+        }
+    }
+    writers
+        .into_iter()
+        .map(|(schema, writer)| Ok((schema, writer.finish()?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_frames_keep_their_columns() {
+        let frames = to_dataframes([]).unwrap();
+        assert!(frames.keys().all(|schema| schema.is_per_game()));
+        let events = &frames[&EventFileSchema::Events];
+        assert_eq!(events.height(), 0);
+        assert_eq!(
+            events.width(),
+            EventFileSchema::Events.columns().unwrap().fields().len()
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use std::sync::{Arc, RwLock};
+
+use anyhow::{anyhow, bail, Result};
+use lazy_static::lazy_static;
+
+use crate::event_file::game_state::GameContext;
+
+pub type GameCallback = dyn Fn(&GameContext) -> Result<()> + Send + Sync;
+
+lazy_static! {
+    static ref REGISTRY: RwLock<Vec<Arc<dyn DerivedSchema>>> = RwLock::default();
+    static ref CALLBACKS: RwLock<Vec<Arc<GameCallback>>> = RwLock::default();
+}
+
+/// A custom output table computed from each play-by-play game. Registered schemas get
+/// their own `{name}.csv` file alongside the built-in ones, so new tables can be added
+/// without touching `EventFileSchema` or the write functions.
+pub trait DerivedSchema: Send + Sync {
+    /// Used as the output file name, so must be unique across all schemas
+    fn name(&self) -> &str;
+
+    fn header(&self) -> Vec<String>;
+
+    /// Rows for a single game, each with the same number of fields as the header
+    fn game_rows(&self, gc: &GameContext) -> Result<Vec<Vec<String>>>;
+}
+
+/// Adds a schema to the set written on every run. This needs to happen before
+/// any files are processed, as writers are created on first use.
+pub fn register_derived_schema(schema: impl DerivedSchema + 'static) -> Result<()> {
+    let mut registry = REGISTRY
+        .write()
+        .map_err(|e| anyhow!("Failed to acquire registry lock: {e}"))?;
+    if registry.iter().any(|s| s.name() == schema.name()) {
+        bail!("Derived schema {} is already registered", schema.name());
+    }
+    registry.push(Arc::new(schema));
+    Ok(())
+}
+
+pub fn registered_derived_schemas() -> Result<Vec<Arc<dyn DerivedSchema>>> {
+    Ok(REGISTRY
+        .read()
+        .map_err(|e| anyhow!("Failed to acquire registry lock: {e}"))?
+        .clone())
+}
+
+/// Registers a function to be called on every parsed game (after deduplication),
+/// for computing custom statistics inline. Callbacks are invoked from the parallel
+/// workers, so they may be called concurrently and in no particular game order.
+pub fn register_game_callback<F>(callback: F) -> Result<()>
+where
+    F: Fn(&GameContext) -> Result<()> + Send + Sync + 'static,
+{
+    CALLBACKS
+        .write()
+        .map_err(|e| anyhow!("Failed to acquire callback lock: {e}"))?
+        .push(Arc::new(callback));
+    Ok(())
+}
+
+pub fn on_game(gc: &GameContext) -> Result<()> {
+    let callbacks = CALLBACKS
+        .read()
+        .map_err(|e| anyhow!("Failed to acquire callback lock: {e}"))?;
+    for callback in callbacks.iter() {
+        callback(gc)?;
+    }
+    Ok(())
+}
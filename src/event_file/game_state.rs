@@ -1,35 +1,39 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use anyhow::{anyhow, bail, Context, Error, Result};
 use arrayvec::{ArrayString, ArrayVec};
 use bounded_integer::{BoundedU8, BoundedUsize};
-use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
 use fixed_map::{Key, Map};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use strum_macros::{AsRefStr, Display};
+use tracing::warn;
 
 use crate::event_file::info::{
-    DayNight, DoubleheaderStatus, FieldCondition, HowScored, InfoRecord, Park, Precipitation, Sky,
-    Team, UmpireAssignment, UmpirePosition, WindDirection,
+    DayNight, DoubleheaderStatus, FieldCondition, Forfeit, HowScored, InfoRecord, Park,
+    Precipitation, Sky, Team, UmpireAssignment, UmpirePosition, WindDirection,
 };
 use crate::event_file::misc::{
     BatHandAdjustment, EarnedRunRecord, GameId, Hand, PitchHandAdjustment,
     PitcherResponsibilityAdjustment, RunnerAdjustment, SubstitutionRecord,
 };
-use crate::event_file::parser::{FileInfo, MappedRecord, RecordSlice};
+use crate::event_file::parks::Roof;
+use crate::event_file::parser::{AccountType, FileInfo, MappedRecord, RecordSlice};
 use crate::event_file::play::{
-    Base, BaseRunner, BaserunningPlayType, Trajectory, Count, FieldersData, FieldingData, HitType,
-    InningFrame, OtherPlateAppearance, OutAtBatType, PlateAppearanceType, PlayModifier, PlayRecord,
-    PlayType, RunnerAdvance, UnearnedRunStatus,
+    Base, BaseRunner, BaserunningPlayType, Trajectory, Count, EventFlagType, FieldersData,
+    FieldingData, HitType, InningFrame, OtherPlateAppearance, OutAtBatType, PlateAppearanceType,
+    PlayModifier, PlayRecord, PlayType, RunnerAdvance, UnearnedRunStatus,
 };
+use crate::event_file::rules::RulesEra;
 use crate::event_file::traits::{
-    FieldingPosition, Inning, LineupPosition, Matchup, Pitcher, Player, RetrosheetVolunteer,
-    Scorer, SequenceId, Side, Umpire, MAX_EVENTS_PER_GAME,
+    max_events_per_game, FieldingPosition, Inning, LineupPosition, Matchup, Person, Pitcher,
+    Player, RetrosheetVolunteer, Scorer, SequenceId, Side, Umpire, MAX_GAMES_PER_FILE,
 };
-use crate::AccountType;
 
 use super::box_score::{BoxScoreEvent, BoxScoreLine, LineScore};
 use super::pitch_sequence::{PitchSequence, PitchSequenceItem, PitchType};
@@ -37,8 +41,12 @@ use super::play::{BattedBallAngle, BattedBallDepth, BattedBallLocationGeneral, B
 use super::schemas::GameIdString;
 use super::traits::{EventKey, FieldingPlayType, GameType};
 
-const UNKNOWN_STRINGS: [&str; 1] = ["unknown"];
-const NONE_STRINGS: [&str; 2] = ["(none)", "none"];
+/// Games played without fans outside of the 2020 season
+const CLOSED_DOOR_GAMES: [&str; 1] = ["BAL201504290"];
+
+/// Innings a game has to last to become official, or half an inning less when the team
+/// batting last is ahead
+const OFFICIAL_GAME_INNINGS: u8 = 5;
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash, Display, Key)]
 enum PositionType {
@@ -81,6 +89,17 @@ type Lineup = PersonnelState;
 type Defense = PersonnelState;
 pub type EventId = SequenceId;
 
+/// Context attached to errors from processing a game's records, giving the line in the
+/// file of the record that failed. Can be recovered from the error with `downcast_ref`.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct RecordLine(pub usize);
+
+impl std::fmt::Display for RecordLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error processing record on line {}", self.0)
+    }
+}
+
 fn get_game_id(rv: &RecordSlice) -> Result<GameId> {
     rv.iter()
         .find_map(|mr| {
@@ -170,6 +189,30 @@ impl PlateAppearanceResultType {
         )
     }
 
+    pub const fn is_hit(self) -> bool {
+        matches!(
+            self,
+            Self::Single
+                | Self::Double
+                | Self::GroundRuleDouble
+                | Self::Triple
+                | Self::HomeRun
+                | Self::InsideTheParkHomeRun
+        )
+    }
+
+    pub const fn is_at_bat(self) -> bool {
+        !matches!(
+            self,
+            Self::Walk
+                | Self::IntentionalWalk
+                | Self::HitByPitch
+                | Self::Interference
+                | Self::SacrificeFly
+                | Self::SacrificeHit
+        )
+    }
+
     fn from_internal(plate_appearance: &PlateAppearanceType, modifiers: &[PlayModifier]) -> Self {
         let is_sac_fly = modifiers.iter().any(|m| m == &PlayModifier::SacrificeFly);
         let is_sac_hit = modifiers.iter().any(|m| m == &PlayModifier::SacrificeHit);
@@ -206,11 +249,14 @@ impl PlateAppearanceResultType {
     }
 }
 
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Serialize)]
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct EventFlag {
     event_key: EventKey,
     sequence_id: SequenceId,
     flag: String,
+    flag_type: EventFlagType,
+    base: Option<Base>,
+    fielding_position: Option<FieldingPosition>,
 }
 
 impl EventFlag {
@@ -225,6 +271,9 @@ impl EventFlag {
                     event_key,
                     sequence_id: SequenceId::new(i + 1).context("Invalid sequence ID")?,
                     flag: pm.flag_string(),
+                    flag_type: pm.flag_type(),
+                    base: pm.flag_base(),
+                    fielding_position: pm.flag_fielder(),
                 })
             })
             .collect()
@@ -232,10 +281,7 @@ impl EventFlag {
 }
 
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
-pub struct Season(u16);
-
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Serialize, Deserialize)]
-struct League(String);
+pub struct Season(pub u16);
 
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct GameSetting {
@@ -255,6 +301,7 @@ pub struct GameSetting {
     pub attendance: Option<u32>,
     pub wind_speed_mph: Option<u8>,
     pub use_dh: bool,
+    pub forfeit: Option<Forfeit>,
 }
 
 impl Default for GameSetting {
@@ -276,10 +323,59 @@ impl Default for GameSetting {
             attendance: None,
             park_id: Park::default(),
             season: Season(0),
+            forfeit: None,
         }
     }
 }
 
+impl GameSetting {
+    pub fn calendar_year(&self) -> u16 {
+        u16::try_from(self.date.year()).unwrap_or_default()
+    }
+
+    pub fn temperature_celsius(&self) -> Option<f32> {
+        self.temperature_fahrenheit
+            .map(|t| round_to_tenths((f32::from(t) - 32.0) * 5.0 / 9.0))
+    }
+
+    pub fn wind_speed_kph(&self) -> Option<f32> {
+        self.wind_speed_mph
+            .map(|w| round_to_tenths(f32::from(w) * 1.609_344))
+    }
+
+    /// Whether the game was played under a roof, from the park. Retractable roofs can be
+    /// open one day and closed the next, so games in those parks, and games without a
+    /// park, go by the sky entry instead.
+    pub fn indoor_flag(&self) -> Option<bool> {
+        // Without a park, only the sky tells whether there was a roof over it
+        let roof = if self.park_id == Park::default() {
+            Roof::Retractable
+        } else {
+            Roof::of(&self.park_id)
+        };
+        match (roof, self.sky) {
+            (Roof::Dome, _) | (Roof::Retractable, Sky::Dome) => Some(true),
+            (Roof::Retractable, Sky::Unknown) => None,
+            (Roof::Open | Roof::Retractable, _) => Some(false),
+        }
+    }
+
+    /// True if none of the weather fields were recorded. Older accounts fill these in
+    /// with 0 or "unknown", which end up blank, so this separates games with no weather
+    /// data at all from those that are only missing a field or two.
+    pub fn weather_data_missing_flag(&self) -> bool {
+        self.temperature_fahrenheit.is_none()
+            && self.wind_speed_mph.is_none()
+            && self.sky == Sky::Unknown
+            && self.precipitation == Precipitation::Unknown
+            && self.wind_direction == WindDirection::Unknown
+    }
+}
+
+fn round_to_tenths(x: f32) -> f32 {
+    (x * 10.0).round() / 10.0
+}
+
 impl From<&RecordSlice> for GameSetting {
     fn from(vec: &RecordSlice) -> Self {
         let infos = vec.iter().filter_map(|rv| {
@@ -311,9 +407,20 @@ impl From<&RecordSlice> for GameSetting {
                 InfoRecord::WindSpeed(x) => setting.wind_speed_mph = *x,
                 InfoRecord::Attendance(x) => setting.attendance = *x,
                 InfoRecord::Park(x) => setting.park_id = *x,
+                InfoRecord::Forfeit(x) => setting.forfeit = Some(*x),
                 _ => {}
             }
         }
+        setting.season = Season(setting.calendar_year());
+        // Zero attendance is a placeholder for a missing value, except for games that
+        // were actually played in an empty park
+        if setting.attendance == Some(0) {
+            let is_closed_door = setting.season == Season(2020)
+                || get_game_id(vec).is_ok_and(|g| CLOSED_DOOR_GAMES.contains(&g.id.as_str()));
+            if !is_closed_door {
+                setting.attendance = None;
+            }
+        }
         setting
     }
 }
@@ -361,28 +468,16 @@ pub struct GameUmpire {
 }
 
 impl GameUmpire {
-    // Retrosheet has two different possible null-like values for umpire names, "none"
+    // Retrosheet has two different kinds of null-like values for umpire names, "none"
     // and "unknown". We take "none" to mean that there was no umpire at that position,
     // so we do not create a record. If "unknown", we assume there was someone at that position,
     // so a struct is created with a None umpire ID.
     fn from_umpire_assignment(ua: &UmpireAssignment, game_id: GameId) -> Option<Self> {
-        let umpire = ua.umpire?;
-        let position = ua.position;
-        if NONE_STRINGS.contains(&umpire.as_str()) {
-            None
-        } else if UNKNOWN_STRINGS.contains(&umpire.as_str()) {
-            Some(Self {
-                game_id: game_id.id,
-                position,
-                umpire_id: None,
-            })
-        } else {
-            Some(Self {
-                game_id: game_id.id,
-                position,
-                umpire_id: Some(umpire),
-            })
-        }
+        (ua.umpire.is_some() || ua.identity_unknown).then_some(Self {
+            game_id: game_id.id,
+            position: ua.position,
+            umpire_id: ua.umpire,
+        })
     }
 
     fn from_record_slice(slice: &RecordSlice) -> Result<Vec<Self>> {
@@ -429,6 +524,9 @@ impl From<&[MappedRecord]> for GameResults {
                 InfoRecord::SavePitcher(x) => results.save_pitcher = *x,
                 InfoRecord::GameWinningRbi(x) => results.game_winning_rbi = *x,
                 InfoRecord::TimeOfGameMinutes(x) => results.time_of_game_minutes = *x,
+                InfoRecord::Completion(x) => {
+                    results.completion_info = x.map(|c| c.to_string());
+                }
                 _ => {}
             });
         // Add earned runs
@@ -445,7 +543,10 @@ impl From<&[MappedRecord]> for GameResults {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize)]
+/// A player's time in a spot in the batting order. Rows are ordered by side, lineup
+/// position, start and end event, then player, and `appearance_id` numbers them in that
+/// order from 1, so `(game_id, appearance_id)` is unique.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub struct GameLineupAppearance {
     pub game_id: GameIdString,
     pub player_id: Player,
@@ -454,6 +555,8 @@ pub struct GameLineupAppearance {
     pub entered_game_as: EnteredGameAs,
     pub start_event_id: EventId,
     pub end_event_id: Option<EventId>,
+    /// Zero until the game's appearances are complete
+    pub appearance_id: usize,
 }
 
 impl GameLineupAppearance {
@@ -489,6 +592,7 @@ impl GameLineupAppearance {
             entered_game_as: EnteredGameAs::Starter,
             start_event_id: EventId::new(1).context("Could not create event ID")?,
             end_event_id: None,
+            appearance_id: 0,
         })
     }
 
@@ -500,7 +604,23 @@ impl GameLineupAppearance {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Serialize, Copy)]
+/// A team giving up the DH for the rest of the game, which happens when its pitcher
+/// takes a spot in the batting order. The DH and the non-batting pitcher, if either was
+/// still in the game, leave their roles before `event_id`.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize, Copy)]
+pub struct GameDhVacancy {
+    pub game_id: GameIdString,
+    pub side: Side,
+    pub event_id: EventId,
+    pub pitcher_id: Player,
+    pub lineup_position: LineupPosition,
+    pub designated_hitter_id: Option<Player>,
+    pub non_batting_pitcher_id: Option<Player>,
+}
+
+/// A player's time at a fielding position, ordered and numbered like
+/// `GameLineupAppearance` but by fielding position instead of lineup position
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize, Copy)]
 pub struct GameFieldingAppearance {
     pub game_id: GameIdString,
     pub player_id: Player,
@@ -508,9 +628,29 @@ pub struct GameFieldingAppearance {
     pub fielding_position: FieldingPosition,
     pub start_event_id: EventId,
     pub end_event_id: Option<EventId>,
+    /// Zero until the game's appearances are complete
+    pub appearance_id: usize,
 }
 
 impl GameFieldingAppearance {
+    pub fn get_at_event(
+        appearances: &[Self],
+        position: FieldingPosition,
+        event_id: EventId,
+        side: Side,
+    ) -> Result<Self> {
+        appearances
+            .iter()
+            .find(|a| {
+                a.fielding_position == position
+                    && a.side == side
+                    && a.start_event_id <= event_id
+                    && a.end_event_id.is_none_or(|end| end >= event_id)
+            })
+            .copied()
+            .context("Could not find fielding appearance")
+    }
+
     fn new_starter(
         player: Player,
         fielding_position: FieldingPosition,
@@ -524,6 +664,7 @@ impl GameFieldingAppearance {
             side,
             start_event_id: EventId::new(1).context("Could not create event ID")?,
             end_event_id: None,
+            appearance_id: 0,
         })
     }
 
@@ -541,6 +682,7 @@ impl GameFieldingAppearance {
             side,
             start_event_id: start_event,
             end_event_id: None,
+            appearance_id: 0,
         }
     }
 
@@ -596,11 +738,14 @@ pub struct GameContext {
     pub results: GameResults,
     pub lineup_appearances: Vec<GameLineupAppearance>,
     pub fielding_appearances: Vec<GameFieldingAppearance>,
+    pub dh_vacancies: Vec<GameDhVacancy>,
     pub events: Vec<Event>,
     pub line_offset: usize,
     pub event_key_offset: i32,
+    pub content_hash: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub box_score_data: Option<BoxScoreData>,
+    pub rules: RulesEra,
 }
 
 impl GameContext {
@@ -609,25 +754,55 @@ impl GameContext {
         file_info: FileInfo,
         line_offset: usize,
         game_num: usize,
+        content_hash: u64,
     ) -> Result<Self> {
         let game_id = get_game_id(record_slice)?;
         let teams: Matchup<Team> = Matchup::try_from(record_slice)?;
-        let setting = GameSetting::try_from(record_slice)?;
+        let mut setting = GameSetting::try_from(record_slice)?;
+        let has_game_type_record = record_slice
+            .iter()
+            .any(|r| matches!(r, MappedRecord::Info(InfoRecord::GameType(_))));
+        if let (Some(game_type), false) = (file_info.game_type, has_game_type_record) {
+            setting.game_type = game_type;
+        }
+        // Some games belong to a season other than the calendar year they were played
+        // in, so the season in the file name wins, unless it's too far off to be plausible
+        match file_info.season {
+            Some(season) if season.abs_diff(setting.calendar_year()) <= 1 => {
+                setting.season = Season(season);
+            }
+            Some(season) => warn!(
+                "Game {} is dated {} but is in a file for {season}, using the date's season",
+                game_id.id, setting.date
+            ),
+            None => {}
+        }
         let metadata = GameMetadata::try_from(record_slice)?;
         let umpires = GameUmpire::from_record_slice(record_slice)?;
         let results = GameResults::try_from(record_slice)?;
         let event_key_offset = Self::event_key_offset(file_info, game_num)?;
+        let file_info = FileInfo {
+            account_type: AccountType::for_game(file_info.account_type, record_slice),
+            ..file_info
+        };
         let box_score_data = if file_info.account_type == AccountType::BoxScore {
             Some(BoxScoreData::from_record_slice(record_slice)?)
         } else {
             None
         };
 
-        let (events, lineup_appearances, fielding_appearances) =
+        let rules = RulesEra::for_game(
+            setting.season.0,
+            setting.game_type,
+            setting.doubleheader_status,
+            record_slice,
+        );
+
+        let (events, lineup_appearances, fielding_appearances, dh_vacancies) =
             if file_info.account_type == AccountType::BoxScore {
-                (vec![], vec![], vec![])
+                (vec![], vec![], vec![], vec![])
             } else {
-                GameState::create_events(record_slice, line_offset, event_key_offset)
+                GameState::create_events(record_slice, line_offset, event_key_offset, rules)
                     .with_context(|| anyhow!("Could not parse events"))?
             };
 
@@ -641,18 +816,260 @@ impl GameContext {
             results,
             lineup_appearances,
             fielding_appearances,
+            dh_vacancies,
             events,
             line_offset,
             event_key_offset,
+            content_hash,
             box_score_data,
+            rules,
         })
     }
 
     fn event_key_offset(file_info: FileInfo, game_num: usize) -> Result<i32> {
-        (file_info.file_index + (game_num * MAX_EVENTS_PER_GAME))
+        if game_num >= MAX_GAMES_PER_FILE {
+            bail!(
+                "File has more than {MAX_GAMES_PER_FILE} games, so its event keys would \
+                 overlap the next file's"
+            );
+        }
+        (file_info.file_index + (game_num * max_events_per_game()))
             .try_into()
             .context("i32 overflow on event key creation")
     }
+
+    /// Runs, hits, errors and runners left on base for each side, counted from the events
+    /// of play-by-play games and summed from the player lines of box score games, except
+    /// for left on base, which box scores only give on the team line. Returns `None` if
+    /// the game has neither.
+    pub fn line_totals(&self) -> Option<Matchup<LineTotals>> {
+        let mut totals = Matchup::<LineTotals>::default();
+        if let Some(box_score) = &self.box_score_data {
+            if box_score.lines.is_empty() {
+                return None;
+            }
+            for line in &box_score.lines {
+                match line {
+                    BoxScoreLine::BattingLine(bl) => {
+                        let t = totals.get_mut(bl.side);
+                        t.runs += u16::from(bl.batting_stats.runs);
+                        t.hits += u16::from(bl.batting_stats.hits);
+                    }
+                    BoxScoreLine::DefenseLine(dl) => {
+                        totals.get_mut(dl.side).errors += dl
+                            .defensive_stats
+                            .and_then(|d| d.errors)
+                            .map_or(0, u16::from);
+                    }
+                    BoxScoreLine::TeamMiscellaneousLine(tl) => {
+                        totals.get_mut(tl.side).left_on_base = tl.left_on_base.map(u16::from);
+                    }
+                    _ => {}
+                }
+            }
+            return Some(totals);
+        }
+        if self.events.is_empty() {
+            return None;
+        }
+        totals.away.left_on_base = Some(0);
+        totals.home.left_on_base = Some(0);
+        for (event, stranded) in self.left_on_base() {
+            let left_on_base = totals
+                .get_mut(event.context.batting_side)
+                .left_on_base
+                .as_mut();
+            if let Some(left_on_base) = left_on_base {
+                *left_on_base += u16::from(stranded);
+            }
+        }
+        for event in &self.events {
+            let batting_side = event.context.batting_side;
+            let batting = totals.get_mut(batting_side);
+            batting.runs += u16::try_from(event.results.runs.len()).unwrap_or_default();
+            batting.hits += u16::from(
+                event
+                    .results
+                    .plate_appearance
+                    .is_some_and(PlateAppearanceResultType::is_hit),
+            );
+            let errors = event
+                .results
+                .fielding_plays
+                .iter()
+                .filter(|fp| fp.fielding_play_type == FieldingPlayType::Error)
+                .count();
+            totals.get_mut(batting_side.flip()).errors +=
+                u16::try_from(errors).unwrap_or_default();
+        }
+        Some(totals)
+    }
+
+    /// The last event of each half-inning, with the number of runners still on base
+    /// once it was over. Runners left on when the game ends are counted too, even if
+    /// it ends on a walk-off.
+    pub fn left_on_base(&self) -> impl Iterator<Item = (&Event, u8)> {
+        self.events
+            .iter()
+            .zip(self.events.iter().skip(1).map(Some).chain([None]))
+            .filter(|(e, next)| {
+                next.is_none_or(|n| {
+                    (n.context.inning, n.context.batting_side)
+                        != (e.context.inning, e.context.batting_side)
+                })
+            })
+            .map(|(e, _)| {
+                let runners = e.results.ending_base_state.num_runners_on_base();
+                (e, u8::try_from(runners).unwrap_or_default())
+            })
+    }
+
+    /// Which optional details were recorded for every event they apply to. Pitch
+    /// sequences and counts are checked on plate appearances and hit locations on balls
+    /// in play. Games without any plate appearances, including box scores, have none.
+    pub fn detail_coverage(&self) -> DetailCoverage {
+        let plate_appearances = self
+            .events
+            .iter()
+            .filter(|e| e.results.plate_appearance.is_some())
+            .collect_vec();
+        if plate_appearances.is_empty() {
+            return DetailCoverage::default();
+        }
+        DetailCoverage {
+            pitch_sequences: plate_appearances.iter().all(|e| {
+                !e.results.pitch_sequence.is_empty()
+                    && !e
+                        .results
+                        .pitch_sequence
+                        .iter()
+                        .any(|p| p.pitch_type.is_unknown())
+            }),
+            counts: plate_appearances.iter().all(|e| {
+                let count = e.results.count_at_event;
+                count.balls.is_some() && count.strikes.is_some()
+            }),
+            hit_locations: plate_appearances
+                .iter()
+                .filter_map(|e| e.results.batted_ball_info.as_ref())
+                .all(|b| b.general_location != BattedBallLocationGeneral::Unknown),
+        }
+    }
+
+    /// How the game ended, judged from its `forfeit` and `completion` info records, the
+    /// final score and how far it got. Returns `None` if there are no events or lines to
+    /// take the score from.
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        if self.setting.forfeit.is_some() {
+            return Some(GameOutcome::Forfeit);
+        }
+        let totals = self.line_totals()?;
+        let bat_last = self.setting.bat_first_side.flip();
+        if !self.is_official(totals.get(bat_last).runs > totals.get(bat_last.flip()).runs) {
+            return Some(GameOutcome::SuspendedIncomplete);
+        }
+        Some(match totals.away.runs.cmp(&totals.home.runs) {
+            Ordering::Greater => GameOutcome::AwayWin,
+            Ordering::Less => GameOutcome::HomeWin,
+            // A suspended game is only tied here if its finish is missing, while before
+            // 2007 a game called tied otherwise stood as a tie
+            Ordering::Equal
+                if self.rules.tied_games_suspended || self.results.completion_info.is_some() =>
+            {
+                GameOutcome::SuspendedIncomplete
+            }
+            Ordering::Equal => GameOutcome::Tie,
+        })
+    }
+
+    /// Whether the game went far enough to count. Box scores are taken to have completed
+    /// every inning in their line score.
+    fn is_official(&self, bat_last_leads: bool) -> bool {
+        let official_innings = OFFICIAL_GAME_INNINGS.min(self.rules.scheduled_innings);
+        if let Some(box_score) = &self.box_score_data {
+            let innings = box_score
+                .line_scores
+                .iter()
+                .map(|ls| ls.line_score.len())
+                .max()
+                .unwrap_or_default();
+            return innings >= usize::from(official_innings);
+        }
+        let Some(last) = self.events.last() else {
+            return false;
+        };
+        let half_inning_over = last.context.outs.get() + last.results.out_on_play.len() >= 3;
+        let inning = last.context.inning.cmp(&official_innings);
+        match (inning, last.context.frame) {
+            (Ordering::Greater, _) => true,
+            (Ordering::Less, _) => false,
+            (Ordering::Equal, InningFrame::Top) => half_inning_over && bat_last_leads,
+            (Ordering::Equal, InningFrame::Bottom) => half_inning_over || bat_last_leads,
+        }
+    }
+
+    /// How much of a deduced game's account was recorded rather than inferred, from 0 to 1.
+    /// Each plate appearance loses a third for a missing count, a third for any unknown
+    /// fielder, and another third if it's a generic out with no known fielders at all,
+    /// and the score is the average over all plate appearances. `None` for other kinds
+    /// of account, and for deduced games without plate appearances.
+    pub fn deduced_confidence(&self) -> Option<f64> {
+        if self.file_info.account_type != AccountType::Deduced {
+            return None;
+        }
+        let penalties = self
+            .events
+            .iter()
+            .filter(|e| e.results.plate_appearance.is_some())
+            .map(|e| {
+                let count = e.results.count_at_event;
+                let fielders = &e.results.fielding_plays;
+                let unknown_fielder =
+                    |fp: &FieldersData| fp.fielding_position == FieldingPosition::Unknown;
+                let missing_count = count.balls.is_none() || count.strikes.is_none();
+                let any_unknown = fielders.iter().any(unknown_fielder);
+                let generic_out = e.results.plate_appearance
+                    == Some(PlateAppearanceResultType::InPlayOut)
+                    && fielders.iter().all(unknown_fielder);
+                u8::from(missing_count) + u8::from(any_unknown) + u8::from(generic_out)
+            })
+            .collect_vec();
+        if penalties.is_empty() {
+            return None;
+        }
+        let total: u32 = penalties.iter().copied().map(u32::from).sum();
+        #[allow(clippy::cast_precision_loss)]
+        let possible = (penalties.len() * 3) as f64;
+        Some(1.0 - f64::from(total) / possible)
+    }
+}
+
+/// How a game ended. A tie that was later replayed is still a tie, as the replay is a
+/// separate game with its own ID.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum GameOutcome {
+    HomeWin,
+    AwayWin,
+    Tie,
+    /// Stopped before it was official, suspended while tied, or called tied in an era when
+    /// that meant it was to be finished another day, and the rest of it isn't in the data
+    SuspendedIncomplete,
+    Forfeit,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub struct DetailCoverage {
+    pub pitch_sequences: bool,
+    pub counts: bool,
+    pub hit_locations: bool,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub struct LineTotals {
+    pub runs: u16,
+    pub hits: u16,
+    pub errors: u16,
+    pub left_on_base: Option<u16>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
@@ -857,6 +1274,9 @@ pub struct EventResults {
     pub plays_at_base: Vec<EventBaserunningPlay>,
     pub out_on_play: Vec<BaseRunner>,
     pub fielding_plays: Vec<FieldersData>,
+    /// The fielders on each runner's out, which `fielding_plays` runs together
+    #[serde(skip)]
+    pub out_fielding_plays: Vec<(BaseRunner, Vec<FieldersData>)>,
     pub baserunning_advances: Vec<EventBaserunningAdvanceAttempt>,
     pub runs: Vec<EventRun>,
     #[serde(skip)]
@@ -866,6 +1286,15 @@ pub struct EventResults {
     pub no_play_flag: bool,
 }
 
+impl EventResults {
+    pub fn out_fielders(&self, baserunner: BaseRunner) -> &[FieldersData] {
+        self.out_fielding_plays
+            .iter()
+            .find(|(br, _)| *br == baserunner)
+            .map_or(&[], |(_, fielders)| fielders)
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Serialize)]
 pub struct Event {
     pub game_id: GameId,
@@ -874,10 +1303,21 @@ pub struct Event {
     pub context: EventContext,
     pub results: EventResults,
     pub line_number: usize,
-    pub raw_play: Arc<String>
+    pub raw_play: Arc<String>,
+    pub raw_pitch_sequence: Arc<String>,
 }
 
 impl Event {
+    /// Runners still on base after the batter made an out on the play, or 0 if the
+    /// batter wasn't put out
+    pub fn batter_left_on_base(&self) -> u8 {
+        if self.results.out_on_play.contains(&BaseRunner::Batter) {
+            u8::try_from(self.results.ending_base_state.num_runners_on_base()).unwrap_or_default()
+        } else {
+            0
+        }
+    }
+
     pub fn summary(&self) -> String {
         format!(
             r#"
@@ -901,6 +1341,149 @@ impl Event {
     }
 }
 
+/// Replays a parsed game one event at a time, for querying the state of the game at any
+/// point: bases, outs, score, who's in the lineup and on the field, and pitch counts.
+/// Unlike `GameState`, which builds the events in the first place, this only reads
+/// from a finished `GameContext`, so it can be stepped backwards or restarted freely.
+#[derive(Debug, Clone)]
+pub struct GameStateMachine<'a> {
+    gc: &'a GameContext,
+    /// Number of events applied so far
+    position: usize,
+    score: Matchup<u8>,
+    pitch_counts: HashMap<Pitcher, usize>,
+}
+
+impl<'a> GameStateMachine<'a> {
+    /// Starts before the first event
+    pub fn new(gc: &'a GameContext) -> Self {
+        Self {
+            gc,
+            position: 0,
+            score: Matchup::default(),
+            pitch_counts: HashMap::new(),
+        }
+    }
+
+    /// Applies the next event and returns it, or `None` once the game is over
+    pub fn step(&mut self) -> Option<&'a Event> {
+        let event = self.gc.events.get(self.position)?;
+        let runs = self.score.get_mut(event.context.batting_side);
+        *runs = runs.saturating_add(u8::try_from(event.results.runs.len()).unwrap_or(u8::MAX));
+        let pitches = event
+            .results
+            .pitch_sequence
+            .iter()
+            .filter(|p| p.pitch_type.is_pitch())
+            .count();
+        *self
+            .pitch_counts
+            .entry(event.context.pitcher_id)
+            .or_default() += pitches;
+        self.position += 1;
+        Some(event)
+    }
+
+    /// Moves to just after the given event, replaying from the start if it's behind
+    /// the current position
+    pub fn seek(&mut self, event_id: EventId) -> Result<()> {
+        if self.current_event().is_some_and(|e| e.event_id > event_id) {
+            *self = Self::new(self.gc);
+        }
+        while self.current_event().is_none_or(|e| e.event_id < event_id) {
+            self.step()
+                .with_context(|| anyhow!("Event {event_id} is past the end of the game"))?;
+        }
+        Ok(())
+    }
+
+    /// The most recently applied event
+    pub fn current_event(&self) -> Option<&'a Event> {
+        self.position.checked_sub(1).and_then(|i| self.gc.events.get(i))
+    }
+
+    /// The event that will be applied by the next call to `step`
+    pub fn next_event(&self) -> Option<&'a Event> {
+        self.gc.events.get(self.position)
+    }
+
+    /// Runners on base after the current event. Runners stranded at the end of an
+    /// inning are still shown until the next event starts the new half-inning.
+    pub fn base_state(&self) -> Option<&'a BaseState> {
+        self.current_event().map(|e| &e.results.ending_base_state)
+    }
+
+    /// Outs after the current event, which is 3 if it ended the half-inning
+    pub fn outs(&self) -> usize {
+        self.current_event()
+            .map_or(0, |e| e.context.outs.get() + e.results.out_on_play.len())
+    }
+
+    pub const fn score(&self) -> Matchup<u8> {
+        self.score
+    }
+
+    /// Pitches thrown by the pitcher so far, not counting pickoff throws
+    pub fn pitch_count(&self, pitcher: Pitcher) -> usize {
+        self.pitch_counts.get(&pitcher).copied().unwrap_or_default()
+    }
+
+    /// The event whose personnel the lineup queries report on: the current event,
+    /// or the first one before the game starts
+    fn personnel_event_id(&self) -> Option<EventId> {
+        self.current_event()
+            .or_else(|| self.gc.events.first())
+            .map(|e| e.event_id)
+    }
+
+    /// Each lineup spot's current occupant, in batting order
+    pub fn lineup(&self, side: Side) -> Vec<(LineupPosition, Player)> {
+        let Some(event_id) = self.personnel_event_id() else {
+            return vec![];
+        };
+        self.gc
+            .lineup_appearances
+            .iter()
+            .filter(|a| {
+                a.side == side
+                    && a.start_event_id <= event_id
+                    && a.end_event_id.is_none_or(|end| end >= event_id)
+            })
+            // A substitute shares the event they entered on with the player they replaced
+            .into_group_map_by(|a| a.lineup_position)
+            .into_iter()
+            .filter_map(|(position, appearances)| {
+                let latest = appearances.into_iter().max_by_key(|a| a.start_event_id)?;
+                Some((position, latest.player_id))
+            })
+            .sorted()
+            .collect()
+    }
+
+    /// Each fielding position's current occupant
+    pub fn defense(&self, side: Side) -> Vec<(FieldingPosition, Player)> {
+        let Some(event_id) = self.personnel_event_id() else {
+            return vec![];
+        };
+        self.gc
+            .fielding_appearances
+            .iter()
+            .filter(|a| {
+                a.side == side
+                    && a.start_event_id <= event_id
+                    && a.end_event_id.is_none_or(|end| end >= event_id)
+            })
+            .into_group_map_by(|a| a.fielding_position)
+            .into_iter()
+            .filter_map(|(position, appearances)| {
+                let latest = appearances.into_iter().max_by_key(|a| a.start_event_id)?;
+                Some((position, latest.player_id))
+            })
+            .sorted_by_key(|(position, _)| u8::from(*position))
+            .collect()
+    }
+}
+
 /// This tracks unusual/miscellaneous elements,
 /// such as batters batting from an unexpected side or a substitution in the middle of
 /// an at-bat. Further exceptions should go here as they come up.
@@ -933,6 +1516,7 @@ struct Personnel {
     // the same game, which has never happened but could theoretically).
     lineup_appearances: HashMap<TrackedPlayer, Vec<GameLineupAppearance>>,
     defense_appearances: HashMap<TrackedPlayer, Vec<GameFieldingAppearance>>,
+    dh_vacancies: Vec<GameDhVacancy>,
 }
 
 impl Default for Personnel {
@@ -947,6 +1531,7 @@ impl Default for Personnel {
             ),
             lineup_appearances: HashMap::with_capacity(30),
             defense_appearances: HashMap::with_capacity(30),
+            dh_vacancies: vec![],
         }
     }
 }
@@ -1124,6 +1709,7 @@ impl Personnel {
             entered_game_as: EnteredGameAs::substitution_type(sub),
             start_event_id: event_id,
             end_event_id: None,
+            appearance_id: 0,
         };
         let (lineup, _) = self.personnel_state.get_mut(sub.side);
         lineup.insert(PositionType::Lineup(sub.lineup_position), new_player);
@@ -1193,22 +1779,32 @@ impl Personnel {
                 sub.side,
                 PositionType::Fielding(FieldingPosition::DesignatedHitter),
             )
-            .ok()
-            .and_then(|tp| {
-                // If the DH vacancy is being created by moving having the DH
-                // come into pitch, we don't need to end their fielding appearance
-                if sub.fielding_position == FieldingPosition::Pitcher {
-                    None
-                } else {
-                    Some(tp)
-                }
-            });
+            .ok();
+        // Pitchers entering a game without a DH come through here too
+        if non_batting_pitcher.is_none() && dh.is_none() {
+            return Ok(());
+        }
         if let Some(p) = non_batting_pitcher {
             self.get_current_lineup_appearance(&p)?.end_event_id = Some(event_id - 1);
         }
-        if let Some(p) = dh {
+        // If the DH vacancy is being created by moving having the DH
+        // come into pitch, we don't need to end their fielding appearance
+        if let Some(p) = dh.filter(|tp| tp.player != sub.player) {
             self.get_current_fielding_appearance(&p)?.end_event_id = Some(event_id - 1);
         }
+        // Otherwise later pitching changes would vacate the DH all over again
+        let (lineup, defense) = self.personnel_state.get_mut(sub.side);
+        lineup.remove(PositionType::Lineup(LineupPosition::PitcherWithDh));
+        defense.remove(PositionType::Fielding(FieldingPosition::DesignatedHitter));
+        self.dh_vacancies.push(GameDhVacancy {
+            game_id: self.game_id.id,
+            side: sub.side,
+            event_id,
+            pitcher_id: sub.player,
+            lineup_position: sub.lineup_position,
+            designated_hitter_id: dh.map(|tp| tp.player),
+            non_batting_pitcher_id: non_batting_pitcher.map(|tp| tp.player),
+        });
         Ok(())
     }
 
@@ -1230,7 +1826,17 @@ impl Personnel {
     }
 }
 
-/// Tracks the information necessary to populate each event.
+/// Everything `GameState::create_events` builds from a game's records
+pub type GameEvents = (
+    Vec<Event>,
+    Vec<GameLineupAppearance>,
+    Vec<GameFieldingAppearance>,
+    Vec<GameDhVacancy>,
+);
+
+/// Tracks the information necessary to populate each event. `GameContext::new` runs a
+/// whole game through it, but it can also be driven a record at a time with `update`
+/// to follow the state of a game as it's read.
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct GameState {
     game_id: GameId,
@@ -1245,6 +1851,7 @@ pub struct GameState {
     personnel: Personnel,
     unusual_state: RareAttributes,
     comment_buffer: Vec<String>,
+    rules: RulesEra,
 }
 
 impl GameState {
@@ -1252,14 +1859,11 @@ impl GameState {
         record_slice: &RecordSlice,
         line_offset: usize,
         event_key_offset: i32,
-    ) -> Result<(
-        Vec<Event>,
-        Vec<GameLineupAppearance>,
-        Vec<GameFieldingAppearance>,
-    )> {
+        rules: RulesEra,
+    ) -> Result<GameEvents> {
         let mut events: Vec<Event> = Vec::with_capacity(100);
 
-        let mut state = Self::new(record_slice)?;
+        let mut state = Self::new(record_slice, rules)?;
         for (i, record) in record_slice.iter().enumerate() {
             let event_key: i32 = event_key_offset + i32::try_from(state.event_id.get())?;
             let opt_play = match record {
@@ -1280,8 +1884,11 @@ impl GameState {
             // Unusual game state also needs to be grabbed before updating state
             let rare_attributes = state.unusual_state.clone();
 
-            state.update(record, opt_play)?;
+            state
+                .update(record, opt_play)
+                .with_context(|| RecordLine(line_offset + i))?;
             if let Some(play) = opt_play {
+                state.check_event_count()?;
                 let context = EventContext {
                     inning: state.inning,
                     batting_side: state.batting_side,
@@ -1306,6 +1913,7 @@ impl GameState {
                     play_info: EventFlag::from_play(play, event_key)?,
                     comment: state.comment_buffer,
                     fielding_plays: play.stats.fielders_data.clone(),
+                    out_fielding_plays: play.parsed.out_fielding_plays(&play.stats.outs),
                     out_on_play: play.stats.outs.clone(),
                     ending_base_state: state.bases.clone(),
                     no_play_flag: play.stats.no_play_flag,
@@ -1318,7 +1926,8 @@ impl GameState {
                     results,
                     line_number,
                     event_key,
-                    raw_play: play.raw.clone()
+                    raw_play: play.raw.clone(),
+                    raw_pitch_sequence: play.raw_pitch_sequence.clone(),
                 });
                 state.event_id += 1;
                 state.comment_buffer = vec![]; // Clear comment buffer
@@ -1332,21 +1941,38 @@ impl GameState {
             .values()
             .flatten()
             .map(|la| la.finalize(max_event_id))
-            .sorted_by_key(|la| (la.side, la.lineup_position, la.start_event_id))
+            .sorted_by_key(|la| {
+                let (start, end) = (la.start_event_id, la.end_event_id);
+                (la.side, la.lineup_position, start, end, la.player_id)
+            })
+            .zip(1..)
+            .map(|(la, appearance_id)| GameLineupAppearance { appearance_id, ..la })
             .collect_vec();
         let defense_appearances = state
             .personnel
             .defense_appearances
             .values()
             .flatten()
-            .map(|la| la.finalize(max_event_id))
-            .sorted_by_key(|la| (la.side, la.fielding_position, la.start_event_id))
+            .map(|fa| fa.finalize(max_event_id))
+            .sorted_by_key(|fa| {
+                let (start, end) = (fa.start_event_id, fa.end_event_id);
+                (fa.side, fa.fielding_position, start, end, fa.player_id)
+            })
+            .zip(1..)
+            .map(|(fa, appearance_id)| GameFieldingAppearance { appearance_id, ..fa })
             .collect_vec();
 
-        Ok((events, lineup_appearances, defense_appearances))
+        Ok((
+            events,
+            lineup_appearances,
+            defense_appearances,
+            state.personnel.dh_vacancies,
+        ))
     }
 
-    pub(crate) fn new(record_slice: &RecordSlice) -> Result<Self> {
+    /// The state before the first play, with the starting lineups taken from the
+    /// game's `start` records
+    pub fn new(record_slice: &RecordSlice, rules: RulesEra) -> Result<Self> {
         let game_id = get_game_id(record_slice)?;
         let batting_side = record_slice
             .iter()
@@ -1372,6 +1998,7 @@ impl GameState {
             personnel: Personnel::new(record_slice)?,
             unusual_state: RareAttributes::default(),
             comment_buffer: vec![],
+            rules,
         })
     }
 
@@ -1474,6 +2101,12 @@ impl GameState {
         // The extra innings runner record can appear before or after the first record of the next
         // inning, and it doesn't have a side associated with it, so we have to do some messy
         // state changes to get it right.
+        if !self.rules.extra_innings_runner {
+            warn!(
+                "Game {} places an extra innings runner, which the rules of {} didn't call for",
+                self.game_id.id, self.rules.season
+            );
+        }
         if self.outs == 3 {
             self.frame = self.frame.flip();
             self.batting_side = self.batting_side.flip();
@@ -1489,6 +2122,19 @@ impl GameState {
         Ok(())
     }
 
+    /// Games get a fixed range of event keys, so one with more events than that can't be
+    /// written without taking keys that belong to the next game
+    fn check_event_count(&self) -> Result<()> {
+        if self.event_id.get() > max_events_per_game() {
+            bail!(
+                "Game has more than {} events, so its event keys would overlap the next \
+                 game's. Raise the max events per game to parse it.",
+                max_events_per_game()
+            );
+        }
+        Ok(())
+    }
+
     fn update_on_comment(&mut self, comment: &str) {
         self.comment_buffer.push(comment.trim().replace('$', ""));
     }
@@ -1510,6 +2156,28 @@ impl GameState {
         Ok(())
     }
 
+    pub const fn inning(&self) -> Inning {
+        self.inning
+    }
+
+    pub const fn frame(&self) -> InningFrame {
+        self.frame
+    }
+
+    pub const fn batting_side(&self) -> Side {
+        self.batting_side
+    }
+
+    pub const fn outs(&self) -> Outs {
+        self.outs
+    }
+
+    pub const fn bases(&self) -> &BaseState {
+        &self.bases
+    }
+
+    /// Applies a single record. For a `play` record, `play` has to be the record's
+    /// parsed play, which is kept separate so it's only parsed once.
     pub fn update(&mut self, record: &MappedRecord, play: Option<&PlayRecord>) -> Result<()> {
         match record {
             // We've already pulled the play record out before the call to this function
@@ -1576,7 +2244,7 @@ impl BaseState {
             | (self.get_third().is_some() as u8) << 2
     }
 
-    fn num_runners_on_base(&self) -> usize {
+    pub fn num_runners_on_base(&self) -> usize {
         self.bases.len()
     }
 
@@ -1584,6 +2252,15 @@ impl BaseState {
         self.bases.get(baserunner)
     }
 
+    pub fn iter_runners(&self) -> impl Iterator<Item = (BaseRunner, &Runner)> {
+        self.bases.iter()
+    }
+
+    /// Runners who scored on the play that produced this state
+    pub fn scored(&self) -> &[Runner] {
+        &self.scored
+    }
+
     fn get_first(&self) -> Option<&Runner> {
         self.bases.get(BaseRunner::First)
     }
@@ -1771,8 +2448,8 @@ pub struct Runner {
 /// has at least one entry in each of its Vecs
 #[allow(clippy::pedantic, clippy::nursery, clippy::unwrap_used)]
 pub fn dummy() -> GameContext {
-    let team = ArrayString::from("ABC").unwrap();
-    let dummy_str8 = ArrayString::from("dummy").unwrap();
+    let team = Team::from_str("ABC").unwrap();
+    let dummy_str8 = Person::from_str("dummy001").unwrap();
     let dummy_str16 = ArrayString::from("dummy").unwrap();
     let dummy_datetime = NaiveDateTime::from_timestamp_opt(0, 0).unwrap();
     let dummy_base_state = BaseState {
@@ -1797,6 +2474,9 @@ pub fn dummy() -> GameContext {
             filename: ArrayString::from("dummy").unwrap(),
             account_type: AccountType::BoxScore,
             file_index: 0,
+            game_type: None,
+            season: None,
+            file_hash: 0,
         },
         metadata: GameMetadata {
             scorer: Some(dummy_str16),
@@ -1822,11 +2502,12 @@ pub fn dummy() -> GameContext {
             precipitation: Precipitation::Unknown,
             wind_direction: WindDirection::Unknown,
             season: Season(1990),
-            park_id: dummy_str16,
+            park_id: Park::from_str("ABC01").unwrap(),
             temperature_fahrenheit: Some(1),
             attendance: Some(1),
             wind_speed_mph: Some(1),
             use_dh: true,
+            forfeit: None,
         },
         umpires: vec![GameUmpire {
             game_id: ArrayString::from("dummy").unwrap(),
@@ -1854,6 +2535,7 @@ pub fn dummy() -> GameContext {
             entered_game_as: EnteredGameAs::Starter,
             start_event_id: EventId::new(1).unwrap(),
             end_event_id: Some(EventId::new(1).unwrap()),
+            appearance_id: 1,
         }],
         fielding_appearances: vec![GameFieldingAppearance {
             game_id: ArrayString::from("dummy").unwrap(),
@@ -1862,6 +2544,16 @@ pub fn dummy() -> GameContext {
             side: Side::Away,
             start_event_id: EventId::new(1).unwrap(),
             end_event_id: Some(EventId::new(1).unwrap()),
+            appearance_id: 1,
+        }],
+        dh_vacancies: vec![GameDhVacancy {
+            game_id: ArrayString::from("dummy").unwrap(),
+            side: Side::Away,
+            event_id: EventId::new(1).unwrap(),
+            pitcher_id: dummy_str8,
+            lineup_position: LineupPosition::Ninth,
+            designated_hitter_id: Some(dummy_str8),
+            non_batting_pitcher_id: Some(dummy_str8),
         }],
         events: vec![Event {
             game_id: GameId {
@@ -1926,12 +2618,16 @@ pub fn dummy() -> GameContext {
                     event_key: 1,
                     sequence_id: SequenceId::new(1).unwrap(),
                     flag: String::from("dummy"),
+                    flag_type: EventFlagType::Unknown,
+                    base: None,
+                    fielding_position: None,
                 }],
                 comment: vec![String::from("dummy")],
                 fielding_plays: vec![FieldersData {
                     fielding_position: FieldingPosition::Pitcher,
                     fielding_play_type: FieldingPlayType::Assist,
                 }],
+                out_fielding_plays: vec![],
                 out_on_play: vec![BaseRunner::Batter],
                 ending_base_state: dummy_base_state.clone(),
                 no_play_flag: false,
@@ -1939,14 +2635,105 @@ pub fn dummy() -> GameContext {
             line_number: 1,
             event_key: 2,
             raw_play: Arc::new(String::from("dummy")),
+            raw_pitch_sequence: Arc::new(String::from("dummy")),
         }],
         line_offset: 1,
         event_key_offset: 3,
+        content_hash: 0,
         box_score_data: Some(BoxScoreData {
             lines: vec![],
             events: vec![],
             line_scores: vec![],
             comments: vec![],
         }),
+        rules: RulesEra::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_file::builder::GameBuilder;
+
+    /// Nine innings of strikeouts, with a home run to win it in the bottom of the ninth
+    /// if `walk_off` is set
+    fn scoreless_game(date: &str, infos: &[(&str, &str)], walk_off: bool) -> GameContext {
+        let mut game = GameBuilder::new(&format!("BOS{}0", date.replace('/', "")))
+            .info("visteam", "NYA")
+            .info("hometeam", "BOS")
+            .info("date", date);
+        for (key, value) in infos {
+            game = game.info(key, value);
+        }
+        for (side, team) in [(Side::Away, "nya"), (Side::Home, "bos")] {
+            for slot in 1..=9 {
+                game = game.start(&format!("{team}{slot:05}"), side, slot, slot);
+            }
+        }
+        let mut batters = Matchup::new((1..=9).cycle(), (1..=9).cycle());
+        for inning in 1..=9 {
+            for (side, team) in [(Side::Away, "nya"), (Side::Home, "bos")] {
+                let plays = if walk_off && inning == 9 && side == Side::Home {
+                    &["HR/F"][..]
+                } else {
+                    &["K"; 3][..]
+                };
+                for play in plays {
+                    let slot = batters.get_mut(side).next().unwrap_or_default();
+                    game = game.play(inning, side, &format!("{team}{slot:05}"), "00", "X", play);
+                }
+            }
+        }
+        game.build().unwrap()
+    }
+
+    #[test]
+    fn games_called_tied_before_2007_are_ties() {
+        let gc = scoreless_game("2000/04/01", &[], false);
+        assert_eq!(gc.outcome(), Some(GameOutcome::Tie));
+    }
+
+    #[test]
+    fn tied_games_are_suspended_from_2007() {
+        let gc = scoreless_game("2010/04/01", &[], false);
+        assert_eq!(gc.outcome(), Some(GameOutcome::SuspendedIncomplete));
+    }
+
+    #[test]
+    fn suspended_games_are_only_ties_without_their_finish() {
+        let completion = [("completion", "20000402,BOS07,0,0,54")];
+        let gc = scoreless_game("2000/04/01", &completion, false);
+        assert_eq!(gc.outcome(), Some(GameOutcome::SuspendedIncomplete));
+        let gc = scoreless_game("2000/04/01", &completion, true);
+        assert_eq!(gc.outcome(), Some(GameOutcome::HomeWin));
+    }
+
+    #[test]
+    fn forfeits_come_from_the_info_record() {
+        let gc = scoreless_game("2000/04/01", &[("forfeit", "V")], true);
+        assert_eq!(gc.outcome(), Some(GameOutcome::Forfeit));
+        let mut gc = scoreless_game("2000/04/01", &[], true);
+        gc.events[0].results.comment = vec![String::from("Fans threatened a forfeit")];
+        assert_eq!(gc.outcome(), Some(GameOutcome::HomeWin));
+    }
+
+    #[test]
+    fn indoor_flag_goes_by_the_park_and_then_the_sky() {
+        Roof::init().unwrap();
+        let indoor_flag = |park_id: &str, sky: Sky| {
+            let setting = GameSetting {
+                park_id: Park::from_raw(park_id).unwrap(),
+                sky,
+                ..GameSetting::default()
+            };
+            setting.indoor_flag()
+        };
+        assert_eq!(indoor_flag("STP01", Sky::Sunny), Some(true));
+        assert_eq!(indoor_flag("BOS07", Sky::Dome), Some(false));
+        assert_eq!(indoor_flag("BOS07", Sky::Unknown), Some(false));
+        assert_eq!(indoor_flag("TOR02", Sky::Dome), Some(true));
+        assert_eq!(indoor_flag("TOR02", Sky::Sunny), Some(false));
+        assert_eq!(indoor_flag("TOR02", Sky::Unknown), None);
+        assert_eq!(indoor_flag("", Sky::Dome), Some(true));
     }
 }
@@ -1,7 +1,8 @@
 use std::convert::TryFrom;
+use std::fmt;
 use std::str::FromStr;
 
-use anyhow::{bail, Error, Result};
+use anyhow::{anyhow, bail, Error, Result};
 use arrayvec::ArrayString;
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
@@ -9,7 +10,7 @@ use strum_macros::{AsRefStr, Display, EnumString};
 
 use crate::event_file::misc::{parse_non_negative_int, parse_positive_int, str_to_tinystr};
 use crate::event_file::traits::{
-    Player, RetrosheetEventRecord, RetrosheetVolunteer, Scorer, Umpire,
+    Person, Player, RetrosheetEventRecord, RetrosheetVolunteer, Scorer, Umpire,
 };
 
 use super::traits::GameType;
@@ -161,8 +162,155 @@ impl Default for WindDirection {
     }
 }
 
-pub type Team = ArrayString<3>;
-pub type Park = ArrayString<16>;
+/// Retrosheet team ID, e.g. `NYA`
+#[derive(
+    Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone, Serialize, Deserialize,
+)]
+#[serde(try_from = "String")]
+pub struct Team(ArrayString<3>);
+
+/// Team IDs that Retrosheet retired when a franchise moved or was renamed, paired with
+/// the ID the franchise uses now
+const FRANCHISE_PREDECESSORS: [(&str, &str); 16] = [
+    ("MLA", "BAL"),
+    ("SLA", "BAL"),
+    ("BLA", "NYA"),
+    ("BSN", "ATL"),
+    ("MLN", "ATL"),
+    ("BRO", "LAN"),
+    ("NY1", "SFN"),
+    ("PHA", "OAK"),
+    ("KC1", "OAK"),
+    ("WS1", "MIN"),
+    ("WS2", "TEX"),
+    ("SE1", "MIL"),
+    ("MON", "WAS"),
+    ("FLO", "MIA"),
+    ("LAA", "ANA"),
+    ("CAL", "ANA"),
+];
+
+impl Team {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Keeps an ID as it was written, as long as it fits, for info records where a
+    /// nonstandard value shouldn't fail the game
+    pub fn from_raw(s: &str) -> Result<Self> {
+        ArrayString::from(s)
+            .map(Self)
+            .map_err(|_| anyhow!("Team ID {s} is longer than 3 characters"))
+    }
+
+    /// Whether the ID has the standard Retrosheet form
+    pub fn is_standard(&self) -> bool {
+        Self::is_valid(self.as_str())
+    }
+
+    fn is_valid(s: &str) -> bool {
+        s.len() == 3
+            && s.bytes()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+    }
+
+    /// The team's current ID, so that seasons before a move line up with the ones after.
+    /// Teams without a predecessor entry are their own franchise.
+    pub fn franchise(self) -> Self {
+        FRANCHISE_PREDECESSORS
+            .iter()
+            .find(|(old, _)| *old == self.as_str())
+            .and_then(|(_, new)| Self::from_str(new).ok())
+            .unwrap_or(self)
+    }
+}
+
+impl FromStr for Team {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if !Self::is_valid(s) {
+            bail!("Invalid Retrosheet team ID: {s}")
+        }
+        ArrayString::from(s)
+            .map(Self)
+            .map_err(|_| anyhow!("Invalid Retrosheet team ID: {s}"))
+    }
+}
+
+impl TryFrom<String> for Team {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        Self::from_raw(&s)
+    }
+}
+
+impl fmt::Display for Team {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Retrosheet park ID, e.g. `NYC16`: a three-character city code and a two-digit number.
+/// Some old games have a blank or nonstandard site, which is kept as written.
+#[derive(
+    Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone, Serialize, Deserialize,
+)]
+#[serde(try_from = "String")]
+pub struct Park(ArrayString<16>);
+
+impl Park {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn from_raw(s: &str) -> Result<Self> {
+        ArrayString::from(s)
+            .map(Self)
+            .map_err(|_| anyhow!("Park ID {s} is longer than 16 characters"))
+    }
+
+    /// Whether the ID has the standard Retrosheet form
+    pub fn is_standard(&self) -> bool {
+        Self::is_valid(self.as_str())
+    }
+
+    fn is_valid(s: &str) -> bool {
+        s.len() == 5
+            && s.bytes()
+                .take(3)
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+            && s.bytes().skip(3).all(|b| b.is_ascii_digit())
+    }
+}
+
+impl FromStr for Park {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if !Self::is_valid(s) {
+            bail!("Invalid Retrosheet park ID: {s}")
+        }
+        ArrayString::from(s)
+            .map(Self)
+            .map_err(|_| anyhow!("Invalid Retrosheet park ID: {s}"))
+    }
+}
+
+impl TryFrom<String> for Park {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        Self::from_raw(&s)
+    }
+}
+
+impl fmt::Display for Park {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 
 #[derive(
     Debug,
@@ -280,10 +428,59 @@ pub enum UmpirePosition {
     RightField,
 }
 
+/// Spellings of an umpire field meaning that nobody umpired at the position, which is
+/// the norm for the outfield positions. Compared case-insensitively.
+pub const NO_UMPIRE_STRINGS: [&str; 6] = ["", "none", "(none)", "nobody", "n/a", "-"];
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
 pub struct UmpireAssignment {
     pub position: UmpirePosition,
     pub umpire: Option<Umpire>,
+    /// There was an umpire at the position, but their ID is unknown. Besides the usual
+    /// `unknown`, any value that is neither a valid ID nor one of the `NO_UMPIRE_STRINGS`
+    /// counts, since old files use several spellings (`unk`, `?`, misspelled IDs).
+    pub identity_unknown: bool,
+}
+
+impl UmpireAssignment {
+    fn new(position: UmpirePosition, value: &str) -> Self {
+        let umpire = Person::from_str(value).ok();
+        let is_vacant = NO_UMPIRE_STRINGS
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(value.trim()));
+        Self {
+            position,
+            umpire,
+            identity_unknown: umpire.is_none() && !is_vacant,
+        }
+    }
+}
+
+/// The ruling on a forfeited game, coded as in the Retrosheet game logs
+#[derive(
+    Debug,
+    Eq,
+    PartialEq,
+    EnumString,
+    Copy,
+    Clone,
+    Display,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+    AsRefStr,
+)]
+pub enum Forfeit {
+    /// Awarded to the home team
+    #[strum(serialize = "V")]
+    VisitorForfeited,
+    /// Awarded to the visiting team
+    #[strum(serialize = "H")]
+    HomeForfeited,
+    /// Ruled a no-decision
+    #[strum(serialize = "T")]
+    NoDecision,
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
@@ -320,6 +517,10 @@ pub enum InfoRecord {
     InputDate(Option<NaiveDateTime>),
     EditDate(Option<NaiveDateTime>),
     Tiebreaker,
+    Forfeit(Forfeit),
+    /// The game was suspended and finished on a later date, which the record gives
+    /// along with the score and outs at the time of the suspension
+    Completion(Option<ArrayString<64>>),
     // We currently don't parse umpire changes as they only occur in box scores
     // and are irregularly shaped
     UmpireChange,
@@ -347,26 +548,25 @@ impl InfoRecord {
 impl TryFrom<&RetrosheetEventRecord> for InfoRecord {
     type Error = Error;
 
-    fn try_from(record: &RetrosheetEventRecord) -> Result<Self> {
-        type I = InfoRecord;
-        let record = record.deserialize::<[&str; 3]>(None)?;
+    fn try_from(raw_record: &RetrosheetEventRecord) -> Result<Self> {
+        let record = raw_record.deserialize::<[&str; 3]>(None)?;
 
         let info_type = record[1];
         let value = record[2];
 
-        let t8 = { || str_to_tinystr::<ArrayString<8>>(value) };
+        let t8 = { || Person::from_str(value) };
         let t16 = { || str_to_tinystr::<ArrayString<16>>(value) };
 
         let info = match info_type {
-            "visteam" => Self::VisitingTeam(str_to_tinystr(value)?),
-            "hometeam" => Self::HomeTeam(str_to_tinystr(value)?),
-            "site" => Self::Park(str_to_tinystr(value)?),
+            "visteam" => Self::VisitingTeam(Team::from_raw(value)?),
+            "hometeam" => Self::HomeTeam(Team::from_raw(value)?),
+            "site" => Self::Park(Park::from_raw(value)?),
 
             "umphome" | "ump1b" | "ump2b" | "ump3b" | "umplf" | "umprf" => {
-                Self::UmpireAssignment(UmpireAssignment {
-                    position: UmpirePosition::from_str(info_type)?,
-                    umpire: t8().ok(),
-                })
+                Self::UmpireAssignment(UmpireAssignment::new(
+                    UmpirePosition::from_str(info_type)?,
+                    value,
+                ))
             }
 
             "number" => Self::DoubleheaderStatus(DoubleheaderStatus::from_str(value)?),
@@ -402,6 +602,11 @@ impl TryFrom<&RetrosheetEventRecord> for InfoRecord {
             "inputtime" => Self::InputDate(Self::parse_datetime(value)),
             "edittime" => Self::EditDate(Self::parse_datetime(value)),
             "tiebreaker" => Self::Tiebreaker,
+            "forfeit" => Self::Forfeit(Forfeit::from_str(value)?),
+            "completion" => {
+                let completion = raw_record.iter().skip(2).collect::<Vec<_>>().join(",");
+                Self::Completion(str_to_tinystr(&completion).ok())
+            }
             "inputprogvers" => Self::InputProgramVersion,
             "umpchange" => Self::UmpireChange,
             _ => Self::Unrecognized,
@@ -412,3 +617,39 @@ impl TryFrom<&RetrosheetEventRecord> for InfoRecord {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn team_ids_are_parsed_strictly_and_read_leniently() {
+        assert_eq!(Team::from_str("NYA").unwrap().as_str(), "NYA");
+        assert!(Team::from_str("nya").is_err());
+        assert!(Team::from_str("NY").is_err());
+        let lowercase = Team::from_raw("nya").unwrap();
+        assert_eq!(lowercase.as_str(), "nya");
+        assert!(!lowercase.is_standard());
+        assert!(Team::from_raw("NYAX").is_err());
+        assert!(Team::try_from(String::from("ny")).is_ok());
+    }
+
+    #[test]
+    fn moved_teams_map_to_their_franchise() {
+        let team = |s| Team::from_str(s).unwrap();
+        assert_eq!(team("SLA").franchise(), team("BAL"));
+        assert_eq!(team("BAL").franchise(), team("BAL"));
+        assert_eq!(team("BOS").franchise(), team("BOS"));
+    }
+
+    #[test]
+    fn park_ids_are_parsed_strictly_and_read_leniently() {
+        assert!(Park::from_str("NYC16").unwrap().is_standard());
+        assert!(Park::from_str("NYC1").is_err());
+        assert!(Park::from_str("NYCAB").is_err());
+        let named = Park::from_raw("Polo Grounds").unwrap();
+        assert_eq!(named.as_str(), "Polo Grounds");
+        assert!(!named.is_standard());
+        assert!(Park::from_raw("A park with a long name").is_err());
+    }
+}
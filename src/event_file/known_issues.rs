@@ -0,0 +1,114 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, bail, Result};
+use csv::ReaderBuilder;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::event_file::game_state::EventId;
+use crate::event_file::schemas::GameIdString;
+
+// Curated list of games/fields known to be wrong in the source data. Matching rows get
+// tagged with the issue ID so users don't have to rediscover the problem themselves.
+const KNOWN_ISSUES_CSV: &str = include_str!("../../data/known_issues.csv");
+
+lazy_static! {
+    static ref KNOWN_ISSUES: Result<HashMap<GameIdString, Vec<KnownIssue>>> =
+        KnownIssue::registry();
+}
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Serialize, Deserialize)]
+pub enum KnownIssueType {
+    MissingParkId,
+    DuplicateBoxScoreLine,
+    InvalidUmpire,
+    Other,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct KnownIssue {
+    #[serde(rename = "known_issue_id")]
+    pub id: String,
+    pub game_id: GameIdString,
+    /// If empty, the issue applies to the game as a whole
+    pub event_id: Option<EventId>,
+    pub issue_type: KnownIssueType,
+    pub description: String,
+}
+
+impl KnownIssue {
+    fn parse_registry(raw: &str) -> Result<Vec<Self>> {
+        ReaderBuilder::new()
+            .from_reader(raw.as_bytes())
+            .deserialize()
+            .map(|r| r.map_err(Into::into))
+            .collect()
+    }
+
+    fn registry() -> Result<HashMap<GameIdString, Vec<Self>>> {
+        let mut registry: HashMap<GameIdString, Vec<Self>> = HashMap::new();
+        let mut ids = HashSet::new();
+        for issue in Self::parse_registry(KNOWN_ISSUES_CSV)? {
+            if !ids.insert(issue.id.clone()) {
+                bail!("Known issue {} is listed more than once", issue.id);
+            }
+            registry.entry(issue.game_id).or_default().push(issue);
+        }
+        Ok(registry)
+    }
+
+    /// Checks that the registry can be read, so that a bad entry fails the run up front
+    /// instead of leaving the issue columns empty
+    pub fn init() -> Result<()> {
+        KNOWN_ISSUES
+            .as_ref()
+            .map(|_| ())
+            .map_err(|e| anyhow!("Invalid known issue registry: {e:?}"))
+    }
+
+    /// Issues that don't match anything in the parsed games, given the number of events
+    /// in each. Only issues from seasons that were parsed are checked, so that running on
+    /// part of the data doesn't flag the rest of the registry.
+    pub fn unresolved(event_counts: &HashMap<GameIdString, usize>) -> Vec<&'static Self> {
+        let season = |game_id: &GameIdString| game_id.get(3..7).map(str::to_string);
+        let seasons = event_counts
+            .keys()
+            .filter_map(season)
+            .collect::<HashSet<_>>();
+        let Ok(registry) = KNOWN_ISSUES.as_ref() else {
+            return vec![];
+        };
+        registry
+            .values()
+            .flatten()
+            .filter(|i| season(&i.game_id).is_some_and(|s| seasons.contains(&s)))
+            .filter(|i| match (event_counts.get(&i.game_id), i.event_id) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(count), Some(event_id)) => event_id.get() > *count,
+            })
+            .collect()
+    }
+
+    /// The first game-level issue for the game, if any
+    pub fn for_game(game_id: &GameIdString) -> Option<&'static str> {
+        KNOWN_ISSUES
+            .as_ref()
+            .ok()?
+            .get(game_id)?
+            .iter()
+            .find(|i| i.event_id.is_none())
+            .map(|i| i.id.as_str())
+    }
+
+    /// The first issue specific to the event, if any
+    pub fn for_event(game_id: &GameIdString, event_id: EventId) -> Option<&'static str> {
+        KNOWN_ISSUES
+            .as_ref()
+            .ok()?
+            .get(game_id)?
+            .iter()
+            .find(|i| i.event_id == Some(event_id))
+            .map(|i| i.id.as_str())
+    }
+}
@@ -4,15 +4,18 @@ use std::str::FromStr;
 
 use anyhow::{anyhow, Error, Result};
 use bimap::BiMap;
+use chrono::NaiveDate;
 use num_traits::PrimInt;
 use regex::{Match, Regex};
 use serde::{Deserialize, Serialize};
 use strum_macros::{AsRefStr, EnumString};
 use tracing::warn;
 
+use crate::event_file::info::Team;
 use crate::event_file::play::Base;
 use crate::event_file::traits::{
-    Batter, Fielder, FieldingPosition, LineupPosition, Pitcher, Player, RetrosheetEventRecord, Side,
+    Batter, Fielder, FieldingPosition, LineupPosition, Person, Pitcher, Player,
+    RetrosheetEventRecord, Side,
 };
 
 use super::play::BaseRunner;
@@ -20,10 +23,74 @@ use super::schemas::GameIdString;
 
 pub type Comment = String;
 
+/// Rough subject of a free-text comment, found by keyword matching. Categories are
+/// checked in order, so a comment about an ejection after a replay review is an ejection.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Serialize, Deserialize, AsRefStr)]
+pub enum CommentCategory {
+    Ejection,
+    Replay,
+    Injury,
+    ScoringDecision,
+    Trivia,
+    Other,
+}
+
+impl CommentCategory {
+    const KEYWORDS: [(Self, &'static [&'static str]); 5] = [
+        (Self::Ejection, &["eject", "thrown out of the game"]),
+        (
+            Self::Replay,
+            &["replay", "review", "challenge", "overturned", "call stands", "call upheld"],
+        ),
+        (
+            Self::Injury,
+            &["injur", "hurt", "left the game", "carried off", "sprain", "strain", "cramp"],
+        ),
+        (
+            Self::ScoringDecision,
+            &["scorer", "scoring", "scored as", "ruled", "charged with", "changed to"],
+        ),
+        (
+            Self::Trivia,
+            &[
+                "first major league",
+                "career",
+                "debut",
+                "milestone",
+                "set a record",
+                "tied a record",
+                "club record",
+                "league record",
+            ],
+        ),
+    ];
+
+    pub fn classify(comment: &str) -> Self {
+        let comment = comment.to_lowercase();
+        Self::KEYWORDS
+            .iter()
+            .find(|(_, keywords)| keywords.iter().any(|k| comment.contains(k)))
+            .map_or(Self::Other, |(category, _)| *category)
+    }
+}
+
 /// Indicates the hands that the batter/pitcher are using. For the most part, this is not given
 /// explicitly, but occasionally the batter bats from a different side than his roster data
 /// indicates, and under very rare circumstances the pitcher can switch.
-#[derive(Debug, Eq, PartialEq, EnumString, Copy, Clone, Serialize, Deserialize, AsRefStr)]
+#[derive(
+    Debug,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    EnumString,
+    Copy,
+    Clone,
+    Serialize,
+    Deserialize,
+    AsRefStr,
+)]
 pub enum Hand {
     #[strum(serialize = "L")]
     Left,
@@ -42,6 +109,75 @@ impl Default for Hand {
 pub struct GameId {
     pub id: GameIdString,
 }
+
+/// IDs are of the form `TTTYYYYMMDDN`: the home team, the date, and the game number.
+/// None of these are guaranteed to agree with the game's info records.
+impl GameId {
+    pub fn home_team(&self) -> Option<Team> {
+        self.id.get(..3).and_then(|s| Team::from_str(s).ok())
+    }
+
+    pub fn date(&self) -> Option<NaiveDate> {
+        self.id
+            .get(3..11)
+            .and_then(|s| NaiveDate::parse_from_str(s, "%Y%m%d").ok())
+    }
+
+    /// 0 for a single game, otherwise the game's place in a doubleheader
+    pub fn game_number(&self) -> Option<u8> {
+        self.id.get(11..).and_then(|s| s.parse().ok())
+    }
+}
+
+/// Outs recorded written the conventional way, with the digit after the point counting
+/// outs rather than tenths of an inning, so 20 outs is `6.2`
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct InningsPitched {
+    pub outs: u16,
+}
+
+impl InningsPitched {
+    pub const fn from_outs(outs: u16) -> Self {
+        Self { outs }
+    }
+}
+
+impl std::fmt::Display for InningsPitched {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.outs / 3, self.outs % 3)
+    }
+}
+
+impl FromStr for InningsPitched {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (innings, outs) = s.split_once('.').unwrap_or((s, "0"));
+        let (innings, outs) = (innings.parse::<u16>()?, outs.parse::<u16>()?);
+        innings
+            .checked_mul(3)
+            .and_then(|i| i.checked_add(outs))
+            .filter(|_| outs < 3)
+            .map(Self::from_outs)
+            .ok_or_else(|| anyhow!("Invalid innings pitched: {s}"))
+    }
+}
+
+impl From<InningsPitched> for String {
+    fn from(ip: InningsPitched) -> Self {
+        ip.to_string()
+    }
+}
+
+impl TryFrom<String> for InningsPitched {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        Self::from_str(&s)
+    }
+}
+
 impl TryFrom<&RetrosheetEventRecord> for GameId {
     type Error = Error;
 
@@ -68,7 +204,7 @@ impl TryFrom<&RetrosheetEventRecord> for HandAdjustment {
         let record = record.deserialize::<[&str; 3]>(None)?;
 
         Ok(Self {
-            player_id: str_to_tinystr(record[1])?,
+            player_id: Person::from_raw(record[1])?,
             hand: Hand::from_str(record[2])?,
         })
     }
@@ -108,7 +244,7 @@ impl TryFrom<&RetrosheetEventRecord> for AppearanceRecord {
     fn try_from(record: &RetrosheetEventRecord) -> Result<Self> {
         let record = record.deserialize::<[&str; 6]>(None)?;
         Ok(Self {
-            player: str_to_tinystr(record[1])?,
+            player: Person::from_raw(record[1])?,
             player_name: record[2].to_string(),
             side: Side::from_str(record[3])?,
             lineup_position: LineupPosition::try_from(record[4])?,
@@ -133,7 +269,7 @@ impl TryFrom<&RetrosheetEventRecord> for EarnedRunRecord {
         let arr = record.deserialize::<[&str; 4]>(None)?;
         match arr[1] {
             "er" => Ok(Self {
-                pitcher_id: str_to_tinystr(arr[2])?,
+                pitcher_id: Person::from_raw(arr[2])?,
                 earned_runs: arr[3].trim_end().parse::<u8>()?,
             }),
             _ => Err(anyhow!("Unexpected `data` type value {:?}", record)),
@@ -155,7 +291,7 @@ impl TryFrom<&RetrosheetEventRecord> for RunnerAdjustment {
         let record = record.deserialize::<[&str; 3]>(None)?;
 
         Ok(Self {
-            runner_id: str_to_tinystr(record[1])?,
+            runner_id: Person::from_raw(record[1])?,
             base: Base::from_str(record[2])?,
         })
     }
@@ -174,7 +310,7 @@ impl TryFrom<&RetrosheetEventRecord> for PitcherResponsibilityAdjustment {
         let record = record.deserialize::<[&str; 3]>(None)?;
 
         Ok(Self {
-            pitcher_id: str_to_tinystr(record[1])?,
+            pitcher_id: Person::from_raw(record[1])?,
             baserunner: BaseRunner::from_str(record[2])?,
         })
     }
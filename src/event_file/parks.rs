@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use csv::ReaderBuilder;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+use crate::event_file::info::Park;
+
+// Parks that have a roof, by Retrosheet park ID. Olympic Stadium's roof could in theory
+// be opened, but it hardly ever was, so it's listed as a dome.
+const PARKS_CSV: &str = include_str!("../../data/parks.csv");
+
+lazy_static! {
+    static ref ROOFS: Result<HashMap<Park, Roof>> = Roof::registry();
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Deserialize)]
+pub enum Roof {
+    Open,
+    Dome,
+    Retractable,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParkRoof {
+    park_id: Park,
+    roof: Roof,
+}
+
+impl Roof {
+    fn registry() -> Result<HashMap<Park, Self>> {
+        ReaderBuilder::new()
+            .from_reader(PARKS_CSV.as_bytes())
+            .deserialize()
+            .map(|r| r.map(|p: ParkRoof| (p.park_id, p.roof)).map_err(Into::into))
+            .collect()
+    }
+
+    /// Checks that the park table can be read, so that a bad entry fails the run up front
+    /// instead of treating every park as open
+    pub fn init() -> Result<()> {
+        ROOFS
+            .as_ref()
+            .map(|_| ())
+            .map_err(|e| anyhow!("Invalid park table: {e:?}"))
+    }
+
+    /// The park's roof. Parks that aren't in the table are open to the sky.
+    pub fn of(park_id: &Park) -> Self {
+        ROOFS
+            .as_ref()
+            .ok()
+            .and_then(|roofs| roofs.get(park_id))
+            .copied()
+            .unwrap_or(Self::Open)
+    }
+}
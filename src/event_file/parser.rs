@@ -1,17 +1,19 @@
 use std::convert::TryFrom;
 use std::fs::File;
-use std::io::BufReader;
+use std::hash::Hasher;
+use std::io::{BufRead, BufReader, Cursor, Read};
 use std::path::{Path, PathBuf};
 
-use anyhow::{anyhow, Context, Error, Result};
+use anyhow::{anyhow, bail, Context, Error, Result};
 use arrayvec::ArrayString;
 use csv::{Reader, ReaderBuilder, StringRecord};
+use flate2::read::MultiGzDecoder;
 use glob::{glob, Paths, PatternError};
 use lazy_regex::{regex, Lazy};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use strum_macros::AsRefStr;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::event_file::box_score::{BoxScoreEvent, BoxScoreLine, LineScore};
 use crate::event_file::info::InfoRecord;
@@ -20,10 +22,91 @@ use crate::event_file::misc::{
     PitcherResponsibilityAdjustment, RunnerAdjustment, StartRecord, SubstitutionRecord,
 };
 use crate::event_file::play::PlayRecord;
-use crate::event_file::traits::RetrosheetEventRecord;
+use crate::event_file::traits::{GameType, RetrosheetEventRecord};
 
 pub type RecordSlice = [MappedRecord];
 
+/// Opens an input file for reading, decompressing it on the fly if it ends in `.gz`
+pub fn open_input(path: impl AsRef<Path>) -> Result<InputReader> {
+    let path = path.as_ref();
+    Ok(InputReader::new(path, File::open(path)?))
+}
+
+/// Reads an input file into memory, returning a reader over it along with the hash of
+/// its bytes as they are on disk. Event files are small, so this saves going back to
+/// the disk to hash a file that has to be read in full anyway.
+fn open_input_with_hash(path: impl AsRef<Path>) -> Result<(InputReader, u64)> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path)?;
+    let mut hasher = StableHasher::default();
+    hasher.write(&bytes);
+    Ok((InputReader::new(path, Cursor::new(bytes)), hasher.finish()))
+}
+
+/// Reads an input file a line at a time, passing valid UTF-8 through unchanged. Some
+/// old files have accented names in Latin-1, which would otherwise fail the whole file,
+/// so lines that aren't valid UTF-8 are decoded as Latin-1 with a warning instead.
+pub struct InputReader {
+    inner: Box<dyn BufRead + Send>,
+    path: String,
+    line: Vec<u8>,
+    position: usize,
+    line_number: usize,
+    non_utf8_lines: usize,
+}
+
+impl InputReader {
+    fn new(path: &Path, source: impl Read + Send + 'static) -> Self {
+        let inner: Box<dyn BufRead + Send> = if path.extension().is_some_and(|e| e == "gz") {
+            Box::new(BufReader::new(MultiGzDecoder::new(source)))
+        } else {
+            Box::new(BufReader::new(source))
+        };
+        Self {
+            inner,
+            path: path.display().to_string(),
+            line: Vec::new(),
+            position: 0,
+            line_number: 0,
+            non_utf8_lines: 0,
+        }
+    }
+
+    /// Lines decoded as Latin-1 so far
+    pub const fn non_utf8_lines(&self) -> usize {
+        self.non_utf8_lines
+    }
+}
+
+impl Read for InputReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.position >= self.line.len() {
+            self.line.clear();
+            self.position = 0;
+            if self.inner.read_until(b'\n', &mut self.line)? == 0 {
+                return Ok(0);
+            }
+            self.line_number += 1;
+            if std::str::from_utf8(&self.line).is_err() {
+                warn!(
+                    "Line {} of {} is not valid UTF-8, decoding it as Latin-1",
+                    self.line_number, self.path
+                );
+                self.non_utf8_lines += 1;
+                self.line = self
+                    .line
+                    .iter()
+                    .map(|&b| char::from(b))
+                    .collect::<String>()
+                    .into_bytes();
+            }
+        }
+        let n = (&self.line[self.position..]).read(buf)?;
+        self.position += n;
+        Ok(n)
+    }
+}
+
 pub static ALL_STAR_GAME: &Lazy<Regex> = regex!(r"[0-9]{4}AS\.EVE$");
 pub static WORLD_SERIES: &Lazy<Regex> = regex!(r"[0-9]{4}WS\.EVE$");
 pub static LCS: &Lazy<Regex> = regex!(r"[0-9]{4}[AN]LCS\.EVE$");
@@ -34,8 +117,15 @@ pub static NEGRO_LEAGUES: &Lazy<Regex> = regex!(r".*\.E[BV]$");
 pub static PLAY_BY_PLAY: &Lazy<Regex> = regex!(r".*\.EV[ANF]?");
 pub static DERIVED: &Lazy<Regex> = regex!(r".*\.ED[ANF]?");
 pub static BOX_SCORE: &Lazy<Regex> = regex!(r".*\.EB[ANF]?");
+/// Event file names start with the season, e.g. `2020NYA.EVA` or `2001WS.EVE`
+pub static FILE_SEASON: &Lazy<Regex> = regex!(r"^([0-9]{4})");
+/// Retrosheet distributes non-championship games in their own directories, and the
+/// files in them don't always have a `gametype` info record
+pub static EXHIBITION_DIR: &Lazy<Regex> = regex!(r"(?i)^exhibitions?$");
+pub static PRESEASON_DIR: &Lazy<Regex> = regex!(r"(?i)^(spring([-_ ]?training)?|preseason)$");
 
-#[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, AsRefStr, Deserialize)]
+/// Ordered from the most detailed account to the least
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Serialize, AsRefStr, Deserialize)]
 pub enum AccountType {
     PlayByPlay,
     Deduced,
@@ -56,6 +146,28 @@ impl AccountType {
             .to_string();
         glob(&input)
     }
+
+    /// The kind of account a single game actually contains. Files are classified by
+    /// name, but a few mix box scores in with play-by-play games, so the game's own
+    /// records take precedence when they clearly belong to the other kind.
+    pub fn for_game(file_type: Self, record_slice: &RecordSlice) -> Self {
+        let has_plays = record_slice
+            .iter()
+            .any(|r| matches!(r, MappedRecord::Play(_)));
+        let has_box_score = record_slice.iter().any(|r| {
+            matches!(
+                r,
+                MappedRecord::BoxScoreLine(_)
+                    | MappedRecord::LineScore(_)
+                    | MappedRecord::BoxScoreEvent(_)
+            )
+        });
+        match (has_plays, has_box_score) {
+            (true, _) if file_type == Self::BoxScore => Self::PlayByPlay,
+            (false, true) => Self::BoxScore,
+            _ => file_type,
+        }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize)]
@@ -63,10 +175,17 @@ pub struct FileInfo {
     pub filename: ArrayString<20>,
     pub account_type: AccountType,
     pub file_index: usize,
+    /// The game type implied by the directory the file is in, if any
+    pub game_type: Option<GameType>,
+    /// The season in the file name, if any
+    pub season: Option<u16>,
+    /// Hash of the file's bytes as they are on disk, to tell apart versions of a file
+    /// with the same name
+    pub file_hash: u64,
 }
 
 impl FileInfo {
-    fn new(path: &Path, file_index: usize) -> Result<Self> {
+    fn new(path: &Path, file_index: usize, file_hash: u64) -> Result<Self> {
         let raw_filename = path
             .file_name()
             .unwrap_or_default()
@@ -79,9 +198,27 @@ impl FileInfo {
             filename,
             account_type: Self::account_type(&raw_filename),
             file_index,
+            game_type: Self::game_type(path),
+            season: Self::season(&raw_filename),
+            file_hash,
         })
     }
 
+    fn season(filename: &str) -> Option<u16> {
+        FILE_SEASON.captures(filename)?.get(1)?.as_str().parse().ok()
+    }
+
+    fn game_type(path: &Path) -> Option<GameType> {
+        path.parent()?
+            .components()
+            .rev()
+            .find_map(|c| match c.as_os_str().to_str() {
+                Some(dir) if EXHIBITION_DIR.is_match(dir) => Some(GameType::Exhibition),
+                Some(dir) if PRESEASON_DIR.is_match(dir) => Some(GameType::Preseason),
+                _ => None,
+            })
+    }
+
     pub fn account_type(s: &str) -> AccountType {
         if PLAY_BY_PLAY.is_match(s) {
             AccountType::PlayByPlay
@@ -95,16 +232,55 @@ impl FileInfo {
     }
 }
 
+/// FNV-1a hasher, for hashes that need to be stable across runs, platforms and
+/// Rust versions (which `DefaultHasher` does not guarantee).
+#[derive(Debug, Clone, Copy)]
+pub struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = (self.0 ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+impl StableHasher {
+    /// Hashes the raw text of the record, so the result only depends on the game's
+    /// contents and not on which file it was found in or how it was parsed.
+    pub(crate) fn write_record(&mut self, record: &StringRecord) {
+        for field in record {
+            self.write(field.as_bytes());
+            self.write(b",");
+        }
+        self.write(b"\n");
+    }
+}
+
+#[allow(clippy::struct_field_names)]
 pub struct RecordVec {
     pub record_vec: Vec<MappedRecord>,
     pub line_offset: usize,
+    pub content_hash: u64,
 }
 
 pub struct RetrosheetReader {
-    reader: Reader<BufReader<File>>,
+    reader: Reader<InputReader>,
     current_record: StringRecord,
     current_game_id: GameId,
     current_record_vec: Vec<MappedRecord>,
+    current_hasher: StableHasher,
+    skip_bad_records: bool,
     pub line_offset: usize,
     pub file_info: FileInfo,
 }
@@ -118,7 +294,10 @@ impl Iterator for RetrosheetReader {
         self.line_offset += self.current_record_vec.len();
 
         let game = match did_process_full_game {
-            Err(e) => Some(Err(e)),
+            Err(e) => {
+                self.current_record_vec.clear();
+                Some(Err(e))
+            }
             Ok(true) => Some(Ok(self.current_record_vec.drain(..).collect())),
             _ if !&self.current_record_vec.is_empty() => {
                 Some(Ok(self.current_record_vec.drain(..).collect()))
@@ -129,6 +308,7 @@ impl Iterator for RetrosheetReader {
             g.map(|v| RecordVec {
                 record_vec: v,
                 line_offset: old_offset,
+                content_hash: self.current_hasher.finish(),
             })
         })
     }
@@ -136,11 +316,12 @@ impl Iterator for RetrosheetReader {
 
 impl RetrosheetReader {
     pub fn new(path: &PathBuf, file_index: usize) -> Result<Self> {
+        let (input, file_hash) = open_input_with_hash(path)?;
         let mut reader = ReaderBuilder::new()
             .has_headers(false)
             .double_quote(false)
             .flexible(true)
-            .from_reader(BufReader::new(File::open(path)?));
+            .from_reader(input);
         let mut current_record = StringRecord::new();
         let mut line_number = 1;
         // Skip comments at top of 1991 files
@@ -159,23 +340,92 @@ impl RetrosheetReader {
             )),
         }?;
         let current_record_vec = Vec::<MappedRecord>::new();
-        let file_info = FileInfo::new(path, file_index)?;
+        let file_info = FileInfo::new(path, file_index, file_hash)?;
         Ok(Self {
             reader,
             current_record,
             current_game_id,
             current_record_vec,
+            current_hasher: StableHasher::default(),
+            skip_bad_records: false,
             file_info,
             line_offset: line_number,
         })
     }
 
+    /// Lines in the file read so far that had to be decoded as Latin-1
+    pub fn non_utf8_lines(&self) -> usize {
+        self.reader.get_ref().non_utf8_lines()
+    }
+
+    /// Drop records that fail to parse with a warning, rather than failing their game
+    #[must_use]
+    pub const fn skip_bad_records(mut self, skip: bool) -> Self {
+        self.skip_bad_records = skip;
+        self
+    }
+
+    /// Reads the records of a single game from the file without parsing the others.
+    /// Records outside the game are only checked for an `id` line, which is much cheaper
+    /// than going through the full reader when a single game needs to be inspected.
+    pub fn read_game(
+        path: &PathBuf,
+        file_index: usize,
+        game_id: &str,
+    ) -> Result<(FileInfo, RecordVec)> {
+        let (input, file_hash) = open_input_with_hash(path)?;
+        let file_info = FileInfo::new(path, file_index, file_hash)?;
+        let mut reader = ReaderBuilder::new()
+            .has_headers(false)
+            .double_quote(false)
+            .flexible(true)
+            .from_reader(input);
+        let mut record_vec = Vec::<MappedRecord>::new();
+        let mut hasher = StableHasher::default();
+        let mut line_offset = 0;
+        for (i, record) in reader.records().enumerate() {
+            let record = record?;
+            let is_id = record.get(0) == Some("id");
+            if record_vec.is_empty() {
+                if is_id && record.get(1) == Some(game_id) {
+                    line_offset = i + 1;
+                } else {
+                    continue;
+                }
+            } else if is_id || record.as_slice() == "\u{001A}" {
+                break;
+            }
+            hasher.write_record(&record);
+            record_vec.push(MappedRecord::try_from(&record).with_context(|| {
+                format!(
+                    "Error in file {} during game {game_id} -- Error reading record: {}",
+                    file_info.filename,
+                    record.iter().collect::<Vec<&str>>().join(",")
+                )
+            })?);
+        }
+        if record_vec.is_empty() {
+            bail!("Game {game_id} not found in file {}", file_info.filename);
+        }
+        Ok((
+            file_info,
+            RecordVec {
+                record_vec,
+                line_offset,
+                content_hash: hasher.finish(),
+            },
+        ))
+    }
+
     fn next_game(&mut self) -> Result<bool> {
         if self.reader.is_done() {
             return Ok(false);
         }
         self.current_record_vec
             .push(MappedRecord::GameId(self.current_game_id));
+        // The current record is still the ID line that ended the previous game
+        self.current_hasher = StableHasher::default();
+        self.current_hasher.write_record(&self.current_record);
         loop {
             let did_read = self.reader.read_record(&mut self.current_record)?;
             // Some Retrosheet files end with the "substitute" char, best to skip it
@@ -186,23 +436,64 @@ impl RetrosheetReader {
             if !did_read {
                 return Ok(false);
             }
-            let mapped_record = MappedRecord::try_from(&self.current_record);
-            match mapped_record {
+            let mapped_record = match MappedRecord::from_record(&self.current_record) {
                 Ok(MappedRecord::GameId(g)) => {
                     self.current_game_id = g;
                     return Ok(true);
                 }
-                Ok(m) => self.current_record_vec.push(m),
+                // Stray lines between or inside games shouldn't cost us the whole file
+                Ok(MappedRecord::Unrecognized) => {
+                    warn!(
+                        "Skipping unrecognized record in file {} during game {}: {}",
+                        self.file_info.filename,
+                        self.current_game_id.id,
+                        self.current_line()
+                    );
+                    MappedRecord::Unrecognized
+                }
+                Ok(m) => m,
+                Err(e) if self.skip_bad_records => {
+                    warn!(
+                        "Skipping malformed record in file {} during game {}: {} ({e})",
+                        self.file_info.filename,
+                        self.current_game_id.id,
+                        self.current_line()
+                    );
+                    MappedRecord::Unrecognized
+                }
                 Err(_) => {
-                    return Err(anyhow!(
+                    let error = anyhow!(
                         "Error file {} during game {} -- Error reading record: {}",
                         &self.file_info.filename,
                         &self.current_game_id.id,
-                        &self.current_record.iter().collect::<Vec<&str>>().join(",")
-                    ))
+                        self.current_line()
+                    );
+                    self.current_record_vec.push(MappedRecord::Unrecognized);
+                    self.skip_rest_of_game()?;
+                    return Err(error);
                 }
+            };
+            // Skipped records stay in as placeholders so that line numbers still line up
+            self.current_hasher.write_record(&self.current_record);
+            self.current_record_vec.push(mapped_record);
+        }
+    }
+
+    /// Moves past the remaining records of a game that failed, so that reading resumes
+    /// cleanly at the next one. The skipped records are still counted towards line numbers.
+    fn skip_rest_of_game(&mut self) -> Result<()> {
+        while self.reader.read_record(&mut self.current_record)? {
+            if self.current_record.get(0) == Some("id") {
+                self.current_game_id = GameId::try_from(&self.current_record)?;
+                break;
             }
+            self.current_record_vec.push(MappedRecord::Unrecognized);
         }
+        Ok(())
+    }
+
+    fn current_line(&self) -> String {
+        self.current_record.iter().collect::<Vec<&str>>().join(",")
     }
 }
 
@@ -227,10 +518,10 @@ pub enum MappedRecord {
     Unrecognized,
 }
 
-impl TryFrom<&RetrosheetEventRecord> for MappedRecord {
-    type Error = Error;
-
-    fn try_from(record: &RetrosheetEventRecord) -> Result<Self> {
+impl MappedRecord {
+    /// Like `try_from`, but returns `Unrecognized` instead of an error for unknown
+    /// record types
+    pub fn from_record(record: &RetrosheetEventRecord) -> Result<Self> {
         let line_type = record.get(0).context("No record")?;
         let mapped = match line_type {
             "id" => Self::GameId(GameId::try_from(record)?),
@@ -253,6 +544,15 @@ impl TryFrom<&RetrosheetEventRecord> for MappedRecord {
             "event" => Self::BoxScoreEvent(BoxScoreEvent::try_from(record)?),
             _ => Self::Unrecognized,
         };
+        Ok(mapped)
+    }
+}
+
+impl TryFrom<&RetrosheetEventRecord> for MappedRecord {
+    type Error = Error;
+
+    fn try_from(record: &RetrosheetEventRecord) -> Result<Self> {
+        let mapped = Self::from_record(record)?;
         match mapped {
             Self::Unrecognized => Err(anyhow!("Unrecognized record type {:?}", record)),
             _ => Ok(mapped),
@@ -77,6 +77,76 @@ impl Default for PitchType {
     }
 }
 
+impl PitchType {
+    pub const fn is_ball(self) -> bool {
+        matches!(
+            self,
+            Self::Ball | Self::IntentionalBall | Self::Pitchout | Self::AutomaticBall
+        )
+    }
+
+    /// Strikes that count regardless of the existing count. Fouls (other than
+    /// on bunts and foul tips) are handled separately since they can't be strike three.
+    pub const fn is_strike(self) -> bool {
+        matches!(
+            self,
+            Self::CalledStrike
+                | Self::StrikeUnknownType
+                | Self::SwingingStrike
+                | Self::SwingingOnPitchout
+                | Self::MissedBunt
+                | Self::FoulBunt
+                | Self::FoulTipBunt
+                | Self::FoulTip
+        )
+    }
+
+    pub const fn is_foul(self) -> bool {
+        matches!(self, Self::Foul | Self::FoulOnPitchout)
+    }
+
+    /// Whether the item counts towards a pitcher's pitch count
+    pub const fn is_pitch(self) -> bool {
+        !matches!(
+            self,
+            Self::PickoffAttemptFirst
+                | Self::PickoffAttemptSecond
+                | Self::PickoffAttemptThird
+                | Self::PlayNotInvolvingBatter
+                | Self::NoPitch
+                | Self::AutomaticBall
+                | Self::Unrecognized
+        )
+    }
+
+    /// Pitches the batter didn't swing at
+    pub const fn is_take(self) -> bool {
+        matches!(
+            self,
+            Self::Ball
+                | Self::CalledStrike
+                | Self::IntentionalBall
+                | Self::Pitchout
+                | Self::HitBatter
+        )
+    }
+
+    /// Rough stand-in for pitch location: called strikes were in the zone and taken
+    /// balls weren't. Swings and non-pitches say nothing about location.
+    pub const fn in_zone_proxy(self) -> Option<bool> {
+        match self {
+            Self::CalledStrike => Some(true),
+            Self::Ball | Self::IntentionalBall | Self::Pitchout | Self::HitBatter => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Pitch types that leave the count unknown when replaying a sequence
+    pub const fn is_unknown(self) -> bool {
+        matches!(self, Self::Unknown | Self::Unrecognized)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize, Hash)]
 pub struct PitchSequenceItem {
     pub sequence_id: SequenceId,
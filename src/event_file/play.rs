@@ -21,10 +21,10 @@ use serde::{Deserialize, Serialize};
 use strum::ParseError;
 use strum_macros::{AsRefStr, Display, EnumDiscriminants, EnumIter, EnumString};
 
-use crate::event_file::misc::{regex_split, str_to_tinystr, to_str_vec};
+use crate::event_file::misc::{regex_split, to_str_vec};
 use crate::event_file::pitch_sequence::{PitchSequence, PitchSequenceItem};
 use crate::event_file::traits::{
-    Batter, FieldingPlayType, FieldingPosition, Inning, RetrosheetEventRecord, Side,
+    Batter, FieldingPlayType, FieldingPosition, Inning, Person, RetrosheetEventRecord, Side,
 };
 
 // Sorry
@@ -164,6 +164,7 @@ pub trait FieldingData {
     Copy,
     Clone,
     Hash,
+    TryFromPrimitive,
     IntoPrimitive,
     EnumIter,
     Serialize,
@@ -259,7 +260,18 @@ impl BaseRunner {
 }
 
 #[derive(
-    Debug, Eq, PartialEq, EnumString, Copy, Clone, Ord, PartialOrd, Serialize, Deserialize, AsRefStr,
+    Debug,
+    Eq,
+    PartialEq,
+    EnumString,
+    Copy,
+    Clone,
+    Ord,
+    PartialOrd,
+    Hash,
+    Serialize,
+    Deserialize,
+    AsRefStr,
 )]
 #[strum(serialize_all = "lowercase")]
 pub enum InningFrame {
@@ -419,6 +431,19 @@ impl FieldingPlay {
             runners_out: vec![],
         }
     }
+
+    /// Each putout comes after its own assists, in the order the runners are listed. The
+    /// batter isn't listed, so when they're out too theirs is the putout after the others.
+    fn fielders_on_out(&self, baserunner: BaseRunner) -> Option<&[FieldersData]> {
+        let index = self
+            .runners_out
+            .iter()
+            .position(|br| *br == baserunner)
+            .or_else(|| (baserunner == BaseRunner::Batter).then_some(self.runners_out.len()))?;
+        self.fielders_data
+            .split_inclusive(|fd| fd.fielding_play_type == FieldingPlayType::Putout)
+            .nth(index)
+    }
 }
 
 impl Default for FieldingPlay {
@@ -1628,7 +1653,11 @@ impl Default for Trajectory {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, EnumString, Clone, Hash)]
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, EnumString, EnumDiscriminants, Clone, Hash)]
+#[strum_discriminants(
+    name(EventFlagType),
+    derive(Ord, PartialOrd, Serialize, Deserialize)
+)]
 pub enum PlayModifier {
     ContactDescription(ContactDescription),
     #[strum(serialize = "AP")]
@@ -1731,6 +1760,26 @@ impl PlayModifier {
         format!("{:?}", self)
     }
 
+    pub fn flag_type(&self) -> EventFlagType {
+        self.into()
+    }
+
+    /// The base named by a throw modifier, e.g. `TH2`
+    pub const fn flag_base(&self) -> Option<Base> {
+        match self {
+            Self::ThrowToBase(b) => *b,
+            _ => None,
+        }
+    }
+
+    /// The fielder named by an error modifier, e.g. `E6`
+    pub const fn flag_fielder(&self) -> Option<FieldingPosition> {
+        match self {
+            Self::ErrorOn(p) => Some(*p),
+            _ => None,
+        }
+    }
+
     const fn double_plays() -> [Self; 6] {
         [
             Self::BuntGroundIntoDoublePlay,
@@ -1865,6 +1914,7 @@ pub struct PlayRecord {
     pub parsed: Arc<ParsedPlay>,
     pub stats: Arc<PlayStats>,
     pub raw: Arc<String>,
+    pub raw_pitch_sequence: Arc<String>,
 }
 
 impl PlayRecord {
@@ -1918,7 +1968,7 @@ impl TryFrom<&RetrosheetEventRecord> for PlayRecord {
         Ok(Self {
             inning: record[1].parse::<Inning>()?,
             batting_side: Side::from_str(record[2])?,
-            batter: str_to_tinystr(record[3])?,
+            batter: Person::from_raw(record[3])?,
             count: Count::new(record[4]),
             pitch_sequence: {
                 match record[5] {
@@ -1928,7 +1978,8 @@ impl TryFrom<&RetrosheetEventRecord> for PlayRecord {
             },
             parsed,
             stats,
-            raw
+            raw,
+            raw_pitch_sequence: Arc::new(record[5].to_string()),
         })
     }
 }
@@ -2009,6 +2060,38 @@ impl ParsedPlay {
         }))
     }
 
+    /// The putout and assists on each runner's out, where `fielders_data` covers every
+    /// fielder on the play
+    pub fn out_fielding_plays(&self, outs: &[BaseRunner]) -> Vec<(BaseRunner, Vec<FieldersData>)> {
+        outs.iter()
+            .map(|br| (*br, self.out_fielders(*br)))
+            .collect()
+    }
+
+    fn out_fielders(&self, baserunner: BaseRunner) -> Vec<FieldersData> {
+        if let Some(ra) = self
+            .explicit_advances
+            .iter()
+            .find(|ra| ra.baserunner == baserunner && ra.is_out())
+        {
+            return ra.fielders_data();
+        }
+        self.main_plays
+            .iter()
+            .find_map(|pt| match pt {
+                PlayType::PlateAppearance(PlateAppearanceType::BattingOut(bo)) => bo
+                    .fielding_play
+                    .as_ref()?
+                    .fielders_on_out(baserunner)
+                    .map(<[FieldersData]>::to_vec),
+                PlayType::BaserunningPlay(br) if br.baserunner() == Some(baserunner) => {
+                    Some(br.fielders_data())
+                }
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
     pub fn outs(&self) -> Result<Vec<BaseRunner>> {
         let (out_advancing, safe_advancing): (Vec<RunnerAdvance>, Vec<RunnerAdvance>) =
             self.advances().partition(RunnerAdvance::is_out);
@@ -0,0 +1,296 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use csv::ReaderBuilder;
+use glob::glob;
+use itertools::Itertools;
+use lazy_regex::{regex, Lazy};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumString;
+use tracing::warn;
+
+use crate::event_file::info::Team;
+use crate::event_file::misc::Hand;
+use crate::event_file::traits::Player;
+
+pub static ROSTER_FILE: &Lazy<Regex> = regex!(r"([[:alnum:]]{3})([0-9]{4})\.ROS$");
+pub static TEAM_FILE: &Lazy<Regex> = regex!(r"^TEAM([0-9]{4})$");
+
+#[derive(Debug, Eq, PartialEq, EnumString, Copy, Clone, Serialize, Deserialize)]
+pub enum RosterHand {
+    #[strum(serialize = "L")]
+    Left,
+    #[strum(serialize = "R")]
+    Right,
+    #[strum(serialize = "B")]
+    Both,
+}
+
+/// A row of a roster file, e.g. `aaroh101,Aaron,Hank,R,R,ML1,RF`
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RosterEntry {
+    pub player_id: Player,
+    pub last_name: String,
+    pub first_name: String,
+    pub bats: Option<RosterHand>,
+    pub throws: Option<RosterHand>,
+    pub team_id: Team,
+    pub position: String,
+}
+
+impl RosterHand {
+    /// The hand used, unless it could be either
+    pub const fn single(self) -> Option<Hand> {
+        match self {
+            Self::Left => Some(Hand::Left),
+            Self::Right => Some(Hand::Right),
+            Self::Both => None,
+        }
+    }
+}
+
+impl RosterEntry {
+    pub fn full_name(&self) -> String {
+        format!("{} {}", self.first_name, self.last_name)
+    }
+}
+
+type RosterKey = (Team, u16);
+
+/// One way a player's name is spelled on their rosters, and the seasons it appears in.
+/// Every spelling of a player's name resolves to the same `canonical_name`.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Serialize, Deserialize)]
+pub struct PlayerNameResolution {
+    pub player_id: Player,
+    pub roster_name: String,
+    pub first_season: u16,
+    pub last_season: u16,
+    pub canonical_name: String,
+}
+
+/// Players on each team-season roster, read from the `TTTYYYY.ROS` files that
+/// Retrosheet distributes alongside the event files.
+#[derive(Debug, Default)]
+pub struct Rosters {
+    rosters: HashMap<RosterKey, HashMap<Player, RosterEntry>>,
+    names: HashMap<(Player, u16), String>,
+}
+
+impl Rosters {
+    pub fn read(input_prefix: &Path) -> Result<Self> {
+        let pattern = input_prefix.join("**/*.ROS");
+        let mut rosters: HashMap<RosterKey, HashMap<Player, RosterEntry>> = HashMap::new();
+        for path in glob(&pattern.to_string_lossy())? {
+            let path = path?;
+            let Some(key) = Self::roster_key(&path) else {
+                warn!("Skipping roster with unexpected name: {}", path.display());
+                continue;
+            };
+            rosters.insert(key, Self::read_file(&path)?);
+        }
+        let canonical_names: HashMap<Player, String> = Self::resolve_names(&rosters)
+            .into_iter()
+            .map(|r| (r.player_id, r.canonical_name))
+            .collect();
+        let names = rosters
+            .iter()
+            .flat_map(|((_, season), roster)| {
+                roster.keys().map(move |player_id| (*player_id, *season))
+            })
+            .filter_map(|key| Some((key, canonical_names.get(&key.0)?.clone())))
+            .collect();
+        Ok(Self { rosters, names })
+    }
+
+    /// Spellings change from roster to roster as research turns up a player's full
+    /// name, so the one on their latest roster is taken as canonical. Spellings tied
+    /// for latest are broken by the number of rosters they're on, then alphabetically.
+    fn resolve_names(
+        rosters: &HashMap<RosterKey, HashMap<Player, RosterEntry>>,
+    ) -> Vec<PlayerNameResolution> {
+        let mut spellings: HashMap<(Player, String), (u16, u16, usize)> = HashMap::new();
+        for ((_, season), roster) in rosters {
+            for entry in roster.values() {
+                let (first, last, count) = spellings
+                    .entry((entry.player_id, entry.full_name()))
+                    .or_insert((*season, *season, 0));
+                *first = (*first).min(*season);
+                *last = (*last).max(*season);
+                *count += 1;
+            }
+        }
+        let mut canonical: HashMap<Player, (u16, usize, &str)> = HashMap::new();
+        for ((player_id, name), (_, last, count)) in &spellings {
+            let candidate = (*last, *count, name.as_str());
+            canonical
+                .entry(*player_id)
+                .and_modify(|best| {
+                    // Reversed on the name so that ties go to the alphabetically first
+                    if (candidate.0, candidate.1, Reverse(candidate.2))
+                        > (best.0, best.1, Reverse(best.2))
+                    {
+                        *best = candidate;
+                    }
+                })
+                .or_insert(candidate);
+        }
+        spellings
+            .iter()
+            .map(|((player_id, name), (first_season, last_season, _))| {
+                let canonical_name = canonical
+                    .get(player_id)
+                    .map_or_else(|| name.clone(), |(_, _, c)| (*c).to_string());
+                PlayerNameResolution {
+                    player_id: *player_id,
+                    roster_name: name.clone(),
+                    first_season: *first_season,
+                    last_season: *last_season,
+                    canonical_name,
+                }
+            })
+            .sorted()
+            .collect()
+    }
+
+    fn roster_key(path: &Path) -> Option<RosterKey> {
+        let filename = path.file_name()?.to_str()?;
+        let captures = ROSTER_FILE.captures(filename)?;
+        let team = Team::from_str(captures.get(1)?.as_str()).ok()?;
+        let season = captures.get(2)?.as_str().parse().ok()?;
+        Some((team, season))
+    }
+
+    fn read_file(path: &PathBuf) -> Result<HashMap<Player, RosterEntry>> {
+        let mut reader = ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_path(path)
+            .with_context(|| format!("Failed to open roster {}", path.display()))?;
+        let mut entries = HashMap::new();
+        for record in reader.records() {
+            let record = record?;
+            let field = |i| record.get(i).unwrap_or_default().trim();
+            let (Ok(player_id), Ok(team_id)) =
+                (Player::from_str(field(0)), Team::from_str(field(5)))
+            else {
+                warn!(
+                    "Skipping invalid roster row in {}: {:?}",
+                    path.display(),
+                    record
+                );
+                continue;
+            };
+            let entry = RosterEntry {
+                player_id,
+                last_name: field(1).to_string(),
+                first_name: field(2).to_string(),
+                bats: RosterHand::from_str(field(3)).ok(),
+                throws: RosterHand::from_str(field(4)).ok(),
+                team_id,
+                position: field(6).to_string(),
+            };
+            entries.insert(player_id, entry);
+        }
+        Ok(entries)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rosters.is_empty()
+    }
+
+    /// The full roster of the team for the season, if a roster file was found
+    pub fn get(&self, team: Team, season: u16) -> Option<&HashMap<Player, RosterEntry>> {
+        self.rosters.get(&(team, season))
+    }
+
+    /// The pitcher's throwing hand on the team's roster, unless they're ambidextrous
+    pub fn pitcher_hand(&self, team: Team, player_id: Player, season: u16) -> Option<Hand> {
+        self.get(team, season)?.get(&player_id)?.throws?.single()
+    }
+
+    /// The side the batter hits from according to the team's roster. Switch hitters bat
+    /// opposite the pitcher's hand, so they can only be resolved when that's known.
+    pub fn batter_hand(
+        &self,
+        team: Team,
+        player_id: Player,
+        season: u16,
+        pitcher_hand: Option<Hand>,
+    ) -> Option<Hand> {
+        match self.get(team, season)?.get(&player_id)?.bats? {
+            RosterHand::Both => match pitcher_hand? {
+                Hand::Left => Some(Hand::Right),
+                Hand::Right => Some(Hand::Left),
+                Hand::Default => None,
+            },
+            hand => hand.single(),
+        }
+    }
+
+    /// The player's canonical name, if they're on any roster for the season. It's the
+    /// same whichever season it's asked for, even if that season's roster spells it
+    /// differently.
+    pub fn name(&self, player_id: Player, season: u16) -> Option<&str> {
+        self.names.get(&(player_id, season)).map(String::as_str)
+    }
+
+    /// Every spelling of every player's name, ordered by player
+    pub fn name_resolutions(&self) -> Vec<PlayerNameResolution> {
+        Self::resolve_names(&self.rosters)
+    }
+}
+
+/// The league of each team-season, read from the `TEAMYYYY` files that Retrosheet
+/// distributes alongside the event files, e.g. `ANA,A,Anaheim,Angels`. Negro League
+/// files use codes like `NN2` and `NAL`, which is the only way to tell their games apart
+/// since all of them share the same game type.
+#[derive(Debug, Default)]
+pub struct TeamLeagues {
+    leagues: HashMap<(Team, u16), String>,
+}
+
+impl TeamLeagues {
+    pub fn read(input_prefix: &Path) -> Result<Self> {
+        let pattern = input_prefix.join("**/TEAM*");
+        let mut leagues = HashMap::new();
+        for path in glob(&pattern.to_string_lossy())? {
+            let path = path?;
+            let Some(season) = path
+                .file_name()
+                .and_then(|f| TEAM_FILE.captures(f.to_str()?))
+                .and_then(|c| c.get(1)?.as_str().parse().ok())
+            else {
+                continue;
+            };
+            let mut reader = ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .from_path(&path)
+                .with_context(|| format!("Failed to open team file {}", path.display()))?;
+            for record in reader.records() {
+                let record = record?;
+                let field = |i| record.get(i).unwrap_or_default().trim();
+                let Ok(team) = Team::from_str(field(0)) else {
+                    warn!(
+                        "Skipping invalid team row in {}: {:?}",
+                        path.display(),
+                        record
+                    );
+                    continue;
+                };
+                if !field(1).is_empty() {
+                    leagues.insert((team, season), field(1).to_string());
+                }
+            }
+        }
+        Ok(Self { leagues })
+    }
+
+    pub fn get(&self, team: Team, season: u16) -> Option<&str> {
+        self.leagues.get(&(team, season)).map(String::as_str)
+    }
+}
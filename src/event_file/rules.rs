@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+use crate::event_file::game_state::PlateAppearanceResultType;
+use crate::event_file::info::{DoubleheaderStatus, InfoRecord};
+use crate::event_file::parser::{MappedRecord, RecordSlice};
+use crate::event_file::traits::GameType;
+
+/// The rules a game was played under, where they've changed over the years. These are
+/// the major league rules of the game's season, so they may not hold for other leagues,
+/// and anything the game's own records say explicitly takes precedence. Only rules that
+/// can be seen in the event files are covered, so limits on things the files don't
+/// record, like mound visits, are left out.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct RulesEra {
+    pub season: u16,
+    /// Innings in a regulation game, which is 7 for the doubleheaders of 2020-21 and
+    /// whatever the `innings` info record says when there is one
+    pub scheduled_innings: u8,
+    /// Whether each extra inning starts with a runner on second, as in the regular
+    /// season since 2020
+    pub extra_innings_runner: bool,
+    /// Whether a fly ball that scores a runner is exempt from being an at-bat. The rule
+    /// came and went until it was settled for good in 1954.
+    pub sacrifice_flies_counted: bool,
+    /// Whether a game called with the score tied is suspended and finished later, as
+    /// since 2007. Before then it went into the books as a tie and was replayed from the
+    /// start if the standings needed it.
+    pub tied_games_suspended: bool,
+}
+
+impl Default for RulesEra {
+    fn default() -> Self {
+        Self {
+            season: 0,
+            scheduled_innings: 9,
+            extra_innings_runner: false,
+            sacrifice_flies_counted: true,
+            tied_games_suspended: false,
+        }
+    }
+}
+
+impl RulesEra {
+    pub fn new(season: u16, game_type: GameType, doubleheader_status: DoubleheaderStatus) -> Self {
+        let is_regular_season = game_type == GameType::RegularSeason;
+        let seven_inning_doubleheader = is_regular_season
+            && matches!(season, 2020 | 2021)
+            && doubleheader_status != DoubleheaderStatus::SingleGame;
+        Self {
+            season,
+            scheduled_innings: if seven_inning_doubleheader { 7 } else { 9 },
+            extra_innings_runner: is_regular_season && season >= 2020,
+            sacrifice_flies_counted: matches!(season, 1908..=1930 | 1939 | 1954..),
+            tied_games_suspended: season >= 2007,
+        }
+    }
+
+    /// The rules for the game's season, with the scheduled innings overridden by the
+    /// game's `innings` info record if it has one
+    pub fn for_game(
+        season: u16,
+        game_type: GameType,
+        doubleheader_status: DoubleheaderStatus,
+        record_slice: &RecordSlice,
+    ) -> Self {
+        let rules = Self::new(season, game_type, doubleheader_status);
+        let scheduled_innings = record_slice.iter().find_map(|r| match r {
+            MappedRecord::Info(InfoRecord::Innings(innings)) => *innings,
+            _ => None,
+        });
+        Self {
+            scheduled_innings: scheduled_innings.unwrap_or(rules.scheduled_innings),
+            ..rules
+        }
+    }
+
+    /// Whether the plate appearance counted as an at-bat. Sacrifice flies did in the
+    /// seasons where they weren't exempt.
+    pub fn is_at_bat(&self, plate_appearance: PlateAppearanceResultType) -> bool {
+        plate_appearance.is_at_bat()
+            || (plate_appearance == PlateAppearanceResultType::SacrificeFly
+                && !self.sacrifice_flies_counted)
+    }
+
+    /// Whether the league used a designated hitter, given a league code from the team
+    /// files. `None` for leagues other than the AL and NL, whose rules varied too much.
+    pub fn designated_hitter(&self, league: &str) -> Option<bool> {
+        match league {
+            "A" => Some(self.season >= 1973),
+            "N" => Some(self.season == 2020 || self.season >= 2022),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regular_season(season: u16) -> RulesEra {
+        RulesEra::new(
+            season,
+            GameType::RegularSeason,
+            DoubleheaderStatus::SingleGame,
+        )
+    }
+
+    #[test]
+    fn sacrifice_flies_follow_the_season() {
+        for (season, counted) in [(1907, false), (1908, true), (1931, false), (1939, true)] {
+            assert_eq!(regular_season(season).sacrifice_flies_counted, counted);
+        }
+        assert!(regular_season(1940).is_at_bat(PlateAppearanceResultType::SacrificeFly));
+        assert!(!regular_season(1954).is_at_bat(PlateAppearanceResultType::SacrificeFly));
+        assert!(regular_season(1954).is_at_bat(PlateAppearanceResultType::Single));
+        assert!(!regular_season(1940).is_at_bat(PlateAppearanceResultType::Walk));
+    }
+
+    #[test]
+    fn seven_inning_games_are_only_pandemic_doubleheaders() {
+        let game_2 = |season, game_type| {
+            RulesEra::new(season, game_type, DoubleheaderStatus::DoubleHeaderGame2)
+        };
+        assert_eq!(game_2(2021, GameType::RegularSeason).scheduled_innings, 7);
+        assert_eq!(game_2(2022, GameType::RegularSeason).scheduled_innings, 9);
+        assert_eq!(game_2(2020, GameType::WorldSeries).scheduled_innings, 9);
+        assert_eq!(regular_season(2020).scheduled_innings, 9);
+    }
+
+    #[test]
+    fn extra_innings_runner_is_regular_season_only() {
+        assert!(regular_season(2020).extra_innings_runner);
+        assert!(!regular_season(2019).extra_innings_runner);
+        let postseason = RulesEra::new(2020, GameType::WorldSeries, DoubleheaderStatus::SingleGame);
+        assert!(!postseason.extra_innings_runner);
+    }
+
+    #[test]
+    fn designated_hitter_by_league() {
+        assert_eq!(regular_season(1973).designated_hitter("A"), Some(true));
+        assert_eq!(regular_season(1972).designated_hitter("A"), Some(false));
+        assert_eq!(regular_season(2021).designated_hitter("N"), Some(false));
+        assert_eq!(regular_season(2022).designated_hitter("N"), Some(true));
+        assert_eq!(regular_season(2022).designated_hitter("F"), None);
+    }
+}
@@ -1,30 +1,41 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use anyhow::{bail, Context, Result};
 use arrayvec::ArrayString;
 use bounded_integer::BoundedU8;
-use chrono::{NaiveDate, NaiveDateTime};
-use either::Either;
-use serde::{Deserialize, Serialize};
-use serde_json::{Map, Value};
+use chrono::{NaiveDate, NaiveDateTime, SubsecRound, Utc};
+use itertools::Itertools;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
 
-use crate::event_file::box_score::{BoxScoreEvent, BoxScoreLine, LineScore};
-use crate::event_file::game_state::{EventId, GameContext, Outs};
+use crate::event_file::box_score::{
+    BattingLine, BattingLineStats, BoxScoreLine, DefenseLine, DefenseLineStats, FieldingPlayLine,
+    HitByPitchLine, HomeRunLine, LineScore, PinchHittingLine, PinchRunningLine, PitchingLine,
+    PitchingLineStats, StolenBaseAttemptLine, TeamBattingLine, TeamDefenseLine,
+    TeamMiscellaneousLine,
+};
+use crate::event_file::game_state::{EventId, GameContext, GameOutcome, Outs, Runner};
 use crate::event_file::info::{
     DayNight, DoubleheaderStatus, FieldCondition, HowScored, Park, Precipitation, Sky, Team,
     WindDirection,
 };
-use crate::event_file::pitch_sequence::PitchType;
+use crate::event_file::pitch_sequence::{PitchSequenceItem, PitchType};
 use crate::event_file::play::{Base, BaseRunner, InningFrame};
 use crate::event_file::traits::{
-    EventKey, FieldingPlayType, FieldingPosition, GameType, Inning, LineupPosition, Pitcher,
-    Player, RetrosheetVolunteer, Scorer, SequenceId, Side, Umpire,
+    Batter, EventKey, Fielder, FieldingPlayType, FieldingPosition, GameType, Inning,
+    LineupPosition, Matchup, Pitcher, Player, RetrosheetVolunteer, Scorer, SequenceId, Side,
+    Umpire,
 };
 
-use super::game_state::{Event as E, GameLineupAppearance, PlateAppearanceResultType};
+use super::game_state::{
+    EnteredGameAs, Event as E, GameFieldingAppearance, GameLineupAppearance,
+    PlateAppearanceResultType,
+};
 use super::info::UmpirePosition;
-use super::misc::Hand;
-use super::parser::{AccountType, MappedRecord, RecordSlice};
+use super::known_issues::KnownIssue;
+use super::misc::{CommentCategory, Hand, InningsPitched};
+use super::parser::{AccountType, FileInfo, MappedRecord, RecordSlice};
+use super::roster::{Rosters, TeamLeagues};
 use super::play::{
     BaserunningPlayType, Trajectory, BattedBallAngle, BattedBallDepth, BattedBallLocationGeneral, BattedBallStrength,
 };
@@ -34,8 +45,11 @@ pub trait ContextToVec<'a>: Serialize + Sized {
 }
 
 pub type GameIdString = ArrayString<12>;
+/// `{game_id}-{inning}-{frame}`, e.g. `BOS201904010-3-bottom`
+pub type HalfInningId = ArrayString<24>;
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Games<'a> {
     game_id: GameIdString,
     date: NaiveDate,
@@ -43,6 +57,8 @@ pub struct Games<'a> {
     doubleheader_status: DoubleheaderStatus,
     time_of_day: DayNight,
     game_type: GameType,
+    /// `Home` in the games marked `htbf`, where the home team batted in the top of
+    /// each inning
     bat_first_side: Side,
     sky: Sky,
     field_condition: FieldCondition,
@@ -52,12 +68,25 @@ pub struct Games<'a> {
     temperature_fahrenheit: Option<u8>,
     attendance: Option<u32>,
     wind_speed_mph: Option<u8>,
+    temperature_celsius: Option<f32>,
+    wind_speed_kph: Option<f32>,
+    indoor_flag: Option<bool>,
+    weather_data_missing_flag: bool,
+    attendance_missing_flag: bool,
     use_dh: bool,
     winning_pitcher: Option<Player>,
     losing_pitcher: Option<Player>,
     save_pitcher: Option<Player>,
     game_winning_rbi: Option<Player>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    winning_pitcher_name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    losing_pitcher_name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    save_pitcher_name: Option<&'a str>,
     time_of_game_minutes: Option<u16>,
+    time_of_game_missing_flag: bool,
+    batting_order_anomaly_flag: bool,
     protest_info: Option<&'a str>,
     completion_info: Option<&'a str>,
     scorer: Option<Scorer>,
@@ -71,18 +100,97 @@ pub struct Games<'a> {
     game_key: EventKey,
     away_team_id: Team,
     home_team_id: Team,
+    away_team_league: Option<&'a str>,
+    home_team_league: Option<&'a str>,
     umpire_home_id: Option<Umpire>,
     umpire_first_id: Option<Umpire>,
     umpire_second_id: Option<Umpire>,
     umpire_third_id: Option<Umpire>,
     umpire_left_id: Option<Umpire>,
     umpire_right_id: Option<Umpire>,
+    /// Whether any position had an umpire whose ID is unknown, as opposed to
+    /// no umpire at all
+    umpire_known_missing_flag: bool,
+    known_issue_id: Option<&'a str>,
+    content_hash: ArrayString<16>,
+    away_runs: Option<u16>,
+    home_runs: Option<u16>,
+    away_hits: Option<u16>,
+    home_hits: Option<u16>,
+    away_errors: Option<u16>,
+    home_errors: Option<u16>,
+    /// The components of the game ID, which can differ from the info records above
+    game_id_home_team_id: Option<Team>,
+    game_id_date: Option<NaiveDate>,
+    game_id_game_number: Option<u8>,
+    /// Usually the same as `calendar_year`, but taken from the file name when they differ
+    season: u16,
+    calendar_year: u16,
+    /// Whether every plate appearance has a pitch sequence with no unknown pitches, every
+    /// plate appearance has a count, and every ball in play has a location, respectively.
+    /// Always false for games without plate appearances, such as box scores.
+    pitch_sequences_present_flag: bool,
+    counts_present_flag: bool,
+    hit_locations_present_flag: bool,
+    /// Share of a deduced game's plate appearances that were recorded rather than
+    /// inferred, weighted by how much of each was inferred. Blank for other games.
+    deduced_confidence_score: Option<f64>,
+    scheduled_innings: u8,
+    extra_innings_runner_flag: bool,
+    /// Summed from the events of play-by-play games, and taken from the team line of
+    /// box scores
+    away_left_on_base: Option<u16>,
+    home_left_on_base: Option<u16>,
+    game_outcome: Option<GameOutcome>,
+}
+
+impl<'a> Games<'a> {
+    /// Adds the names of the pitchers of record. As with `Events`, unknown names
+    /// are written as empty strings to keep the columns consistent.
+    pub fn with_names(self, rosters: &'a Rosters, season: u16) -> Self {
+        let name = |player_id: Option<Player>| {
+            player_id
+                .and_then(|p| rosters.name(p, season))
+                .or(Some(""))
+        };
+        Self {
+            winning_pitcher_name: name(self.winning_pitcher),
+            losing_pitcher_name: name(self.losing_pitcher),
+            save_pitcher_name: name(self.save_pitcher),
+            ..self
+        }
+    }
+
+    /// Treats umpires with unknown IDs as if there was no umpire at their position
+    pub const fn without_unknown_umpires(self) -> Self {
+        Self {
+            umpire_known_missing_flag: false,
+            ..self
+        }
+    }
+
+    /// Adds each team's league for the season, when the input has team files
+    pub fn with_leagues(self, leagues: &'a TeamLeagues, season: u16) -> Self {
+        Self {
+            away_team_league: leagues.get(self.away_team_id, season),
+            home_team_league: leagues.get(self.home_team_id, season),
+            ..self
+        }
+    }
 }
 
 impl<'a> From<&'a GameContext> for Games<'a> {
     fn from(gc: &'a GameContext) -> Self {
         let setting = &gc.setting;
         let results = &gc.results;
+        let line_totals = gc.line_totals();
+        let detail_coverage = gc.detail_coverage();
+        let umpire = |position| {
+            gc.umpires
+                .iter()
+                .find(|u| u.position == position)
+                .and_then(|u| u.umpire_id)
+        };
         let start_time = setting
             .start_time
             .map(|time| NaiveDateTime::new(setting.date, time));
@@ -102,12 +210,22 @@ impl<'a> From<&'a GameContext> for Games<'a> {
             temperature_fahrenheit: setting.temperature_fahrenheit,
             attendance: setting.attendance,
             wind_speed_mph: setting.wind_speed_mph,
+            temperature_celsius: setting.temperature_celsius(),
+            wind_speed_kph: setting.wind_speed_kph(),
+            indoor_flag: setting.indoor_flag(),
+            weather_data_missing_flag: setting.weather_data_missing_flag(),
+            attendance_missing_flag: setting.attendance.is_none(),
             use_dh: setting.use_dh,
             winning_pitcher: results.winning_pitcher,
             losing_pitcher: results.losing_pitcher,
             save_pitcher: results.save_pitcher,
             game_winning_rbi: results.game_winning_rbi,
+            winning_pitcher_name: None,
+            losing_pitcher_name: None,
+            save_pitcher_name: None,
             time_of_game_minutes: results.time_of_game_minutes,
+            time_of_game_missing_flag: results.time_of_game_minutes.is_none(),
+            batting_order_anomaly_flag: !LintFindings::batting_order(gc).is_empty(),
             protest_info: results.protest_info.as_deref(),
             completion_info: results.completion_info.as_deref(),
             game_key: gc.event_key_offset,
@@ -121,38 +239,569 @@ impl<'a> From<&'a GameContext> for Games<'a> {
             filename: gc.file_info.filename.as_str(),
             away_team_id: gc.teams.away,
             home_team_id: gc.teams.home,
-            umpire_home_id: gc
-                .umpires
-                .iter()
-                .find(|u| u.position == UmpirePosition::Home)
-                .and_then(|u| u.umpire_id),
-            umpire_first_id: gc
-                .umpires
-                .iter()
-                .find(|u| u.position == UmpirePosition::First)
-                .and_then(|u| u.umpire_id),
-            umpire_second_id: gc
-                .umpires
-                .iter()
-                .find(|u| u.position == UmpirePosition::Second)
-                .and_then(|u| u.umpire_id),
-            umpire_third_id: gc
-                .umpires
-                .iter()
-                .find(|u| u.position == UmpirePosition::Third)
-                .and_then(|u| u.umpire_id),
-            umpire_left_id: gc
-                .umpires
-                .iter()
-                .find(|u| u.position == UmpirePosition::LeftField)
-                .and_then(|u| u.umpire_id),
-            umpire_right_id: gc
-                .umpires
-                .iter()
-                .find(|u| u.position == UmpirePosition::RightField)
-                .and_then(|u| u.umpire_id),
+            away_team_league: None,
+            home_team_league: None,
+            umpire_home_id: umpire(UmpirePosition::Home),
+            umpire_first_id: umpire(UmpirePosition::First),
+            umpire_second_id: umpire(UmpirePosition::Second),
+            umpire_third_id: umpire(UmpirePosition::Third),
+            umpire_left_id: umpire(UmpirePosition::LeftField),
+            umpire_right_id: umpire(UmpirePosition::RightField),
+            umpire_known_missing_flag: gc.umpires.iter().any(|u| u.umpire_id.is_none()),
+            known_issue_id: KnownIssue::for_game(&gc.game_id.id),
+            content_hash: GameManifest::content_hash(gc),
+            away_runs: line_totals.as_ref().map(|t| t.away.runs),
+            home_runs: line_totals.as_ref().map(|t| t.home.runs),
+            away_hits: line_totals.as_ref().map(|t| t.away.hits),
+            home_hits: line_totals.as_ref().map(|t| t.home.hits),
+            away_errors: line_totals.as_ref().map(|t| t.away.errors),
+            home_errors: line_totals.as_ref().map(|t| t.home.errors),
+            game_id_home_team_id: gc.game_id.home_team(),
+            game_id_date: gc.game_id.date(),
+            game_id_game_number: gc.game_id.game_number(),
+            season: setting.season.0,
+            calendar_year: setting.calendar_year(),
+            pitch_sequences_present_flag: detail_coverage.pitch_sequences,
+            counts_present_flag: detail_coverage.counts,
+            hit_locations_present_flag: detail_coverage.hit_locations,
+            deduced_confidence_score: gc.deduced_confidence(),
+            scheduled_innings: gc.rules.scheduled_innings,
+            extra_innings_runner_flag: gc.rules.extra_innings_runner,
+            away_left_on_base: line_totals.as_ref().and_then(|t| t.away.left_on_base),
+            home_left_on_base: line_totals.as_ref().and_then(|t| t.home.left_on_base),
+            game_outcome: gc.outcome(),
+        }
+    }
+}
+
+/// Content hash of every game written in a run, used by later runs to find
+/// which games have been added or changed since.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct GameManifest {
+    pub game_id: GameIdString,
+    pub content_hash: ArrayString<16>,
+}
+
+impl GameManifest {
+    fn content_hash(gc: &GameContext) -> ArrayString<16> {
+        ArrayString::from(&format!("{:016x}", gc.content_hash)).unwrap_or_default()
+    }
+}
+
+impl From<&GameContext> for GameManifest {
+    fn from(gc: &GameContext) -> Self {
+        Self {
+            game_id: gc.game_id.id,
+            content_hash: Self::content_hash(gc),
+        }
+    }
+}
+
+/// How many of the rare records that patch up a game's state (`badj`, `padj`, `radj`,
+/// `ladj` and `presadj`) each game has. Only games with at least one are written.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct GameAdjustments {
+    pub game_id: GameIdString,
+    pub season: u16,
+    pub bat_hand_adjustments: usize,
+    pub pitch_hand_adjustments: usize,
+    pub runner_adjustments: usize,
+    pub lineup_adjustments: usize,
+    pub pitcher_responsibility_adjustments: usize,
+}
+
+impl GameAdjustments {
+    pub fn from_record_slice(gc: &GameContext, slice: &RecordSlice) -> Option<Self> {
+        let count = |f: fn(&MappedRecord) -> bool| slice.iter().filter(|r| f(r)).count();
+        let adjustments = Self {
+            game_id: gc.game_id.id,
+            season: gc.setting.season.0,
+            bat_hand_adjustments: count(|r| matches!(r, MappedRecord::BatHandAdjustment(_))),
+            pitch_hand_adjustments: count(|r| matches!(r, MappedRecord::PitchHandAdjustment(_))),
+            runner_adjustments: count(|r| matches!(r, MappedRecord::RunnerAdjustment(_))),
+            lineup_adjustments: count(|r| matches!(r, MappedRecord::LineupAdjustment(_))),
+            pitcher_responsibility_adjustments: count(|r| {
+                matches!(r, MappedRecord::PitcherResponsibilityAdjustment(_))
+            }),
+        };
+        let total = adjustments.bat_hand_adjustments
+            + adjustments.pitch_hand_adjustments
+            + adjustments.runner_adjustments
+            + adjustments.lineup_adjustments
+            + adjustments.pitcher_responsibility_adjustments;
+        (total > 0).then_some(adjustments)
+    }
+}
+
+/// Parse statistics for a single input file. Errors are games that could not be read
+/// or parsed and were skipped.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct RunTelemetry {
+    pub filename: ArrayString<20>,
+    pub account_type: AccountType,
+    pub parse_duration_seconds: f64,
+    pub game_count: usize,
+    pub event_count: usize,
+    pub error_count: usize,
+    /// Lines that weren't valid UTF-8 and were read as Latin-1 instead
+    pub non_utf8_line_count: usize,
+}
+
+impl RunTelemetry {
+    pub const fn new(file_info: FileInfo) -> Self {
+        Self {
+            filename: file_info.filename,
+            account_type: file_info.account_type,
+            parse_duration_seconds: 0.0,
+            game_count: 0,
+            event_count: 0,
+            error_count: 0,
+            non_utf8_line_count: 0,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Serialize, Deserialize)]
+pub enum LintRule {
+    PlayerNotOnRoster,
+    BattingOutOfOrder,
+    CountMismatch,
+    PlayerReentered,
+    FieldersChoiceAnomaly,
+    FieldingCreditMismatch,
+    NonstandardId,
+    DesignatedHitterMismatch,
+}
+
+/// Likely errors in the source data, found by checking a game against other
+/// information. Games are still written in full when they have findings.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct LintFindings {
+    pub game_id: GameIdString,
+    pub event_id: Option<EventId>,
+    pub rule: LintRule,
+    pub message: String,
+}
+
+impl LintFindings {
+    /// Players appearing for a team that aren't on its roster for the season, which
+    /// usually means a mistyped player ID. Teams without a roster file are skipped.
+    pub fn roster_mismatches(gc: &GameContext, rosters: &Rosters) -> Vec<Self> {
+        let lineup = gc
+            .lineup_appearances
+            .iter()
+            .map(|la| (la.player_id, la.side, la.start_event_id));
+        let fielding = gc
+            .fielding_appearances
+            .iter()
+            .map(|fa| (fa.player_id, fa.side, fa.start_event_id));
+        lineup
+            .chain(fielding)
+            .unique_by(|(player_id, side, _)| (*player_id, *side))
+            .filter_map(|(player_id, side, event_id)| {
+                let team = *gc.teams.get(side);
+                let roster = rosters.get(team, gc.setting.season.0)?;
+                if roster.contains_key(&player_id) {
+                    return None;
+                }
+                Some(Self {
+                    game_id: gc.game_id.id,
+                    event_id: Some(event_id),
+                    rule: LintRule::PlayerNotOnRoster,
+                    message: format!(
+                        "{player_id} appears for {team} but is not on the {team} {} roster",
+                        gc.setting.season.0
+                    ),
+                })
+            })
+            .collect()
+    }
+
+    /// Regular-season games whose `usedh` record disagrees with the home league's rule
+    /// for the season. Games whose home team has no league in the team files are skipped.
+    pub fn designated_hitter_mismatches(gc: &GameContext, leagues: &TeamLeagues) -> Vec<Self> {
+        let season = gc.setting.season.0;
+        let home_team = gc.teams.home;
+        let expected = leagues
+            .get(home_team, season)
+            .filter(|_| gc.setting.game_type == GameType::RegularSeason)
+            .and_then(|league| Some((league, gc.rules.designated_hitter(league)?)));
+        match expected {
+            Some((league, used_dh)) if used_dh != gc.setting.use_dh => vec![Self {
+                game_id: gc.game_id.id,
+                event_id: None,
+                rule: LintRule::DesignatedHitterMismatch,
+                message: format!(
+                    "Game has usedh={} but {home_team}'s league ({league}) {} the DH in {season}",
+                    gc.setting.use_dh,
+                    if used_dh { "used" } else { "didn't use" }
+                ),
+            }],
+            _ => vec![],
+        }
+    }
+
+    /// Team, park and player IDs that don't have the standard Retrosheet form. They're
+    /// kept as written so the game can still be loaded, but won't join to other tables.
+    pub fn nonstandard_ids(gc: &GameContext) -> Vec<Self> {
+        let finding = |event_id, message| Self {
+            game_id: gc.game_id.id,
+            event_id,
+            rule: LintRule::NonstandardId,
+            message,
+        };
+        let mut findings = [Side::Away, Side::Home]
+            .into_iter()
+            .map(|side| *gc.teams.get(side))
+            .filter(|team| !team.is_standard())
+            .map(|team| finding(None, format!("Team ID \"{team}\" is not a standard ID")))
+            .collect_vec();
+        let park = gc.setting.park_id;
+        if !park.is_standard() {
+            findings.push(finding(
+                None,
+                format!("Park ID \"{park}\" is not a standard ID"),
+            ));
+        }
+        let lineup = gc
+            .lineup_appearances
+            .iter()
+            .map(|la| (la.player_id, la.start_event_id));
+        let fielding = gc
+            .fielding_appearances
+            .iter()
+            .map(|fa| (fa.player_id, fa.start_event_id));
+        findings.extend(
+            lineup
+                .chain(fielding)
+                .unique_by(|(player_id, _)| *player_id)
+                .filter(|(player_id, _)| !player_id.is_standard())
+                .map(|(player_id, event_id)| {
+                    finding(
+                        Some(event_id),
+                        format!("Player ID \"{player_id}\" is not a standard ID"),
+                    )
+                }),
+        );
+        findings
+    }
+
+    /// Events whose batter isn't in the lineup spot due up. A completed plate appearance
+    /// moves the side on to the next spot, while an inning ending mid-PA leaves the
+    /// same spot to lead off the next one. Substitutes inherit their spot, so they
+    /// don't break the rotation.
+    pub fn batting_order(gc: &GameContext) -> Vec<Self> {
+        let mut due_up: Matchup<Option<LineupPosition>> = Matchup::default();
+        let mut findings = vec![];
+        for e in &gc.events {
+            let side = e.context.batting_side;
+            let at_bat = e.context.at_bat;
+            let expected = due_up.get_mut(side);
+            if let Some(lp) = expected.filter(|lp| *lp != at_bat) {
+                let team = gc.teams.get(side);
+                findings.push(Self {
+                    game_id: gc.game_id.id,
+                    event_id: Some(e.event_id),
+                    rule: LintRule::BattingOutOfOrder,
+                    message: format!(
+                        "{} batted in spot {} for {team}, but spot {} was due up",
+                        e.context.batter_id,
+                        at_bat.retrosheet_string(),
+                        lp.retrosheet_string()
+                    ),
+                });
+            }
+            *expected = if e.results.plate_appearance.is_some() {
+                at_bat.next().ok()
+            } else {
+                Some(at_bat)
+            };
+        }
+        findings
+    }
+
+    /// Plate appearances whose recorded count doesn't match the count before the final
+    /// pitch of the sequence. Sequences with unknown pitches or no count are skipped.
+    pub fn count_mismatches(gc: &GameContext) -> Vec<Self> {
+        gc.events
+            .iter()
+            .filter(|e| e.results.plate_appearance.is_some())
+            .filter_map(|e| {
+                let count = e.results.count_at_event;
+                let (balls, strikes) = (count.balls?.get(), count.strikes?.get());
+                // The event's own sequence only starts after the last interruption
+                let full_sequence = e.raw_pitch_sequence.replace('.', "");
+                let pitches = PitchSequenceItem::new_pitch_sequence(&full_sequence).ok()?;
+                if pitches.iter().any(|p| p.pitch_type.is_unknown()) {
+                    return None;
+                }
+                let pitches = pitches
+                    .iter()
+                    .map(|p| p.pitch_type)
+                    .filter(|p| p.is_pitch())
+                    .collect_vec();
+                let (_, before_final) = pitches.split_last()?;
+                let (mut replay_balls, mut replay_strikes) = (0, 0);
+                for pitch in before_final {
+                    if pitch.is_ball() {
+                        replay_balls += 1;
+                    } else if pitch.is_strike() || (pitch.is_foul() && replay_strikes < 2) {
+                        replay_strikes += 1;
+                    }
+                }
+                ((balls, strikes) != (replay_balls, replay_strikes)).then(|| Self {
+                    game_id: gc.game_id.id,
+                    event_id: Some(e.event_id),
+                    rule: LintRule::CountMismatch,
+                    message: format!(
+                        "Count is {balls}-{strikes}, but the pitch sequence {} \
+                         reaches {replay_balls}-{replay_strikes} before the final pitch",
+                        e.raw_pitch_sequence
+                    ),
+                })
+            })
+            .collect()
+    }
+
+    /// Fielder's choices that don't fit the usual meaning of a fielder trying to put out a
+    /// runner instead of the batter: no runners on base, the batter put out anyway, or
+    /// no out made on the play. The last is legitimate when every runner is safe, but it
+    /// is also a common sign of a mis-scored play.
+    pub fn fielders_choices(gc: &GameContext) -> Vec<Self> {
+        gc.events
+            .iter()
+            .filter(|e| {
+                e.results.plate_appearance == Some(PlateAppearanceResultType::FieldersChoice)
+                    || e.results
+                        .fielding_plays
+                        .iter()
+                        .any(|fp| fp.fielding_play_type == FieldingPlayType::FieldersChoice)
+            })
+            .filter_map(|e| {
+                let problem = if e.context.starting_base_state.iter_runners().next().is_none() {
+                    "with no runners on base"
+                } else if e.results.out_on_play.contains(&BaseRunner::Batter) {
+                    "with the batter put out"
+                } else if e.results.out_on_play.is_empty() {
+                    "with no out made on the play"
+                } else {
+                    return None;
+                };
+                Some(Self {
+                    game_id: gc.game_id.id,
+                    event_id: Some(e.event_id),
+                    rule: LintRule::FieldersChoiceAnomaly,
+                    message: format!("Fielder's choice {problem}: {}", e.raw_play),
+                })
+            })
+            .collect()
+    }
+
+    /// Batted-ball outs whose fielding credits don't involve the fielder the ball was hit
+    /// to. Caught balls should be a putout for that fielder, and ground balls should
+    /// at least give them an assist or putout. Plays with errors are skipped.
+    pub fn fielding_credits(gc: &GameContext) -> Vec<Self> {
+        gc.events
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e.results.plate_appearance,
+                    Some(
+                        PlateAppearanceResultType::InPlayOut
+                            | PlateAppearanceResultType::SacrificeFly
+                    )
+                ) && e.results.out_on_play.contains(&BaseRunner::Batter)
+            })
+            .filter_map(|e| {
+                let info = e.results.batted_ball_info.as_ref()?;
+                let hit_to = info
+                    .hit_to_fielder
+                    .filter(|f| *f != FieldingPosition::Unknown)?;
+                let plays = &e.results.fielding_plays;
+                if plays
+                    .iter()
+                    .any(|fp| fp.fielding_play_type == FieldingPlayType::Error)
+                {
+                    return None;
+                }
+                let is_caught = matches!(
+                    info.trajectory,
+                    Trajectory::Fly
+                        | Trajectory::LineDrive
+                        | Trajectory::PopUp
+                        | Trajectory::PopUpBunt
+                        | Trajectory::LineDriveBunt
+                );
+                let first_putout = plays
+                    .iter()
+                    .find(|fp| fp.fielding_play_type == FieldingPlayType::Putout)
+                    .map(|fp| fp.fielding_position);
+                let problem = if is_caught && first_putout != Some(hit_to) {
+                    "is not credited with the putout"
+                } else if plays.iter().all(|fp| fp.fielding_position != hit_to) {
+                    "has no putout or assist"
+                } else {
+                    return None;
+                };
+                Some(Self {
+                    game_id: gc.game_id.id,
+                    event_id: Some(e.event_id),
+                    rule: LintRule::FieldingCreditMismatch,
+                    message: format!(
+                        "Ball was hit to fielder {}, who {problem}: {}",
+                        hit_to.retrosheet_string(),
+                        e.raw_play
+                    ),
+                })
+            })
+            .collect()
+    }
+
+    /// Players who come back into the game after being replaced, which the rules
+    /// don't allow (courtesy runners in very old games aside)
+    pub fn reentries(gc: &GameContext) -> Vec<Self> {
+        gc.lineup_appearances
+            .iter()
+            .into_group_map_by(|la| (la.side, la.player_id))
+            .into_iter()
+            .flat_map(|((side, player_id), appearances)| {
+                appearances
+                    .into_iter()
+                    .sorted_by_key(|la| la.start_event_id)
+                    .tuple_windows()
+                    // A pitcher batting for the DH after it's vacated hasn't left the game
+                    .filter(|(previous, next)| {
+                        previous.lineup_position != LineupPosition::PitcherWithDh
+                            && previous
+                                .end_event_id
+                                .is_some_and(|end| end <= next.start_event_id)
+                    })
+                    .map(move |(previous, next)| Self {
+                        game_id: gc.game_id.id,
+                        event_id: Some(next.start_event_id),
+                        rule: LintRule::PlayerReentered,
+                        message: format!(
+                            "{player_id} re-entered for {} in spot {} after leaving \
+                             spot {}",
+                            gc.teams.get(side),
+                            next.lineup_position.retrosheet_string(),
+                            previous.lineup_position.retrosheet_string()
+                        ),
+                    })
+            })
+            .sorted_by_key(|f| f.event_id)
+            .collect()
+    }
+}
+
+/// Summary of a whole run. `partial` is set when the run was interrupted, in which
+/// case the skipped files are missing from every output.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RunManifest {
+    pub schema_version: String,
+    pub partial: bool,
+    pub files_processed: usize,
+    pub files_skipped: usize,
+    pub files_unreadable: usize,
+}
+
+/// Input files that couldn't be opened or listed, even after retrying
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct UnreadableFiles {
+    pub path: String,
+    pub error: String,
+}
+
+/// How often each column of each output file is empty or zero, by season. Rows of
+/// files without a season of their own get the season of their game, if any.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ColumnProfile {
+    pub dataset: String,
+    pub column_name: String,
+    pub season: Option<u16>,
+    pub row_count: u64,
+    pub null_count: u64,
+    pub zero_count: u64,
+    pub null_rate: f64,
+    pub zero_rate: f64,
+}
+
+impl ColumnProfile {
+    pub fn new(
+        (dataset, column_name, season): (String, String, Option<u16>),
+        [row_count, null_count, zero_count]: [u64; 3],
+    ) -> Self {
+        #[allow(clippy::cast_precision_loss)]
+        let rate = |count| count as f64 / row_count as f64;
+        Self {
+            dataset,
+            column_name,
+            season,
+            row_count,
+            null_count,
+            zero_count,
+            null_rate: rate(null_count),
+            zero_rate: rate(zero_count),
+        }
+    }
+}
+
+/// Every account of each game that has more than one, such as a box score alongside a
+/// play-by-play account from a later release, so that data missing from one can be joined
+/// from the other. `preferred_flag` marks the account that the games table was written
+/// from, which is the first play-by-play account, then deduced, then box score. Deduced
+/// accounts of games that already have a play-by-play account aren't written at all, so
+/// they have `written_flag` unset.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct GameAccountLinks {
+    pub game_id: GameIdString,
+    pub account_type: AccountType,
+    pub filename: ArrayString<20>,
+    pub written_flag: bool,
+    pub preferred_flag: bool,
+}
+
+impl GameAccountLinks {
+    pub const fn new(gc: &GameContext, written: bool) -> Self {
+        Self {
+            game_id: gc.game_id.id,
+            account_type: gc.file_info.account_type,
+            filename: gc.file_info.filename,
+            written_flag: written,
+            preferred_flag: false,
         }
     }
+
+    /// Groups the accounts of a run by game, keeping the games with more than one and
+    /// picking the preferred account of each. Accounts should be in the order they were
+    /// parsed.
+    pub fn link(accounts: impl IntoIterator<Item = Self>) -> Vec<Self> {
+        accounts
+            .into_iter()
+            .into_group_map_by(|a| a.game_id)
+            .into_iter()
+            .filter(|(_, accounts)| accounts.len() > 1)
+            .sorted_by_key(|(game_id, _)| *game_id)
+            .flat_map(|(_, mut accounts)| {
+                let preferred = accounts
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, a)| a.written_flag)
+                    .min_by_key(|(i, a)| (a.account_type, *i))
+                    .map(|(i, _)| i);
+                if let Some(a) = preferred.and_then(|i| accounts.get_mut(i)) {
+                    a.preferred_flag = true;
+                }
+                accounts
+            })
+            .collect()
+    }
+}
+
+/// Games present in the previous run's manifest that no longer appear in the input
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct DeletedGames {
+    pub game_id: GameIdString,
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
@@ -175,20 +824,31 @@ impl ContextToVec<'_> for GameEarnedRuns {
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
-pub struct Events {
+pub struct Events<'a> {
     game_id: GameIdString,
     event_id: EventId,
     event_key: EventKey,
+    previous_event_key: Option<EventKey>,
+    next_event_key: Option<EventKey>,
     batting_side: Side,
     inning: u8,
     frame: InningFrame,
+    half_inning_id: HalfInningId,
+    event_index_in_half_inning: usize,
     batter_lineup_position: LineupPosition,
     batter_id: Player,
     pitcher_id: Player,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    batter_name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pitcher_name: Option<&'a str>,
     batting_team_id: Team,
     fielding_team_id: Team,
     outs: Outs,
     base_state: u8,
+    ending_outs: usize,
+    /// Runners left on base after the play, including when it ended the inning
+    ending_base_state: u8,
     count_balls: Option<u8>,
     count_strikes: Option<u8>,
     specified_batter_hand: Option<Hand>,
@@ -202,27 +862,138 @@ pub struct Events {
     batted_location_depth: Option<BattedBallDepth>,
     batted_location_angle: Option<BattedBallAngle>,
     batted_contact_strength: Option<BattedBallStrength>,
+    /// The fielder who made a fielder's choice on the play, if any
+    fielders_choice_fielder: Option<FieldingPosition>,
     outs_on_play: usize,
     runs_on_play: usize,
     runs_batted_in: usize,
     team_unearned_runs: usize,
-    no_play_flag: bool
+    no_play_flag: bool,
+    known_issue_id: Option<&'static str>,
+    /// The hands actually used, from the play if specified and otherwise the rosters
+    batter_hand: Option<Hand>,
+    pitcher_hand: Option<Hand>,
+    /// Runners stranded when the event ended its half-inning, blank for other events
+    left_on_base: Option<u8>,
+    /// Runners still on base after the batter made an out, for the batter's own LOB
+    batter_left_on_base: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw_play: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw_pitch_sequence: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    putout_positions: Option<PositionList>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assist_positions: Option<PositionList>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_positions: Option<PositionList>,
 }
 
-impl ContextToVec<'_> for Events {
-    fn from_game_context(gc: &GameContext) -> Box<dyn Iterator<Item = Self> + '_> {
-        Box::from(gc.events.iter().map(move |e| {
+/// Fielding positions joined with `;` in the order they appear in the play, e.g. `6;4;3`
+type PositionList = ArrayString<32>;
+
+impl<'a> Events<'a> {
+    /// Adds the batter and pitcher names. Players missing from the rosters get an
+    /// empty name, so that every row has the same columns.
+    pub fn with_names(self, rosters: &'a Rosters, season: u16) -> Self {
+        let name = |player_id| rosters.name(player_id, season).or(Some(""));
+        Self {
+            batter_name: name(self.batter_id),
+            pitcher_name: name(self.pitcher_id),
+            ..self
+        }
+    }
+
+    /// Fills in the batter and pitcher hands from the rosters where the play doesn't
+    /// specify them. Switch hitters are left blank if the pitcher's hand isn't known.
+    pub fn with_hands(self, rosters: &Rosters, season: u16) -> Self {
+        let pitcher_hand = self
+            .pitcher_hand
+            .or_else(|| rosters.pitcher_hand(self.fielding_team_id, self.pitcher_id, season));
+        let batter_hand = self.batter_hand.or_else(|| {
+            rosters.batter_hand(self.batting_team_id, self.batter_id, season, pitcher_hand)
+        });
+        Self {
+            batter_hand,
+            pitcher_hand,
+            ..self
+        }
+    }
+
+    /// Adds the play and pitch sequence exactly as they appear in the event file
+    pub fn with_raw_strings(self, gc: &'a GameContext) -> Self {
+        let event = gc.events.iter().find(|e| e.event_id == self.event_id);
+        Self {
+            raw_play: event.map(|e| e.raw_play.as_str()),
+            raw_pitch_sequence: event.map(|e| e.raw_pitch_sequence.as_str()),
+            ..self
+        }
+    }
+
+    /// Adds the positions credited with each kind of fielding play, as an alternative to
+    /// joining `event_fielding_plays`. Events without any get an empty string.
+    pub fn with_fielding_positions(self, gc: &GameContext) -> Self {
+        let event = gc.events.iter().find(|e| e.event_id == self.event_id);
+        let positions = |play_type| {
+            let joined = event
+                .iter()
+                .flat_map(|e| &e.results.fielding_plays)
+                .filter(|fp| fp.fielding_play_type == play_type)
+                .map(|fp| fp.fielding_position.retrosheet_string())
+                .join(";");
+            Some(PositionList::from(&joined).unwrap_or_default())
+        };
+        Self {
+            putout_positions: positions(FieldingPlayType::Putout),
+            assist_positions: positions(FieldingPlayType::Assist),
+            error_positions: positions(FieldingPlayType::Error),
+            ..self
+        }
+    }
+
+    fn half_inning_id(gc: &GameContext, inning: u8, frame: InningFrame) -> HalfInningId {
+        ArrayString::from(&format!("{}-{inning}-{}", gc.game_id.id, frame.as_ref()))
+            .unwrap_or_default()
+    }
+}
+
+impl<'a> ContextToVec<'a> for Events<'a> {
+    fn from_game_context(gc: &'a GameContext) -> Box<dyn Iterator<Item = Self> + 'a> {
+        let left_on_base: HashMap<EventId, u8> = gc
+            .left_on_base()
+            .map(|(e, stranded)| (e.event_id, stranded))
+            .collect();
+        Box::from(gc.events.iter().enumerate().map(move |(i, e)| {
             let batted_ball_info = e.results.batted_ball_info.as_ref();
+            let specified = |hand: Option<Hand>| hand.filter(|h| *h != Hand::Default);
+            // Starts at 1, like event_id
+            let event_index_in_half_inning = gc.events[..i]
+                .iter()
+                .rev()
+                .take_while(|prev| {
+                    (prev.context.inning, prev.context.frame) == (e.context.inning, e.context.frame)
+                })
+                .count()
+                + 1;
             Self {
                 game_id: gc.game_id.id,
                 event_id: e.event_id,
                 event_key: e.event_key,
+                previous_event_key: i
+                    .checked_sub(1)
+                    .and_then(|prev| gc.events.get(prev))
+                    .map(|prev| prev.event_key),
+                next_event_key: gc.events.get(i + 1).map(|next| next.event_key),
                 batting_side: e.context.batting_side,
                 inning: e.context.inning,
                 frame: e.context.frame,
+                half_inning_id: Self::half_inning_id(gc, e.context.inning, e.context.frame),
+                event_index_in_half_inning,
                 batter_lineup_position: e.context.at_bat,
                 batter_id: e.context.batter_id,
                 pitcher_id: e.context.pitcher_id,
+                batter_name: None,
+                pitcher_name: None,
                 batting_team_id: match e.context.batting_side {
                     Side::Away => gc.teams.away,
                     Side::Home => gc.teams.home,
@@ -233,6 +1004,8 @@ impl ContextToVec<'_> for Events {
                 },
                 outs: e.context.outs,
                 base_state: e.context.starting_base_state.get_base_state(),
+                ending_outs: e.context.outs.get() + e.results.out_on_play.len(),
+                ending_base_state: e.results.ending_base_state.get_base_state(),
                 count_balls: e.results.count_at_event.balls.map(BoundedU8::get),
                 count_strikes: e.results.count_at_event.strikes.map(BoundedU8::get),
                 specified_batter_hand: e.context.rare_attributes.batter_hand,
@@ -253,6 +1026,12 @@ impl ContextToVec<'_> for Events {
                 batted_location_depth: batted_ball_info.map(|i| i.depth),
                 batted_location_angle: batted_ball_info.map(|i| i.angle),
                 batted_contact_strength: batted_ball_info.map(|i| i.strength),
+                fielders_choice_fielder: e
+                    .results
+                    .fielding_plays
+                    .iter()
+                    .find(|fp| fp.fielding_play_type == FieldingPlayType::FieldersChoice)
+                    .map(|fp| fp.fielding_position),
                 outs_on_play: e.results.out_on_play.len(),
                 runs_on_play: e.results.runs.len(),
                 runs_batted_in: e.results.runs.iter().filter(|r| r.rbi_flag).count(),
@@ -263,11 +1042,23 @@ impl ContextToVec<'_> for Events {
                     .filter(|r| r.is_team_unearned_run())
                     .count(),
                 no_play_flag: e.results.no_play_flag,
+                known_issue_id: KnownIssue::for_event(&gc.game_id.id, e.event_id),
+                batter_hand: specified(e.context.rare_attributes.batter_hand),
+                pitcher_hand: specified(e.context.rare_attributes.pitcher_hand),
+                left_on_base: left_on_base.get(&e.event_id).copied(),
+                batter_left_on_base: e.batter_left_on_base(),
+                raw_play: None,
+                raw_pitch_sequence: None,
+                putout_positions: None,
+                assist_positions: None,
+                error_positions: None,
             }
         }))
     }
 }
 
+/// Where each event came from and what produced it, so that any row of the output can
+/// be traced back to a line of a specific version of a file and a version of the parser
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct EventAudit {
     game_id: GameIdString,
@@ -276,22 +1067,34 @@ pub struct EventAudit {
     filename: ArrayString<20>,
     line_number: usize,
     raw_play: Arc<String>,
+    parser_version: ArrayString<16>,
+    parsed_at: NaiveDateTime,
+    file_hash: ArrayString<16>,
+    account_type: AccountType,
 }
 
 impl ContextToVec<'_> for EventAudit {
     fn from_game_context(gc: &GameContext) -> Box<dyn Iterator<Item = Self> + '_> {
-        Box::from(gc.events.iter().map(|e| Self {
+        let parser_version = ArrayString::from(env!("CARGO_PKG_VERSION")).unwrap_or_default();
+        let parsed_at = Utc::now().naive_utc().trunc_subsecs(0);
+        let file_hash =
+            ArrayString::from(&format!("{:016x}", gc.file_info.file_hash)).unwrap_or_default();
+        Box::from(gc.events.iter().map(move |e| Self {
             game_id: gc.game_id.id,
             event_id: e.event_id,
             event_key: e.event_key,
             filename: gc.file_info.filename,
             line_number: e.line_number,
-            raw_play: e.raw_play.clone()
+            raw_play: e.raw_play.clone(),
+            parser_version,
+            parsed_at,
+            file_hash,
+            account_type: gc.file_info.account_type,
         }))
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Deserialize)]
 pub struct EventPitchSequences {
     game_id: GameIdString,
     event_id: EventId,
@@ -301,34 +1104,174 @@ pub struct EventPitchSequences {
     runners_going_flag: bool,
     blocked_by_catcher_flag: bool,
     catcher_pickoff_attempt_at_base: Option<Base>,
+    enrichment: Option<PitchEnrichment>,
+}
+
+impl EventPitchSequences {
+    const FIELD_COUNT: usize = 8;
+
+    const fn new(gc: &GameContext, e: &E, psi: &PitchSequenceItem) -> Self {
+        Self {
+            game_id: gc.game_id.id,
+            event_id: e.event_id,
+            event_key: e.event_key,
+            sequence_id: psi.sequence_id,
+            sequence_item: psi.pitch_type,
+            runners_going_flag: psi.runners_going,
+            blocked_by_catcher_flag: psi.blocked_by_catcher,
+            catcher_pickoff_attempt_at_base: psi.catcher_pickoff_attempt,
+            enrichment: None,
+        }
+    }
+
+    /// Rows with the enrichment columns filled in. The count is replayed across all
+    /// events of a plate appearance, since a single PA can span several events.
+    pub fn enriched(gc: &GameContext) -> Vec<Self> {
+        let mut rows = Vec::with_capacity(gc.events.len() * 4);
+        let mut count = Some((0, 0));
+        let mut pitches = 0;
+        let mut half_inning = None;
+        for e in &gc.events {
+            // A PA interrupted by the third out resumes with a fresh count next inning
+            let current_half_inning = Some((e.context.inning, e.context.frame));
+            if half_inning != current_half_inning {
+                count = Some((0, 0));
+                pitches = 0;
+                half_inning = current_half_inning;
+            }
+            for psi in e.results.pitch_sequence.iter() {
+                let pitch_type = psi.pitch_type;
+                rows.push(Self {
+                    enrichment: Some(PitchEnrichment::new(pitch_type, count, pitches)),
+                    ..Self::new(gc, e, psi)
+                });
+                if !pitch_type.is_pitch() {
+                    continue;
+                }
+                pitches += 1;
+                count = count.and_then(|(balls, strikes)| {
+                    if pitch_type.is_unknown() {
+                        None
+                    } else if pitch_type.is_ball() {
+                        Some((balls + 1, strikes))
+                    } else if pitch_type.is_strike() || (pitch_type.is_foul() && strikes < 2) {
+                        Some((balls, strikes + 1))
+                    } else {
+                        Some((balls, strikes))
+                    }
+                });
+            }
+            if e.results.plate_appearance.is_some() {
+                count = Some((0, 0));
+                pitches = 0;
+            }
+        }
+        rows
+    }
+}
+
+impl Serialize for EventPitchSequences {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let field_count = Self::FIELD_COUNT
+            + self
+                .enrichment
+                .map_or(0, |_| PitchEnrichment::FIELD_COUNT);
+        let mut state = serializer.serialize_struct("EventPitchSequences", field_count)?;
+        state.serialize_field("game_id", &self.game_id)?;
+        state.serialize_field("event_id", &self.event_id)?;
+        state.serialize_field("event_key", &self.event_key)?;
+        state.serialize_field("sequence_id", &self.sequence_id)?;
+        state.serialize_field("sequence_item", &self.sequence_item)?;
+        state.serialize_field("runners_going_flag", &self.runners_going_flag)?;
+        state.serialize_field("blocked_by_catcher_flag", &self.blocked_by_catcher_flag)?;
+        state.serialize_field(
+            "catcher_pickoff_attempt_at_base",
+            &self.catcher_pickoff_attempt_at_base,
+        )?;
+        if let Some(enrichment) = self.enrichment {
+            enrichment.serialize_fields(&mut state)?;
+        }
+        state.end()
+    }
 }
 
 impl ContextToVec<'_> for EventPitchSequences {
     fn from_game_context(gc: &GameContext) -> Box<dyn Iterator<Item = Self> + '_> {
         let pitch_sequences = gc.events.iter().flat_map(move |e| {
-            e.results.pitch_sequence.iter().map(move |psi| Self {
-                game_id: gc.game_id.id,
-                event_id: e.event_id,
-                event_key: e.event_key,
-                sequence_id: psi.sequence_id,
-                sequence_item: psi.pitch_type,
-                runners_going_flag: psi.runners_going,
-                blocked_by_catcher_flag: psi.blocked_by_catcher,
-                catcher_pickoff_attempt_at_base: psi.catcher_pickoff_attempt,
-            })
+            e.results
+                .pitch_sequence
+                .iter()
+                .map(move |psi| Self::new(gc, e, psi))
         });
         Box::from(pitch_sequences)
     }
 }
 
+/// Analytical categories for a single pitch, based on its type and the count it was
+/// thrown in. The count-based flags are empty once the count can no longer be
+/// reconstructed, e.g. after an unknown pitch.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Deserialize)]
+pub struct PitchEnrichment {
+    balls: Option<u8>,
+    strikes: Option<u8>,
+    in_zone_proxy: Option<bool>,
+    first_pitch_flag: bool,
+    payoff_pitch_flag: Option<bool>,
+    three_zero_take_flag: Option<bool>,
+}
+
+impl PitchEnrichment {
+    const FIELD_COUNT: usize = 6;
+
+    fn new(pitch_type: PitchType, count: Option<(u8, u8)>, previous_pitches: usize) -> Self {
+        let is_pitch = pitch_type.is_pitch();
+        Self {
+            balls: count.map(|(b, _)| b),
+            strikes: count.map(|(_, s)| s),
+            in_zone_proxy: pitch_type.in_zone_proxy(),
+            first_pitch_flag: is_pitch && previous_pitches == 0,
+            payoff_pitch_flag: count.map(|c| is_pitch && c == (3, 2)),
+            three_zero_take_flag: count.map(|c| c == (3, 0) && pitch_type.is_take()),
+        }
+    }
+
+    fn serialize_fields<S: SerializeStruct>(self, state: &mut S) -> Result<(), S::Error> {
+        state.serialize_field("balls", &self.balls)?;
+        state.serialize_field("strikes", &self.strikes)?;
+        state.serialize_field("in_zone_proxy", &self.in_zone_proxy)?;
+        state.serialize_field("first_pitch_flag", &self.first_pitch_flag)?;
+        state.serialize_field("payoff_pitch_flag", &self.payoff_pitch_flag)?;
+        state.serialize_field("three_zero_take_flag", &self.three_zero_take_flag)?;
+        Ok(())
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub struct EventFieldingPlays {
     game_id: GameIdString,
     event_id: EventId,
     event_key: EventKey,
     sequence_id: usize,
-    fielding_position: FieldingPosition,
+    fielding_position: Option<FieldingPosition>,
     fielding_play: FieldingPlayType,
+    /// The fielder was recorded as `?` or `99`, so the credit assignment is a guess
+    unknown_fielder_flag: bool,
+}
+
+impl EventFieldingPlays {
+    pub const fn without_unknown_fielder(self) -> Self {
+        if self.unknown_fielder_flag {
+            Self {
+                fielding_position: None,
+                ..self
+            }
+        } else {
+            self
+        }
+    }
 }
 
 impl ContextToVec<'_> for EventFieldingPlays {
@@ -343,8 +1286,9 @@ impl ContextToVec<'_> for EventFieldingPlays {
                     event_id: e.event_id,
                     event_key: e.event_key,
                     sequence_id: i + 1,
-                    fielding_position: fp.fielding_position,
+                    fielding_position: Some(fp.fielding_position),
                     fielding_play: fp.fielding_play_type,
+                    unknown_fielder_flag: fp.fielding_position == FieldingPosition::Unknown,
                 })
         }))
     }
@@ -508,32 +1452,602 @@ impl ContextToVec<'_> for EventBaserunners {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
-pub struct EventComments {
+/// Every pinch hitter and pinch runner, linked to the player they replaced. For pinch
+/// hitters, `plate_appearance_result` is what they did (empty if they were themselves
+/// replaced before the plate appearance ended). `fielding_position` is the first position
+/// they played afterwards, if they stayed in the game.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct PinchAppearances {
     game_id: GameIdString,
-    event_id: EventId,
-    event_key: EventKey,
-    sequence_id: usize,
-    comment: String,
+    side: Side,
+    lineup_position: LineupPosition,
+    player_id: Player,
+    entered_game_as: EnteredGameAs,
+    start_event_id: EventId,
+    replaced_player_id: Option<Player>,
+    plate_appearance_result: Option<PlateAppearanceResultType>,
+    stayed_in_game_flag: bool,
+    fielding_position: Option<FieldingPosition>,
 }
 
-impl ContextToVec<'_> for EventComments {
-    fn from_game_context(gc: &GameContext) -> Box<dyn Iterator<Item = Self> + '_> {
-        Box::from(gc.events.iter().enumerate().flat_map(move |(i, e)| {
-            e.results.comment.iter().map(move |c| Self {
-                game_id: gc.game_id.id,
-                event_id: e.event_id,
-                event_key: e.event_key,
-                sequence_id: i + 1,
-                comment: c.clone(),
+impl PinchAppearances {
+    fn new(gc: &GameContext, la: &GameLineupAppearance) -> Self {
+        let replaced_player_id = gc
+            .lineup_appearances
+            .iter()
+            .filter(|prev| {
+                prev.side == la.side
+                    && prev.lineup_position == la.lineup_position
+                    && prev.start_event_id < la.start_event_id
             })
-        }))
-    }
-}
-
-#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
-pub struct BoxScoreComments {
-    game_id: GameIdString,
+            .max_by_key(|prev| prev.start_event_id)
+            .map(|prev| prev.player_id);
+        let plate_appearance_result = if la.entered_game_as == EnteredGameAs::PinchHitter {
+            gc.events
+                .iter()
+                .filter(|e| e.event_id >= la.start_event_id && e.context.batter_id == la.player_id)
+                .find_map(|e| e.results.plate_appearance)
+        } else {
+            None
+        };
+        let fielding_position = gc
+            .fielding_appearances
+            .iter()
+            .filter(|fa| {
+                fa.side == la.side
+                    && fa.player_id == la.player_id
+                    && fa.start_event_id >= la.start_event_id
+            })
+            .min_by_key(|fa| fa.start_event_id)
+            .map(|fa| fa.fielding_position);
+        Self {
+            game_id: gc.game_id.id,
+            side: la.side,
+            lineup_position: la.lineup_position,
+            player_id: la.player_id,
+            entered_game_as: la.entered_game_as,
+            start_event_id: la.start_event_id,
+            replaced_player_id,
+            plate_appearance_result,
+            stayed_in_game_flag: fielding_position.is_some(),
+            fielding_position,
+        }
+    }
+}
+
+impl ContextToVec<'_> for PinchAppearances {
+    fn from_game_context(gc: &GameContext) -> Box<dyn Iterator<Item = Self> + '_> {
+        Box::from(
+            gc.lineup_appearances
+                .iter()
+                .filter(|la| {
+                    matches!(
+                        la.entered_game_as,
+                        EnteredGameAs::PinchHitter | EnteredGameAs::PinchRunner
+                    )
+                })
+                .map(|la| Self::new(gc, la)),
+        )
+    }
+}
+
+/// The events a pitcher pitched in one continuous stay on the mound, numbered per side
+/// in the order the stints started. Never empty.
+struct PitchingStint<'a> {
+    side: Side,
+    pitcher_id: Pitcher,
+    stint: u8,
+    events: Vec<&'a E>,
+}
+
+impl<'a> PitchingStint<'a> {
+    fn from_game_context(gc: &'a GameContext) -> impl Iterator<Item = Self> + 'a {
+        [Side::Away, Side::Home].into_iter().flat_map(move |side| {
+            gc.fielding_appearances
+                .iter()
+                .filter(move |fa| {
+                    fa.side == side && fa.fielding_position == FieldingPosition::Pitcher
+                })
+                .sorted_by_key(|fa| fa.start_event_id)
+                .map(|fa| {
+                    gc.events
+                        .iter()
+                        .filter(|e| {
+                            e.event_id >= fa.start_event_id
+                                && fa.end_event_id.is_none_or(|end| e.event_id <= end)
+                                && e.context.pitcher_id == fa.player_id
+                                && e.context.batting_side != fa.side
+                        })
+                        .collect_vec()
+                })
+                .filter(|events| !events.is_empty())
+                .zip(1..)
+                .map(move |(events, stint)| Self {
+                    side,
+                    pitcher_id: events[0].context.pitcher_id,
+                    stint,
+                    events,
+                })
+        })
+    }
+
+    fn first(&self) -> &'a E {
+        self.events[0]
+    }
+
+    fn last(&self) -> &'a E {
+        self.events[self.events.len() - 1]
+    }
+}
+
+/// One row per pitcher stint, from the first event they pitched to the last.
+/// A pitcher who leaves the mound for another position and comes back has two stints.
+/// Inherited runners were on base when the stint started, and bequeathed runners were
+/// left on base when it ended mid-inning; the `_scored` columns count how many of each
+/// came around to score. `pitches` is empty when the stint has no pitch sequence data.
+/// `innings_pitched` is `outs_recorded` in the usual notation, and the `_mid_inning`
+/// flags mark stints that began or ended with the half-inning underway.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct PitchingAppearances {
+    game_id: GameIdString,
+    side: Side,
+    pitcher_id: Pitcher,
+    stint: u8,
+    start_event_id: EventId,
+    end_event_id: EventId,
+    start_inning: Inning,
+    start_outs: Outs,
+    end_inning: Inning,
+    inherited_runners: u8,
+    inherited_runners_scored: u8,
+    bequeathed_runners: u8,
+    bequeathed_runners_scored: u8,
+    batters_faced: u8,
+    pitches: Option<u16>,
+    outs_recorded: u16,
+    innings_pitched: InningsPitched,
+    entered_mid_inning_flag: bool,
+    exited_mid_inning_flag: bool,
+}
+
+impl PitchingAppearances {
+    fn new(gc: &GameContext, stint: &PitchingStint) -> Self {
+        let (first, last) = (stint.first(), stint.last());
+        let is_charged_to_stint = |r: &Runner| {
+            r.explicit_charged_pitcher_id.map_or_else(
+                || (first.event_id..=last.event_id).contains(&r.charge_event_id),
+                |p| p == stint.pitcher_id,
+            )
+        };
+        let inherited_runners_scored = stint
+            .events
+            .iter()
+            .flat_map(|e| e.results.ending_base_state.scored())
+            .filter(|r| r.charge_event_id < first.event_id && !is_charged_to_stint(r))
+            .count();
+        let same_half_inning = |a: &E, b: &E| {
+            a.context.inning == b.context.inning && a.context.frame == b.context.frame
+        };
+        let entered_mid_inning = gc
+            .events
+            .iter()
+            .rev()
+            .find(|e| e.event_id < first.event_id)
+            .is_some_and(|e| same_half_inning(e, first));
+        let next_event = gc
+            .events
+            .iter()
+            .find(|e| e.event_id > last.event_id)
+            .filter(|e| same_half_inning(e, last));
+        let bequeathed_runners =
+            next_event.map_or(0, |e| e.context.starting_base_state.iter_runners().count());
+        let bequeathed_runners_scored = gc
+            .events
+            .iter()
+            .filter(|e| e.event_id > last.event_id && e.context.batting_side != stint.side)
+            .flat_map(|e| e.results.ending_base_state.scored())
+            .filter(|r| is_charged_to_stint(r))
+            .count();
+        let batters_faced = stint
+            .events
+            .iter()
+            .filter(|e| e.results.plate_appearance.is_some())
+            .count();
+        let pitches = stint
+            .events
+            .iter()
+            .flat_map(|e| e.results.pitch_sequence.iter())
+            .filter(|p| p.pitch_type.is_pitch())
+            .count();
+        let outs_recorded = stint
+            .events
+            .iter()
+            .map(|e| e.results.out_on_play.len())
+            .sum::<usize>();
+        let outs_recorded = u16::try_from(outs_recorded).unwrap_or_default();
+        Self {
+            game_id: gc.game_id.id,
+            side: stint.side,
+            pitcher_id: stint.pitcher_id,
+            stint: stint.stint,
+            start_event_id: first.event_id,
+            end_event_id: last.event_id,
+            start_inning: first.context.inning,
+            start_outs: first.context.outs,
+            end_inning: last.context.inning,
+            inherited_runners: u8::try_from(
+                first.context.starting_base_state.iter_runners().count(),
+            )
+            .unwrap_or_default(),
+            inherited_runners_scored: u8::try_from(inherited_runners_scored).unwrap_or_default(),
+            bequeathed_runners: u8::try_from(bequeathed_runners).unwrap_or_default(),
+            bequeathed_runners_scored: u8::try_from(bequeathed_runners_scored).unwrap_or_default(),
+            batters_faced: u8::try_from(batters_faced).unwrap_or_default(),
+            pitches: u16::try_from(pitches).ok().filter(|p| *p > 0),
+            outs_recorded,
+            innings_pitched: InningsPitched::from_outs(outs_recorded),
+            entered_mid_inning_flag: entered_mid_inning,
+            exited_mid_inning_flag: next_event.is_some(),
+        }
+    }
+}
+
+impl ContextToVec<'_> for PitchingAppearances {
+    fn from_game_context(gc: &GameContext) -> Box<dyn Iterator<Item = Self> + '_> {
+        Box::from(PitchingStint::from_game_context(gc).map(|stint| Self::new(gc, &stint)))
+    }
+}
+
+/// Each runner on base when a pitcher took over, and what became of them. The runner is
+/// followed until they score or the half-inning ends, so `scored_flag` is false for
+/// runners who were put out or stranded. `charged_pitcher_id` is the pitcher the runner
+/// was charged to when the stint began; under Rule 9.16(g) that charge can pass to
+/// another runner if this one is forced out, which `pitching_appearances` accounts for.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct InheritedRunners {
+    game_id: GameIdString,
+    side: Side,
+    pitcher_id: Pitcher,
+    stint: u8,
+    start_event_id: EventId,
+    baserunner: BaseRunner,
+    runner_lineup_position: LineupPosition,
+    runner_id: Option<Player>,
+    charge_event_id: EventId,
+    charged_pitcher_id: Option<Pitcher>,
+    scored_flag: bool,
+    scored_event_id: Option<EventId>,
+}
+
+impl InheritedRunners {
+    fn new(
+        gc: &GameContext,
+        stint: &PitchingStint,
+        baserunner: BaseRunner,
+        runner: &Runner,
+    ) -> Self {
+        let first = stint.first();
+        let charged_pitcher_id = runner.explicit_charged_pitcher_id.or_else(|| {
+            gc.events
+                .iter()
+                .find(|e| e.event_id == runner.charge_event_id)
+                .map(|e| e.context.pitcher_id)
+        });
+        let scored_event_id = gc
+            .events
+            .iter()
+            .skip_while(|e| e.event_id < first.event_id)
+            .take_while(|e| {
+                e.context.inning == first.context.inning && e.context.frame == first.context.frame
+            })
+            .find(|e| {
+                e.results.ending_base_state.scored().iter().any(|r| {
+                    (r.lineup_position, r.reached_on_event_id)
+                        == (runner.lineup_position, runner.reached_on_event_id)
+                })
+            })
+            .map(|e| e.event_id);
+        Self {
+            game_id: gc.game_id.id,
+            side: stint.side,
+            pitcher_id: stint.pitcher_id,
+            stint: stint.stint,
+            start_event_id: first.event_id,
+            baserunner,
+            runner_lineup_position: runner.lineup_position,
+            runner_id: GameLineupAppearance::get_at_event(
+                &gc.lineup_appearances,
+                runner.lineup_position,
+                first.event_id,
+                first.context.batting_side,
+            )
+            .ok()
+            .map(|la| la.player_id),
+            charge_event_id: runner.charge_event_id,
+            charged_pitcher_id,
+            scored_flag: scored_event_id.is_some(),
+            scored_event_id,
+        }
+    }
+}
+
+impl ContextToVec<'_> for InheritedRunners {
+    fn from_game_context(gc: &GameContext) -> Box<dyn Iterator<Item = Self> + '_> {
+        Box::from(PitchingStint::from_game_context(gc).flat_map(move |stint| {
+            stint
+                .first()
+                .context
+                .starting_base_state
+                .iter_runners()
+                .map(|(baserunner, runner)| Self::new(gc, &stint, baserunner, runner))
+                .collect_vec()
+        }))
+    }
+}
+
+/// Runners whose charged event changed on a play, which happens under Rule 9.16(g) when
+/// a runner is put out on a fielder's choice or force: the batter takes over the charge
+/// of the runner put out, and trailing runners shift up in the same way. The batter's
+/// original charge is the event itself.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct EventChargeReassignments {
+    game_id: GameIdString,
+    event_id: EventId,
+    event_key: EventKey,
+    baserunner: BaseRunner,
+    runner_lineup_position: LineupPosition,
+    runner_id: Option<Player>,
+    original_charge_event_id: EventId,
+    charge_event_id: EventId,
+}
+
+impl ContextToVec<'_> for EventChargeReassignments {
+    fn from_game_context(gc: &GameContext) -> Box<dyn Iterator<Item = Self> + '_> {
+        Box::from(gc.events.iter().flat_map(move |e| {
+            let starting_state = &e.context.starting_base_state;
+            let ending_state = &e.results.ending_base_state;
+            ending_state
+                .iter_runners()
+                .map(|(_, r)| r)
+                .chain(ending_state.scored())
+                .filter_map(move |runner| {
+                    let (baserunner, original_charge_event_id) = starting_state
+                        .iter_runners()
+                        .find(|(_, r)| r.lineup_position == runner.lineup_position)
+                        .map_or((BaseRunner::Batter, e.event_id), |(br, r)| {
+                            (br, r.charge_event_id)
+                        });
+                    if original_charge_event_id == runner.charge_event_id {
+                        return None;
+                    }
+                    Some(Self {
+                        game_id: gc.game_id.id,
+                        event_id: e.event_id,
+                        event_key: e.event_key,
+                        baserunner,
+                        runner_lineup_position: runner.lineup_position,
+                        runner_id: GameLineupAppearance::get_at_event(
+                            &gc.lineup_appearances,
+                            runner.lineup_position,
+                            e.event_id,
+                            e.context.batting_side,
+                        )
+                        .ok()
+                        .map(|la| la.player_id),
+                        original_charge_event_id,
+                        charge_event_id: runner.charge_event_id,
+                    })
+                })
+        }))
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Serialize, Deserialize)]
+pub enum OutfieldRunnerResult {
+    ThrownOut,
+    Advanced,
+    Held,
+}
+
+/// One row per runner on base when a ball is hit to an outfielder, either for a hit or
+/// a catch, covering both outfield assists and runners held to station-to-station advances.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct EventOutfieldRunnerPlays {
+    game_id: GameIdString,
+    event_id: EventId,
+    event_key: EventKey,
+    fielding_position: FieldingPosition,
+    fielder_id: Option<Player>,
+    baserunner: BaseRunner,
+    runner_id: Option<Player>,
+    expected_base_end: Base,
+    base_end: Option<Base>,
+    outfield_assist_flag: bool,
+    result: OutfieldRunnerResult,
+}
+
+impl EventOutfieldRunnerPlays {
+    /// Number of bases a runner is expected to move on the play without taking an extra base
+    fn station_to_station_bases(event: &E) -> Option<u8> {
+        match event.results.plate_appearance? {
+            PlateAppearanceResultType::Single => Some(1),
+            PlateAppearanceResultType::Double => Some(2),
+            PlateAppearanceResultType::Triple => Some(3),
+            PlateAppearanceResultType::InPlayOut | PlateAppearanceResultType::SacrificeFly
+                if event
+                    .results
+                    .out_on_play
+                    .contains(&BaseRunner::Batter) =>
+            {
+                Some(0)
+            }
+            _ => None,
+        }
+    }
+
+    fn runner(
+        gc: &GameContext,
+        event: &E,
+        baserunner: BaseRunner,
+        fielding_position: FieldingPosition,
+        bases: u8,
+    ) -> Option<Self> {
+        let runner = event.context.starting_base_state.get_runner(baserunner)?;
+        let start: u8 = baserunner.into();
+        // Runners who would score anyway don't test the outfielder
+        let expected_base_end = Base::try_from(start + bases).ok()?;
+        if expected_base_end == Base::Home {
+            return None;
+        }
+        let advance = event
+            .results
+            .baserunning_advances
+            .iter()
+            .find(|a| a.baserunner == baserunner);
+        let is_out = event.results.out_on_play.contains(&baserunner)
+            || advance.is_some_and(|a| !a.is_successful);
+        let base_end = match advance {
+            _ if is_out => None,
+            Some(a) => Some(a.attempted_advance_to),
+            None => baserunner.to_current_base(),
+        };
+        let outfield_assist_flag = is_out
+            && event.results.out_fielders(baserunner).iter().any(|fp| {
+                fp.fielding_play_type == FieldingPlayType::Assist
+                    && fp.fielding_position.is_outfield()
+            });
+        let result = match base_end {
+            None => OutfieldRunnerResult::ThrownOut,
+            Some(b) if b > expected_base_end => OutfieldRunnerResult::Advanced,
+            Some(_) => OutfieldRunnerResult::Held,
+        };
+        Some(Self {
+            game_id: gc.game_id.id,
+            event_id: event.event_id,
+            event_key: event.event_key,
+            fielding_position,
+            fielder_id: GameFieldingAppearance::get_at_event(
+                &gc.fielding_appearances,
+                fielding_position,
+                event.event_id,
+                event.context.batting_side.flip(),
+            )
+            .ok()
+            .map(|fa| fa.player_id),
+            baserunner,
+            runner_id: GameLineupAppearance::get_at_event(
+                &gc.lineup_appearances,
+                runner.lineup_position,
+                event.event_id,
+                event.context.batting_side,
+            )
+            .ok()
+            .map(|la| la.player_id),
+            expected_base_end,
+            base_end,
+            outfield_assist_flag,
+            result,
+        })
+    }
+}
+
+impl ContextToVec<'_> for EventOutfieldRunnerPlays {
+    fn from_game_context(gc: &GameContext) -> Box<dyn Iterator<Item = Self> + '_> {
+        let runners = [BaseRunner::First, BaseRunner::Second, BaseRunner::Third];
+        Box::from(gc.events.iter().flat_map(move |e| {
+            let fielding_position = e
+                .results
+                .batted_ball_info
+                .as_ref()
+                .and_then(|bb| bb.hit_to_fielder)
+                .filter(|fp| fp.is_outfield());
+            let bases = Self::station_to_station_bases(e);
+            runners.into_iter().filter_map(move |r| {
+                Self::runner(gc, e, r, fielding_position?, bases?)
+            })
+        }))
+    }
+}
+
+/// The full defensive alignment at the start of each event
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct EventFieldingAlignments {
+    game_id: GameIdString,
+    event_id: EventId,
+    event_key: EventKey,
+    fielding_side: Side,
+    pitcher_id: Option<Player>,
+    catcher_id: Option<Player>,
+    first_baseman_id: Option<Player>,
+    second_baseman_id: Option<Player>,
+    third_baseman_id: Option<Player>,
+    shortstop_id: Option<Player>,
+    left_fielder_id: Option<Player>,
+    center_fielder_id: Option<Player>,
+    right_fielder_id: Option<Player>,
+}
+
+impl ContextToVec<'_> for EventFieldingAlignments {
+    fn from_game_context(gc: &GameContext) -> Box<dyn Iterator<Item = Self> + '_> {
+        Box::from(gc.events.iter().map(move |e| {
+            let fielding_side = e.context.batting_side.flip();
+            let fielder = |position| {
+                GameFieldingAppearance::get_at_event(
+                    &gc.fielding_appearances,
+                    position,
+                    e.event_id,
+                    fielding_side,
+                )
+                .ok()
+                .map(|a| a.player_id)
+            };
+            Self {
+                game_id: gc.game_id.id,
+                event_id: e.event_id,
+                event_key: e.event_key,
+                fielding_side,
+                pitcher_id: fielder(FieldingPosition::Pitcher),
+                catcher_id: fielder(FieldingPosition::Catcher),
+                first_baseman_id: fielder(FieldingPosition::FirstBaseman),
+                second_baseman_id: fielder(FieldingPosition::SecondBaseman),
+                third_baseman_id: fielder(FieldingPosition::ThirdBaseman),
+                shortstop_id: fielder(FieldingPosition::Shortstop),
+                left_fielder_id: fielder(FieldingPosition::LeftFielder),
+                center_fielder_id: fielder(FieldingPosition::CenterFielder),
+                right_fielder_id: fielder(FieldingPosition::RightFielder),
+            }
+        }))
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct EventComments {
+    game_id: GameIdString,
+    event_id: EventId,
+    event_key: EventKey,
+    sequence_id: usize,
+    comment: String,
+    category: CommentCategory,
+}
+
+impl ContextToVec<'_> for EventComments {
+    fn from_game_context(gc: &GameContext) -> Box<dyn Iterator<Item = Self> + '_> {
+        Box::from(gc.events.iter().enumerate().flat_map(move |(i, e)| {
+            e.results.comment.iter().map(move |c| Self {
+                game_id: gc.game_id.id,
+                event_id: e.event_id,
+                event_key: e.event_key,
+                sequence_id: i + 1,
+                comment: c.clone(),
+                category: CommentCategory::classify(c),
+            })
+        }))
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BoxScoreComments {
+    game_id: GameIdString,
     sequence_id: usize,
     comment: String,
 }
@@ -556,38 +2070,571 @@ impl BoxScoreComments {
     }
 }
 
-#[derive(Debug, Serialize, Clone)]
-pub struct BoxScoreWritableRecord<'a> {
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Deserialize)]
+pub struct BoxScoreBattingLines {
     pub game_id: GameIdString,
-    #[serde(with = "either::serde_untagged")]
-    pub record: Either<&'a BoxScoreLine, &'a BoxScoreEvent>,
+    pub batter_id: Batter,
+    pub side: Side,
+    pub lineup_position: LineupPosition,
+    pub nth_player_at_position: u8,
+    pub batting_stats: BattingLineStats,
 }
 
-impl BoxScoreWritableRecord<'_> {
-    fn map_to_header(map: &Map<String, Value>) -> Result<Vec<String>> {
-        let mut header = vec![];
-        for (k, v) in map {
-            match v {
-                Value::Object(m) => {
-                    header.extend(Self::map_to_header(m)?);
-                }
-                Value::Array(_) => bail!("Cannot make header out of struct with vec"),
-                _ => header.push(k.clone()),
+impl BoxScoreBattingLines {
+    pub const fn new(game_id: GameIdString, line: &BattingLine) -> Self {
+        Self {
+            game_id,
+            batter_id: line.batter_id,
+            side: line.side,
+            lineup_position: line.lineup_position,
+            nth_player_at_position: line.nth_player_at_position,
+            batting_stats: line.batting_stats,
+        }
+    }
+}
+
+impl Serialize for BoxScoreBattingLines {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer
+            .serialize_struct("BoxScoreBattingLines", 5 + BattingLineStats::FIELD_COUNT)?;
+        state.serialize_field("game_id", &self.game_id)?;
+        state.serialize_field("batter_id", &self.batter_id)?;
+        state.serialize_field("side", &self.side)?;
+        state.serialize_field("lineup_position", &self.lineup_position)?;
+        state.serialize_field("nth_player_at_position", &self.nth_player_at_position)?;
+        self.batting_stats.serialize_fields(&mut state)?;
+        state.end()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Deserialize)]
+pub struct BoxScorePinchHittingLines {
+    pub game_id: GameIdString,
+    pub pinch_hitter_id: Batter,
+    pub inning: Option<Inning>,
+    pub side: Side,
+    pub batting_stats: BattingLineStats,
+}
+
+impl BoxScorePinchHittingLines {
+    pub const fn new(game_id: GameIdString, line: &PinchHittingLine) -> Self {
+        Self {
+            game_id,
+            pinch_hitter_id: line.pinch_hitter_id,
+            inning: line.inning,
+            side: line.side,
+            batting_stats: line.batting_stats,
+        }
+    }
+}
+
+impl Serialize for BoxScorePinchHittingLines {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer
+            .serialize_struct("BoxScorePinchHittingLines", 4 + BattingLineStats::FIELD_COUNT)?;
+        state.serialize_field("game_id", &self.game_id)?;
+        state.serialize_field("pinch_hitter_id", &self.pinch_hitter_id)?;
+        state.serialize_field("inning", &self.inning)?;
+        state.serialize_field("side", &self.side)?;
+        self.batting_stats.serialize_fields(&mut state)?;
+        state.end()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct BoxScorePinchRunningLines {
+    pub game_id: GameIdString,
+    pub pinch_runner_id: Batter,
+    pub inning: Option<Inning>,
+    pub side: Side,
+    pub runs: Option<u8>,
+    pub stolen_bases: Option<u8>,
+    pub caught_stealing: Option<u8>,
+}
+
+impl BoxScorePinchRunningLines {
+    pub const fn new(game_id: GameIdString, line: &PinchRunningLine) -> Self {
+        Self {
+            game_id,
+            pinch_runner_id: line.pinch_runner_id,
+            inning: line.inning,
+            side: line.side,
+            runs: line.runs,
+            stolen_bases: line.stolen_bases,
+            caught_stealing: line.caught_stealing,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Deserialize)]
+pub struct BoxScorePitchingLines {
+    pub game_id: GameIdString,
+    pub pitcher_id: Pitcher,
+    pub side: Side,
+    pub nth_pitcher: u8,
+    pub pitching_stats: PitchingLineStats,
+}
+
+impl BoxScorePitchingLines {
+    pub const fn new(game_id: GameIdString, line: &PitchingLine) -> Self {
+        Self {
+            game_id,
+            pitcher_id: line.pitcher_id,
+            side: line.side,
+            nth_pitcher: line.nth_pitcher,
+            pitching_stats: line.pitching_stats,
+        }
+    }
+}
+
+impl Serialize for BoxScorePitchingLines {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer
+            .serialize_struct("BoxScorePitchingLines", 4 + PitchingLineStats::FIELD_COUNT)?;
+        state.serialize_field("game_id", &self.game_id)?;
+        state.serialize_field("pitcher_id", &self.pitcher_id)?;
+        state.serialize_field("side", &self.side)?;
+        state.serialize_field("nth_pitcher", &self.nth_pitcher)?;
+        self.pitching_stats.serialize_fields(&mut state)?;
+        state.end()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Deserialize)]
+pub struct BoxScoreFieldingLines {
+    pub game_id: GameIdString,
+    pub fielder_id: Fielder,
+    pub side: Side,
+    pub fielding_position: FieldingPosition,
+    pub nth_position_played_by_player: u8,
+    // Missing stats are written as empty fields rather than dropping the columns
+    pub defensive_stats: DefenseLineStats,
+}
+
+impl BoxScoreFieldingLines {
+    pub fn new(game_id: GameIdString, line: &DefenseLine) -> Self {
+        Self {
+            game_id,
+            fielder_id: line.fielder_id,
+            side: line.side,
+            fielding_position: line.fielding_position,
+            nth_position_played_by_player: line.nth_position_played_by_player,
+            defensive_stats: line.defensive_stats.unwrap_or_default(),
+        }
+    }
+}
+
+impl Serialize for BoxScoreFieldingLines {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer
+            .serialize_struct("BoxScoreFieldingLines", 5 + DefenseLineStats::FIELD_COUNT)?;
+        state.serialize_field("game_id", &self.game_id)?;
+        state.serialize_field("fielder_id", &self.fielder_id)?;
+        state.serialize_field("side", &self.side)?;
+        state.serialize_field("fielding_position", &self.fielding_position)?;
+        state.serialize_field(
+            "nth_position_played_by_player",
+            &self.nth_position_played_by_player,
+        )?;
+        self.defensive_stats.serialize_fields(&mut state)?;
+        state.end()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct BoxScoreTeamMiscellaneousLines {
+    pub game_id: GameIdString,
+    pub side: Side,
+    pub left_on_base: Option<u8>,
+    pub team_earned_runs: Option<u8>,
+    pub double_plays_turned: Option<u8>,
+    pub triple_plays_turned: Option<u8>,
+}
+
+impl BoxScoreTeamMiscellaneousLines {
+    pub const fn new(game_id: GameIdString, line: &TeamMiscellaneousLine) -> Self {
+        Self {
+            game_id,
+            side: line.side,
+            left_on_base: line.left_on_base,
+            team_earned_runs: line.team_earned_runs,
+            double_plays_turned: line.double_plays_turned,
+            triple_plays_turned: line.triple_plays_turned,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Deserialize)]
+pub struct BoxScoreTeamBattingLines {
+    pub game_id: GameIdString,
+    pub side: Side,
+    pub batting_stats: BattingLineStats,
+}
+
+impl BoxScoreTeamBattingLines {
+    pub const fn new(game_id: GameIdString, line: &TeamBattingLine) -> Self {
+        Self {
+            game_id,
+            side: line.side,
+            batting_stats: line.batting_stats,
+        }
+    }
+}
+
+impl Serialize for BoxScoreTeamBattingLines {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer
+            .serialize_struct("BoxScoreTeamBattingLines", 2 + BattingLineStats::FIELD_COUNT)?;
+        state.serialize_field("game_id", &self.game_id)?;
+        state.serialize_field("side", &self.side)?;
+        self.batting_stats.serialize_fields(&mut state)?;
+        state.end()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Deserialize)]
+pub struct BoxScoreTeamFieldingLines {
+    pub game_id: GameIdString,
+    pub side: Side,
+    pub defensive_stats: DefenseLineStats,
+}
+
+impl BoxScoreTeamFieldingLines {
+    pub const fn new(game_id: GameIdString, line: &TeamDefenseLine) -> Self {
+        Self {
+            game_id,
+            side: line.side,
+            defensive_stats: line.defensive_stats,
+        }
+    }
+}
+
+impl Serialize for BoxScoreTeamFieldingLines {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer
+            .serialize_struct("BoxScoreTeamFieldingLines", 2 + DefenseLineStats::FIELD_COUNT)?;
+        state.serialize_field("game_id", &self.game_id)?;
+        state.serialize_field("side", &self.side)?;
+        self.defensive_stats.serialize_fields(&mut state)?;
+        state.end()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum TeamLineType {
+    Batting,
+    Fielding,
+}
+
+/// A stat on a box score's team batting or fielding line that doesn't equal the sum of
+/// the players' lines. Stats missing from the team line or from any player's line
+/// are skipped.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct BoxScoreReconciliation {
+    game_id: GameIdString,
+    side: Side,
+    line_type: TeamLineType,
+    stat: &'static str,
+    team_total: u16,
+    player_total: u16,
+    /// Team total minus player total
+    delta: i32,
+}
+
+impl BoxScoreReconciliation {
+    fn discrepancies<const N: usize>(
+        game_id: GameIdString,
+        side: Side,
+        line_type: TeamLineType,
+        team: [(&'static str, Option<u8>); N],
+        players: impl Iterator<Item = [(&'static str, Option<u8>); N]>,
+    ) -> impl Iterator<Item = Self> {
+        let mut player_totals = [Some(0_u16); N];
+        for player in players {
+            for (total, (_, stat)) in player_totals.iter_mut().zip(player) {
+                *total = total.zip(stat).map(|(t, s)| t + u16::from(s));
             }
         }
-        Ok(header)
+        team.into_iter()
+            .zip(player_totals)
+            .filter_map(move |((stat, team_total), player_total)| {
+                let (team_total, player_total) = (u16::from(team_total?), player_total?);
+                let delta = i32::from(team_total) - i32::from(player_total);
+                (delta != 0).then_some(Self {
+                    game_id,
+                    side,
+                    line_type,
+                    stat,
+                    team_total,
+                    player_total,
+                    delta,
+                })
+            })
     }
+}
 
-    pub fn generate_header(&self) -> Result<Vec<String>> {
-        let map = serde_json::to_value(self)?
-            .as_object()
-            .context("Unable to generate object")?
-            .clone();
-        Self::map_to_header(&map)
+impl<'a> ContextToVec<'a> for BoxScoreReconciliation {
+    fn from_game_context(gc: &'a GameContext) -> Box<dyn Iterator<Item = Self> + 'a> {
+        let Some(data) = &gc.box_score_data else {
+            return Box::new(std::iter::empty());
+        };
+        let game_id = gc.game_id.id;
+        let lines = &data.lines;
+        Box::new([Side::Away, Side::Home].into_iter().flat_map(move |side| {
+            let batting = lines.iter().find_map(|l| match l {
+                BoxScoreLine::TeamBattingLine(t) if t.side == side => Some(t.batting_stats),
+                _ => None,
+            });
+            let fielding = lines.iter().find_map(|l| match l {
+                BoxScoreLine::TeamDefenseLine(t) if t.side == side => Some(t.defensive_stats),
+                _ => None,
+            });
+            let batting = batting.into_iter().flat_map(move |team| {
+                let players = lines.iter().filter_map(move |l| match l {
+                    BoxScoreLine::BattingLine(b) if b.side == side => {
+                        Some(b.batting_stats.summable_stats())
+                    }
+                    _ => None,
+                });
+                let team = team.summable_stats();
+                Self::discrepancies(game_id, side, TeamLineType::Batting, team, players)
+            });
+            let fielding = fielding.into_iter().flat_map(move |team| {
+                let players = lines.iter().filter_map(move |l| match l {
+                    BoxScoreLine::DefenseLine(d) if d.side == side => {
+                        Some(d.defensive_stats.unwrap_or_default().summable_stats())
+                    }
+                    _ => None,
+                });
+                let team = team.summable_stats();
+                Self::discrepancies(game_id, side, TeamLineType::Fielding, team, players)
+            });
+            batting.chain(fielding)
+        }))
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum ReplayCheck {
+    /// Runs in the half-inning as tracked by the game state, against runners who
+    /// advanced home
+    HalfInningRuns,
+    /// Outs in the half-inning as tracked by the game state, against putouts credited
+    HalfInningOuts,
+    /// Runs in the inning on the game's `line` record, against runners who advanced home
+    LineScoreRuns,
+    /// The total on the game's `line` record, against runners who advanced home
+    FinalScore,
+}
+
+/// Disagreements found by replaying a play-by-play game through a second, simpler
+/// scorekeeper: runs are counted from runners who advanced home and outs from putouts,
+/// without any of the state tracked while parsing. These are checked against the game
+/// state and, when the game has `line` records, against the line score.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct ReplayReconciliation {
+    game_id: GameIdString,
+    side: Side,
+    /// Blank for checks of the whole game
+    inning: Option<u8>,
+    check: ReplayCheck,
+    recorded: u16,
+    replayed: u16,
+    /// Recorded minus replayed
+    delta: i32,
+}
+
+impl ReplayReconciliation {
+    pub fn from_record_slice(gc: &GameContext, slice: &RecordSlice) -> Vec<Self> {
+        let mut half_innings: Vec<(u8, Side, [u16; 4])> = vec![];
+        for e in &gc.events {
+            let key = (e.context.inning, e.context.batting_side);
+            if half_innings.last().map(|(i, s, _)| (*i, *s)) != Some(key) {
+                half_innings.push((key.0, key.1, [0; 4]));
+            }
+            let Some((_, _, [runs, replayed_runs, outs, putouts])) = half_innings.last_mut()
+            else {
+                continue;
+            };
+            let count = |n: usize| u16::try_from(n).unwrap_or(u16::MAX);
+            *runs += count(e.results.runs.len());
+            *replayed_runs += count(
+                e.results
+                    .baserunning_advances
+                    .iter()
+                    .filter(|a| a.scored())
+                    .count(),
+            );
+            *outs += count(e.results.out_on_play.len());
+            *putouts += count(
+                e.results
+                    .fielding_plays
+                    .iter()
+                    .filter(|fp| fp.fielding_play_type == FieldingPlayType::Putout)
+                    .count(),
+            );
+        }
+        let row = |side, inning, check, recorded: u16, replayed: u16| {
+            let delta = i32::from(recorded) - i32::from(replayed);
+            (delta != 0).then_some(Self {
+                game_id: gc.game_id.id,
+                side,
+                inning,
+                check,
+                recorded,
+                replayed,
+                delta,
+            })
+        };
+        let mut rows = half_innings
+            .iter()
+            .flat_map(|&(inning, side, [runs, replayed_runs, outs, putouts])| {
+                [
+                    row(side, Some(inning), ReplayCheck::HalfInningRuns, runs, replayed_runs),
+                    row(side, Some(inning), ReplayCheck::HalfInningOuts, outs, putouts),
+                ]
+            })
+            .flatten()
+            .collect_vec();
+        let line_scores = slice.iter().filter_map(|r| match r {
+            MappedRecord::LineScore(ls) => Some(ls),
+            _ => None,
+        });
+        for line_score in line_scores {
+            let side = line_score.side;
+            let replayed = |inning: u8| {
+                half_innings
+                    .iter()
+                    .filter(|(i, s, _)| *i == inning && *s == side)
+                    .map(|(_, _, [_, replayed_runs, _, _])| replayed_runs)
+                    .sum::<u16>()
+            };
+            for (inning, runs) in (1..).zip(&line_score.line_score) {
+                rows.extend(row(
+                    side,
+                    Some(inning),
+                    ReplayCheck::LineScoreRuns,
+                    u16::from(*runs),
+                    replayed(inning),
+                ));
+            }
+            let total = line_score.line_score.iter().copied().map(u16::from).sum();
+            let replayed_total = half_innings
+                .iter()
+                .filter(|(_, s, _)| *s == side)
+                .map(|(_, _, [_, replayed_runs, _, _])| replayed_runs)
+                .sum();
+            rows.extend(row(side, None, ReplayCheck::FinalScore, total, replayed_total));
+        }
+        rows
+    }
+}
+
+/// Used for both double and triple plays
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BoxScoreFieldingPlays<'a> {
+    pub game_id: GameIdString,
+    pub defense_side: Side,
+    pub fielders: &'a str,
+}
+
+impl<'a> BoxScoreFieldingPlays<'a> {
+    pub fn new(game_id: GameIdString, line: &'a FieldingPlayLine) -> Self {
+        Self {
+            game_id,
+            defense_side: line.defense_side,
+            fielders: &line.fielders,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct BoxScoreHitByPitches {
+    pub game_id: GameIdString,
+    pub pitching_side: Side,
+    pub pitcher_id: Option<Pitcher>,
+    pub batter_id: Batter,
+}
+
+impl BoxScoreHitByPitches {
+    pub const fn new(game_id: GameIdString, line: &HitByPitchLine) -> Self {
+        Self {
+            game_id,
+            pitching_side: line.pitching_side,
+            pitcher_id: line.pitcher_id,
+            batter_id: line.batter_id,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct BoxScoreHomeRuns {
+    pub game_id: GameIdString,
+    pub batting_side: Side,
+    pub batter_id: Batter,
+    pub pitcher_id: Pitcher,
+    pub inning: Option<Inning>,
+    pub runners_on: Option<u8>,
+    pub outs: Option<u8>,
+}
+
+impl BoxScoreHomeRuns {
+    pub const fn new(game_id: GameIdString, line: &HomeRunLine) -> Self {
+        Self {
+            game_id,
+            batting_side: line.batting_side,
+            batter_id: line.batter_id,
+            pitcher_id: line.pitcher_id,
+            inning: line.inning,
+            runners_on: line.runners_on,
+            outs: line.outs,
+        }
+    }
+}
+
+/// Used for both stolen bases and caught stealing
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct BoxScoreStolenBaseAttempts {
+    pub game_id: GameIdString,
+    pub running_side: Side,
+    pub runner_id: Batter,
+    pub pitcher_id: Option<Pitcher>,
+    pub catcher_id: Option<Fielder>,
+    pub inning: Option<Inning>,
+}
+
+impl BoxScoreStolenBaseAttempts {
+    pub const fn new(game_id: GameIdString, line: &StolenBaseAttemptLine) -> Self {
+        Self {
+            game_id,
+            running_side: line.running_side,
+            runner_id: line.runner_id,
+            pitcher_id: line.pitcher_id,
+            catcher_id: line.catcher_id,
+            inning: line.inning,
+        }
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Serialize, Clone)]
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone)]
 pub struct BoxScoreLineScores {
     pub game_id: GameIdString,
     pub side: Side,
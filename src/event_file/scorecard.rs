@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use anyhow::Result;
+use itertools::Itertools;
+
+use crate::event_file::aggregates::final_score;
+use crate::event_file::game_state::{
+    EnteredGameAs, Event, GameContext, PlateAppearanceResultType, Runner,
+};
+use crate::event_file::parser::RecordSlice;
+use crate::event_file::pitch_sequence::PitchType;
+use crate::event_file::play::{BaseRunner, Trajectory};
+use crate::event_file::summary::player_names;
+use crate::event_file::traits::{FieldingPosition, LineupPosition, Player, Side};
+
+const STYLE: &str = "
+body { font-family: sans-serif; }
+table.scorecard { border-collapse: collapse; margin-bottom: 2em; }
+table.scorecard th, table.scorecard td { border: 1px solid #888; padding: 2px 4px; }
+td.player { white-space: nowrap; }
+td.inning { vertical-align: top; min-width: 48px; }
+div.pa { position: relative; width: 48px; height: 48px; }
+div.pa span.notation { position: absolute; left: 0; bottom: 0; font-size: 11px; }
+div.pa span.out { position: absolute; right: 0; top: 0; font-size: 10px;
+  border: 1px solid #000; border-radius: 50%; width: 12px; text-align: center; }
+";
+
+/// Corners of the diamond in the SVG for each plate appearance, starting and ending at home
+const DIAMOND_POINTS: [(u8, u8); 5] = [(20, 38), (38, 20), (20, 2), (2, 20), (20, 38)];
+
+/// What goes in a single box of the grid
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct PlateAppearanceMark {
+    notation: String,
+    /// Furthest base the batter reached, with 4 meaning they scored
+    bases: usize,
+    /// Which out of the inning the batter made, at the plate or on the bases
+    out_number: Option<usize>,
+}
+
+/// A traditional batter-by-inning scorecard for a single game, rendered as a
+/// standalone HTML page with an SVG diamond for each plate appearance
+pub struct Scorecard<'a> {
+    gc: &'a GameContext,
+    names: HashMap<Player, &'a str>,
+}
+
+impl<'a> Scorecard<'a> {
+    pub fn new(gc: &'a GameContext, record_slice: &'a RecordSlice) -> Self {
+        Self {
+            gc,
+            names: player_names(record_slice),
+        }
+    }
+
+    pub fn to_html(&self) -> Result<String> {
+        let score = final_score(self.gc);
+        let mut out = String::new();
+        writeln!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">"
+        )?;
+        writeln!(out, "<title>{}</title>", self.gc.game_id.id)?;
+        writeln!(out, "<style>{STYLE}</style>\n</head>\n<body>")?;
+        writeln!(
+            out,
+            "<h1>{} {} at {} {}</h1>",
+            self.gc.teams.away, score.away, self.gc.teams.home, score.home
+        )?;
+        writeln!(
+            out,
+            "<p>{} at {} ({})</p>",
+            self.gc.setting.date, self.gc.setting.park_id, self.gc.game_id.id
+        )?;
+        for side in [Side::Away, Side::Home] {
+            writeln!(out, "<h2>{}</h2>", self.gc.teams.get(side))?;
+            self.write_grid(&mut out, side)?;
+        }
+        writeln!(out, "</body>\n</html>")?;
+        Ok(out)
+    }
+
+    fn write_grid(&self, out: &mut String, side: Side) -> Result<()> {
+        let innings = self
+            .gc
+            .events
+            .iter()
+            .map(|e| e.context.inning)
+            .max()
+            .unwrap_or_default()
+            .max(9);
+        writeln!(out, "<table class=\"scorecard\">")?;
+        write!(out, "<tr><th>#</th><th>Batter</th>")?;
+        for inning in 1..=innings {
+            write!(out, "<th>{inning}</th>")?;
+        }
+        writeln!(out, "</tr>")?;
+
+        let slots = self
+            .gc
+            .lineup_appearances
+            .iter()
+            .filter(|la| la.side == side && la.lineup_position != LineupPosition::PitcherWithDh)
+            .map(|la| la.lineup_position)
+            .unique()
+            .sorted();
+        for slot in slots {
+            let position: u8 = slot.into();
+            write!(
+                out,
+                "<tr><td>{position}</td><td class=\"player\">{}</td>",
+                self.players_in_slot(side, slot).join("<br>")
+            )?;
+            for inning in 1..=innings {
+                write!(out, "<td class=\"inning\">")?;
+                for (i, _) in self.gc.events.iter().enumerate().filter(|(_, e)| {
+                    e.context.batting_side == side
+                        && e.context.inning == inning
+                        && e.context.at_bat == slot
+                        && e.results.plate_appearance.is_some()
+                }) {
+                    write!(out, "{}", Self::mark_html(&self.mark(i)))?;
+                }
+                write!(out, "</td>")?;
+            }
+            writeln!(out, "</tr>")?;
+        }
+
+        write!(out, "<tr><td></td><td>R/H</td>")?;
+        for inning in 1..=innings {
+            let half = self
+                .gc
+                .events
+                .iter()
+                .filter(|e| e.context.batting_side == side && e.context.inning == inning)
+                .collect_vec();
+            if half.is_empty() {
+                write!(out, "<td></td>")?;
+                continue;
+            }
+            let runs: usize = half.iter().map(|e| e.results.runs.len()).sum();
+            let hits = half
+                .iter()
+                .filter(|e| {
+                    e.results
+                        .plate_appearance
+                        .is_some_and(PlateAppearanceResultType::is_hit)
+                })
+                .count();
+            write!(out, "<td>{runs}/{hits}</td>")?;
+        }
+        writeln!(out, "</tr>\n</table>")?;
+        Ok(())
+    }
+
+    /// Each player who batted in the slot, with the positions they played
+    fn players_in_slot(&self, side: Side, slot: LineupPosition) -> Vec<String> {
+        self.gc
+            .lineup_appearances
+            .iter()
+            .filter(|la| la.side == side && la.lineup_position == slot)
+            .sorted_by_key(|la| la.start_event_id)
+            .map(|la| {
+                let entry = match la.entered_game_as {
+                    EnteredGameAs::PinchHitter => Some(String::from("PH")),
+                    EnteredGameAs::PinchRunner => Some(String::from("PR")),
+                    _ => None,
+                };
+                let fielding = self
+                    .gc
+                    .fielding_appearances
+                    .iter()
+                    .filter(|fa| fa.side == side && fa.player_id == la.player_id)
+                    .filter(|fa| fa.fielding_position.is_true_position())
+                    .sorted_by_key(|fa| fa.start_event_id)
+                    .map(|fa| match fa.fielding_position {
+                        FieldingPosition::DesignatedHitter => String::from("DH"),
+                        p => p.retrosheet_string(),
+                    });
+                let positions = entry.into_iter().chain(fielding).join(", ");
+                let name = self
+                    .names
+                    .get(&la.player_id)
+                    .map_or_else(|| la.player_id.to_string(), ToString::to_string);
+                format!("{} ({positions})", escape(&name))
+            })
+            .collect()
+    }
+
+    /// Standard scoring notation for the plate appearance, e.g. `1B`, `6-3`, `F8`, or `K`
+    fn notation(e: &Event) -> String {
+        let Some(pa) = e.results.plate_appearance else {
+            return String::new();
+        };
+        let main_play = e.raw_play.split(['/', '.']).next().unwrap_or_default();
+        // Fielders in the play, skipping the runner markers in parentheses, e.g. `6(1)3`
+        let mut depth = 0_usize;
+        let fielders = main_play
+            .chars()
+            .filter(|c| {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                depth == 0 && c.is_ascii_digit()
+            })
+            .collect_vec();
+        let first_fielder = fielders
+            .first()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        let trajectory = e.results.batted_ball_info.as_ref().map(|bb| bb.trajectory);
+        let notation = match pa {
+            PlateAppearanceResultType::Single => String::from("1B"),
+            PlateAppearanceResultType::Double | PlateAppearanceResultType::GroundRuleDouble => {
+                String::from("2B")
+            }
+            PlateAppearanceResultType::Triple => String::from("3B"),
+            PlateAppearanceResultType::HomeRun
+            | PlateAppearanceResultType::InsideTheParkHomeRun => String::from("HR"),
+            PlateAppearanceResultType::Walk => String::from("BB"),
+            PlateAppearanceResultType::IntentionalWalk => String::from("IBB"),
+            PlateAppearanceResultType::HitByPitch => String::from("HBP"),
+            PlateAppearanceResultType::Interference => String::from("CI"),
+            // Backwards K for a called third strike
+            PlateAppearanceResultType::StrikeOut
+                if e.results.pitch_sequence.last().map(|p| p.pitch_type)
+                    == Some(PitchType::CalledStrike) =>
+            {
+                String::from("\u{A4D8}")
+            }
+            PlateAppearanceResultType::StrikeOut => String::from("K"),
+            PlateAppearanceResultType::ReachedOnError => format!("E{first_fielder}"),
+            PlateAppearanceResultType::FieldersChoice => String::from("FC"),
+            PlateAppearanceResultType::SacrificeFly => format!("SF{first_fielder}"),
+            PlateAppearanceResultType::SacrificeHit => String::from("SH"),
+            PlateAppearanceResultType::InPlayOut => match (fielders.as_slice(), trajectory) {
+                ([f], Some(Trajectory::Fly)) => format!("F{f}"),
+                ([f], Some(Trajectory::LineDrive | Trajectory::LineDriveBunt)) => {
+                    format!("L{f}")
+                }
+                ([f], Some(Trajectory::PopUp | Trajectory::PopUpBunt)) => format!("P{f}"),
+                ([f], _) => format!("{f}U"),
+                _ => fielders.iter().join("-"),
+            },
+        };
+        match e.results.out_on_play.len() {
+            2 => format!("{notation} DP"),
+            3 => format!("{notation} TP"),
+            _ => notation,
+        }
+    }
+
+    /// Follows the batter around the bases for the rest of the half-inning
+    fn mark(&self, index: usize) -> PlateAppearanceMark {
+        let e = &self.gc.events[index];
+        let is_batter = |r: &Runner| {
+            r.lineup_position == e.context.at_bat && r.reached_on_event_id == e.event_id
+        };
+        let half_inning = self.gc.events[index..].iter().take_while(|ev| {
+            ev.context.inning == e.context.inning && ev.context.frame == e.context.frame
+        });
+        let mut mark = PlateAppearanceMark {
+            notation: Self::notation(e),
+            bases: 0,
+            out_number: None,
+        };
+        let mut current_base = BaseRunner::Batter;
+        for ev in half_inning {
+            let ending_state = &ev.results.ending_base_state;
+            if ending_state.scored().iter().any(is_batter) {
+                mark.bases = 4;
+                break;
+            }
+            if let Some((base, _)) = ending_state.iter_runners().find(|(_, r)| is_batter(r)) {
+                current_base = base;
+                mark.bases = mark.bases.max(base_number(base));
+                continue;
+            }
+            // Neither on base nor scored, so either put out or stranded by the end of the inning
+            mark.out_number = ev
+                .results
+                .out_on_play
+                .iter()
+                .position(|br| *br == current_base)
+                .map(|i| ev.context.outs.get() + i + 1);
+            if mark.out_number.is_none() && current_base == BaseRunner::Batter {
+                mark.bases = 1;
+            }
+            break;
+        }
+        mark
+    }
+
+    fn mark_html(mark: &PlateAppearanceMark) -> String {
+        let points = |n: usize| {
+            DIAMOND_POINTS
+                .iter()
+                .take(n)
+                .map(|(x, y)| format!("{x},{y}"))
+                .join(" ")
+        };
+        let path = match mark.bases {
+            0 => String::new(),
+            4 => format!(
+                "<polygon points=\"{}\" fill=\"#444\" stroke=\"#000\" stroke-width=\"2\"/>",
+                points(5)
+            ),
+            n => format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"#000\" stroke-width=\"2\"/>",
+                points(n + 1)
+            ),
+        };
+        let out = mark
+            .out_number
+            .map(|n| format!("<span class=\"out\">{n}</span>"))
+            .unwrap_or_default();
+        format!(
+            "<div class=\"pa\"><svg viewBox=\"0 0 40 40\" width=\"40\" height=\"40\">\
+             <polygon points=\"{}\" fill=\"none\" stroke=\"#bbb\"/>{path}</svg>\
+             <span class=\"notation\">{}</span>{out}</div>",
+            points(4),
+            escape(&mark.notation)
+        )
+    }
+}
+
+const fn base_number(base: BaseRunner) -> usize {
+    match base {
+        BaseRunner::Batter => 0,
+        BaseRunner::First => 1,
+        BaseRunner::Second => 2,
+        BaseRunner::Third => 3,
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
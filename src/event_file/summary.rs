@@ -0,0 +1,403 @@
+use std::collections::HashMap;
+use std::fmt::{Result as FmtResult, Write};
+
+use anyhow::Result;
+use clap::ValueEnum;
+use itertools::Itertools;
+
+use crate::event_file::aggregates::{events_with_score, final_score};
+use crate::event_file::game_state::{Event, GameContext, PlateAppearanceResultType};
+use crate::event_file::misc::InningsPitched;
+use crate::event_file::parser::{MappedRecord, RecordSlice};
+use crate::event_file::play::{BaserunningPlayType, InningFrame};
+use crate::event_file::traits::{FieldingPlayType, FieldingPosition, Player, Side};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default, ValueEnum)]
+pub enum SummaryFormat {
+    #[default]
+    Text,
+    Markdown,
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+struct PitchingLine {
+    outs: usize,
+    hits: usize,
+    runs: usize,
+    walks: usize,
+    strikeouts: usize,
+}
+
+/// Player names as written in the game's own start and sub records
+pub fn player_names(record_slice: &RecordSlice) -> HashMap<Player, &str> {
+    record_slice
+        .iter()
+        .filter_map(|r| match r {
+            MappedRecord::Start(a) | MappedRecord::Substitution(a) => {
+                Some((a.player, a.player_name.as_str()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// A readable recap of a single game: line score, scoring plays, pitching lines,
+/// and other notable events
+pub struct GameSummary<'a> {
+    gc: &'a GameContext,
+    names: HashMap<Player, &'a str>,
+    format: SummaryFormat,
+}
+
+impl<'a> GameSummary<'a> {
+    pub fn new(gc: &'a GameContext, record_slice: &'a RecordSlice, format: SummaryFormat) -> Self {
+        Self {
+            gc,
+            names: player_names(record_slice),
+            format,
+        }
+    }
+
+    pub fn render(&self) -> Result<String> {
+        let score = final_score(self.gc);
+        let mut out = String::new();
+        let title = format!(
+            "{} {}, {} {}",
+            self.gc.teams.away, score.away, self.gc.teams.home, score.home
+        );
+        match self.format {
+            SummaryFormat::Markdown => writeln!(out, "# {title}\n")?,
+            SummaryFormat::Text => writeln!(out, "{title}")?,
+        }
+        writeln!(
+            out,
+            "{} at {} ({})",
+            self.gc.setting.date, self.gc.setting.park_id, self.gc.game_id.id
+        )?;
+        if let Some(info) = &self.gc.results.completion_info {
+            writeln!(out, "Completed later: {info}")?;
+        }
+        if let Some(info) = &self.gc.results.protest_info {
+            writeln!(out, "Protested: {info}")?;
+        }
+        self.section(&mut out, "Line score", &self.line_score()?)?;
+        self.section(&mut out, "Scoring plays", &self.scoring_plays())?;
+        for side in [Side::Away, Side::Home] {
+            let heading = format!("Pitching: {}", self.gc.teams.get(side));
+            self.section(&mut out, &heading, &self.pitching_lines(side)?)?;
+        }
+        self.section(&mut out, "Notable events", &self.notable_events())?;
+        Ok(out)
+    }
+
+    fn name(&self, player: Player) -> String {
+        self.names
+            .get(&player)
+            .map_or_else(|| player.to_string(), ToString::to_string)
+    }
+
+    fn section(&self, out: &mut String, heading: &str, body: &str) -> FmtResult {
+        if body.is_empty() {
+            return Ok(());
+        }
+        match self.format {
+            SummaryFormat::Markdown => writeln!(out, "\n## {heading}\n")?,
+            SummaryFormat::Text => writeln!(out, "\n{heading}\n{}", "-".repeat(heading.len()))?,
+        }
+        out.push_str(body);
+        Ok(())
+    }
+
+    fn list(&self, items: &[String]) -> String {
+        let bullet = match self.format {
+            SummaryFormat::Markdown => "- ",
+            SummaryFormat::Text => "  ",
+        };
+        items
+            .iter()
+            .map(|i| bullet.to_string() + i + "\n")
+            .collect()
+    }
+
+    fn table(&self, header: &[String], rows: &[Vec<String>]) -> Result<String> {
+        let mut out = String::new();
+        match self.format {
+            SummaryFormat::Markdown => {
+                writeln!(out, "| {} |", header.join(" | "))?;
+                writeln!(out, "|{}", "---|".repeat(header.len()))?;
+                for row in rows {
+                    writeln!(out, "| {} |", row.join(" | "))?;
+                }
+            }
+            SummaryFormat::Text => {
+                let header = header.to_vec();
+                let widths = (0..header.len())
+                    .map(|i| {
+                        rows.iter()
+                            .chain(std::iter::once(&header))
+                            .map(|r| r.get(i).map_or(0, String::len))
+                            .max()
+                            .unwrap_or_default()
+                    })
+                    .collect_vec();
+                for row in std::iter::once(&header).chain(rows) {
+                    let line = row
+                        .iter()
+                        .zip(&widths)
+                        .enumerate()
+                        // Left-align the first column (names), right-align the numbers
+                        .map(|(i, (cell, w))| match i {
+                            0 => format!("{cell:<w$}"),
+                            _ => format!("{cell:>w$}"),
+                        })
+                        .join("  ");
+                    writeln!(out, "  {}", line.trim_end())?;
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    fn line_score(&self) -> Result<String> {
+        let innings = self.gc.events.iter().map(|e| e.context.inning).max();
+        let Some(innings) = innings else {
+            return Ok(String::new());
+        };
+        let mut runs: HashMap<(Side, u8), usize> = HashMap::new();
+        let mut hits = HashMap::new();
+        let mut errors = HashMap::new();
+        for e in &self.gc.events {
+            let side = e.context.batting_side;
+            *runs.entry((side, e.context.inning)).or_default() += e.results.runs.len();
+            if e.results
+                .plate_appearance
+                .is_some_and(PlateAppearanceResultType::is_hit)
+            {
+                *hits.entry(side).or_default() += 1;
+            }
+            *errors.entry(side.flip()).or_default() += e
+                .results
+                .fielding_plays
+                .iter()
+                .filter(|fp| fp.fielding_play_type == FieldingPlayType::Error)
+                .count();
+        }
+        let batted = |side: Side, inning: u8| {
+            self.gc
+                .events
+                .iter()
+                .any(|e| e.context.batting_side == side && e.context.inning == inning)
+        };
+        let header = std::iter::once(String::new())
+            .chain((1..=innings).map(|i| i.to_string()))
+            .chain(["R", "H", "E"].map(String::from))
+            .collect_vec();
+        let score = final_score(self.gc);
+        let rows = [Side::Away, Side::Home]
+            .into_iter()
+            .map(|side| {
+                std::iter::once(self.gc.teams.get(side).to_string())
+                    .chain((1..=innings).map(|i| {
+                        if batted(side, i) {
+                            runs.get(&(side, i))
+                                .copied()
+                                .unwrap_or_default()
+                                .to_string()
+                        } else {
+                            String::from("X")
+                        }
+                    }))
+                    .chain([
+                        score.get(side).to_string(),
+                        hits.get(&side).copied().unwrap_or(0_usize).to_string(),
+                        errors.get(&side).copied().unwrap_or(0_usize).to_string(),
+                    ])
+                    .collect_vec()
+            })
+            .collect_vec();
+        self.table(&header, &rows)
+    }
+
+    fn describe(&self, e: &Event) -> String {
+        let batter = self.name(e.context.batter_id);
+        let action = e.results.plate_appearance.map(|pa| match pa {
+            PlateAppearanceResultType::Single => "singled",
+            PlateAppearanceResultType::Double | PlateAppearanceResultType::GroundRuleDouble => {
+                "doubled"
+            }
+            PlateAppearanceResultType::Triple => "tripled",
+            PlateAppearanceResultType::HomeRun => "homered",
+            PlateAppearanceResultType::InsideTheParkHomeRun => "hit an inside-the-park home run",
+            PlateAppearanceResultType::InPlayOut => "made an out",
+            PlateAppearanceResultType::StrikeOut => "struck out",
+            PlateAppearanceResultType::FieldersChoice => "reached on a fielder's choice",
+            PlateAppearanceResultType::ReachedOnError => "reached on an error",
+            PlateAppearanceResultType::Interference => "reached on interference",
+            PlateAppearanceResultType::HitByPitch => "was hit by a pitch",
+            PlateAppearanceResultType::Walk => "walked",
+            PlateAppearanceResultType::IntentionalWalk => "was intentionally walked",
+            PlateAppearanceResultType::SacrificeFly => "hit a sacrifice fly",
+            PlateAppearanceResultType::SacrificeHit => "laid down a sacrifice bunt",
+        });
+        action.map_or_else(
+            || format!("Play with {batter} batting ({})", e.raw_play),
+            |action| format!("{batter} {action} ({})", e.raw_play),
+        )
+    }
+
+    fn half_inning(e: &Event) -> String {
+        let frame = match e.context.frame {
+            InningFrame::Top => "Top",
+            InningFrame::Bottom => "Bottom",
+        };
+        format!("{frame} {}", e.context.inning)
+    }
+
+    fn scoring_plays(&self) -> String {
+        let items = events_with_score(self.gc)
+            .filter(|(e, _)| !e.results.runs.is_empty())
+            .map(|(e, mut score)| {
+                let runs = e.results.runs.len();
+                *score.get_mut(e.context.batting_side) += u8::try_from(runs).unwrap_or_default();
+                format!(
+                    "{}: {}, {} run{} scored. {} {}, {} {}",
+                    Self::half_inning(e),
+                    self.describe(e),
+                    runs,
+                    if runs == 1 { "" } else { "s" },
+                    self.gc.teams.away,
+                    score.away,
+                    self.gc.teams.home,
+                    score.home
+                )
+            })
+            .collect_vec();
+        self.list(&items)
+    }
+
+    fn pitching_lines(&self, side: Side) -> Result<String> {
+        let pitchers = self
+            .gc
+            .fielding_appearances
+            .iter()
+            .filter(|fa| fa.side == side && fa.fielding_position == FieldingPosition::Pitcher)
+            .sorted_by_key(|fa| fa.start_event_id)
+            .map(|fa| fa.player_id)
+            .unique()
+            .collect_vec();
+        let mut lines: HashMap<Player, PitchingLine> = HashMap::new();
+        for e in self
+            .gc
+            .events
+            .iter()
+            .filter(|e| e.context.batting_side != side)
+        {
+            let line = lines.entry(e.context.pitcher_id).or_default();
+            line.outs += e.results.out_on_play.len();
+            match e.results.plate_appearance {
+                Some(pa) if pa.is_hit() => line.hits += 1,
+                Some(PlateAppearanceResultType::StrikeOut) => line.strikeouts += 1,
+                Some(
+                    PlateAppearanceResultType::Walk | PlateAppearanceResultType::IntentionalWalk,
+                ) => {
+                    let pitcher = e
+                        .context
+                        .rare_attributes
+                        .walk_responsible_pitcher
+                        .unwrap_or(e.context.pitcher_id);
+                    lines.entry(pitcher).or_default().walks += 1;
+                }
+                _ => {}
+            }
+            for runner in e.results.ending_base_state.scored() {
+                let pitcher = runner.explicit_charged_pitcher_id.or_else(|| {
+                    self.gc
+                        .events
+                        .iter()
+                        .find(|c| c.event_id == runner.charge_event_id)
+                        .map(|c| c.context.pitcher_id)
+                });
+                lines
+                    .entry(pitcher.unwrap_or(e.context.pitcher_id))
+                    .or_default()
+                    .runs += 1;
+            }
+        }
+        let earned_runs: HashMap<Player, u8> = self
+            .gc
+            .results
+            .earned_runs
+            .iter()
+            .map(|er| (er.pitcher_id, er.earned_runs))
+            .collect();
+        let results = &self.gc.results;
+        let header = ["Pitcher", "IP", "H", "R", "ER", "BB", "SO"].map(String::from);
+        let rows = pitchers
+            .into_iter()
+            .map(|p| {
+                let line = lines.get(&p).copied().unwrap_or_default();
+                let decision = [
+                    (results.winning_pitcher, " (W)"),
+                    (results.losing_pitcher, " (L)"),
+                    (results.save_pitcher, " (S)"),
+                ]
+                .into_iter()
+                .filter(|(player, _)| *player == Some(p))
+                .map(|(_, d)| d)
+                .join("");
+                vec![
+                    format!("{}{decision}", self.name(p)),
+                    InningsPitched::from_outs(u16::try_from(line.outs).unwrap_or_default())
+                        .to_string(),
+                    line.hits.to_string(),
+                    line.runs.to_string(),
+                    earned_runs
+                        .get(&p)
+                        .map_or_else(|| String::from("-"), ToString::to_string),
+                    line.walks.to_string(),
+                    line.strikeouts.to_string(),
+                ]
+            })
+            .collect_vec();
+        self.table(&header, &rows)
+    }
+
+    fn notable_events(&self) -> String {
+        let mut items = vec![];
+        for e in &self.gc.events {
+            let home_run = matches!(
+                e.results.plate_appearance,
+                Some(
+                    PlateAppearanceResultType::HomeRun
+                        | PlateAppearanceResultType::InsideTheParkHomeRun
+                )
+            );
+            if home_run {
+                items.push(format!("{}: {}", Self::half_inning(e), self.describe(e)));
+            }
+            let stolen_bases = e
+                .results
+                .plays_at_base
+                .iter()
+                .filter(|p| p.baserunning_play_type == BaserunningPlayType::StolenBase)
+                .count();
+            if stolen_bases > 0 {
+                items.push(format!(
+                    "{}: {} stolen base{} ({})",
+                    Self::half_inning(e),
+                    stolen_bases,
+                    if stolen_bases == 1 { "" } else { "s" },
+                    e.raw_play
+                ));
+            }
+            if e.results.out_on_play.len() >= 2 {
+                let kind = match e.results.out_on_play.len() {
+                    2 => "Double play",
+                    _ => "Triple play",
+                };
+                items.push(format!("{}: {kind} ({})", Self::half_inning(e), e.raw_play));
+            }
+        }
+        self.list(&items)
+    }
+}
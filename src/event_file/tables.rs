@@ -0,0 +1,202 @@
+use anyhow::{Context, Result};
+use arrow_schema::Schema;
+use fixed_map::Key;
+use strum_macros::{Display, EnumIter};
+
+use crate::event_file::aggregates::{
+    Aggregate, AttendanceSummary, CatcherControl, CountSplits, DoubleheaderPairing, EloRatings,
+    ExtraBasesTaken, Matchups, ParkUsage, PitcherUsagePatterns, SituationalSplits, SprayTendencies,
+    Streaks, SubstitutionContexts, TeamSeasons, WinExpectancyTable,
+};
+use crate::event_file::game_state::{
+    EventFlag, GameDhVacancy, GameFieldingAppearance, GameLineupAppearance,
+};
+use crate::event_file::roster::PlayerNameResolution;
+use crate::event_file::schemas::{
+    BoxScoreBattingLines, BoxScoreComments, BoxScoreFieldingLines, BoxScoreFieldingPlays,
+    BoxScoreHitByPitches, BoxScoreHomeRuns, BoxScoreLineScores, BoxScorePinchHittingLines,
+    BoxScorePinchRunningLines, BoxScorePitchingLines, BoxScoreReconciliation,
+    BoxScoreStolenBaseAttempts, BoxScoreTeamBattingLines, BoxScoreTeamFieldingLines,
+    BoxScoreTeamMiscellaneousLines, ColumnProfile, DeletedGames, EventAudit, EventBaserunners,
+    EventChargeReassignments, EventComments, EventFieldingAlignments, EventFieldingPlays,
+    EventOutfieldRunnerPlays, EventPitchSequences, Events, GameAccountLinks, GameAdjustments,
+    GameEarnedRuns, GameManifest, Games, InheritedRunners, LintFindings, PinchAppearances,
+    PitchingAppearances, ReplayReconciliation, RunManifest, RunTelemetry, UnreadableFiles,
+};
+use crate::event_file::writer::table_schema;
+
+/// Every table that `parse` writes, named the same as its file
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Ord, PartialOrd, Hash, Display, EnumIter, Key)]
+#[strum(serialize_all = "snake_case")]
+pub enum EventFileSchema {
+    Games,
+    GameLineupAppearances,
+    GameFieldingAppearances,
+    GameEarnedRuns,
+    Events,
+    EventAudit,
+    EventBaserunners,
+    EventFieldingPlay,
+    EventPitchSequences,
+    EventFlags,
+    EventComments,
+    EventOutfieldRunnerPlays,
+    EventFieldingAlignments,
+    BoxScoreGames,
+    BoxScoreLineScores,
+    BoxScoreBattingLines,
+    BoxScorePitchingLines,
+    BoxScoreFieldingLines,
+    BoxScorePinchHittingLines,
+    BoxScorePinchRunningLines,
+    BoxScoreTeamMiscellaneousLines,
+    BoxScoreTeamBattingLines,
+    BoxScoreTeamFieldingLines,
+    BoxScoreDoublePlays,
+    BoxScoreTriplePlays,
+    BoxScoreHitByPitches,
+    BoxScoreHomeRuns,
+    BoxScoreStolenBases,
+    BoxScoreCaughtStealing,
+    BoxScoreComments,
+    PlayerSituationalSplits,
+    SeasonCountSplits,
+    PlayerExtraBasesTaken,
+    CatcherBaserunningControl,
+    PitcherUsage,
+    BatterPitcherMatchups,
+    WinExpectancy,
+    PlayerStreaks,
+    GameManifest,
+    DeletedGames,
+    RunTelemetry,
+    LintFindings,
+    RunManifest,
+    UnreadableFiles,
+    EventChargeReassignments,
+    GameAdjustments,
+    GameDhVacancies,
+    PinchAppearances,
+    TeamSeasonRecords,
+    TeamElo,
+    SubstitutionContexts,
+    PitchingAppearances,
+    InheritedRunners,
+    ParkUsageFindings,
+    Doubleheaders,
+    ColumnProfile,
+    BoxScoreReconciliation,
+    ParkSeasonAttendance,
+    BatterSprayTendencies,
+    ReplayReconciliation,
+    PlayerNameResolutions,
+    GameAccountLinks,
+}
+
+impl EventFileSchema {
+    /// Whether rows are written per game, as opposed to once per run
+    pub const fn is_game_level(self) -> bool {
+        !matches!(
+            self,
+            Self::PlayerSituationalSplits
+                | Self::SeasonCountSplits
+                | Self::PlayerExtraBasesTaken
+                | Self::CatcherBaserunningControl
+                | Self::PitcherUsage
+                | Self::BatterPitcherMatchups
+                | Self::WinExpectancy
+                | Self::PlayerStreaks
+                | Self::GameManifest
+                | Self::DeletedGames
+                | Self::RunTelemetry
+                | Self::RunManifest
+                | Self::UnreadableFiles
+                | Self::TeamSeasonRecords
+                | Self::TeamElo
+                | Self::SubstitutionContexts
+                | Self::ParkUsageFindings
+                | Self::Doubleheaders
+                | Self::ColumnProfile
+                | Self::ParkSeasonAttendance
+                | Self::BatterSprayTendencies
+                | Self::PlayerNameResolutions
+                | Self::GameAccountLinks
+        )
+    }
+
+    /// The columns of the schema's rows, which Parquet and Arrow files are written with
+    /// even when the table ends up empty
+    pub fn columns(self) -> Result<Schema> {
+        fn aggregate<A: Aggregate>() -> Result<Schema> {
+            table_schema::<A::Row>()
+        }
+        match self {
+            Self::Games | Self::BoxScoreGames => table_schema::<Games>(),
+            Self::GameLineupAppearances => table_schema::<GameLineupAppearance>(),
+            Self::GameFieldingAppearances => table_schema::<GameFieldingAppearance>(),
+            Self::GameEarnedRuns => table_schema::<GameEarnedRuns>(),
+            Self::Events => table_schema::<Events>(),
+            Self::EventAudit => table_schema::<EventAudit>(),
+            Self::EventBaserunners => table_schema::<EventBaserunners>(),
+            Self::EventFieldingPlay => table_schema::<EventFieldingPlays>(),
+            Self::EventPitchSequences => table_schema::<EventPitchSequences>(),
+            Self::EventFlags => table_schema::<EventFlag>(),
+            Self::EventComments => table_schema::<EventComments>(),
+            Self::EventOutfieldRunnerPlays => table_schema::<EventOutfieldRunnerPlays>(),
+            Self::EventFieldingAlignments => table_schema::<EventFieldingAlignments>(),
+            Self::BoxScoreLineScores => table_schema::<BoxScoreLineScores>(),
+            Self::BoxScoreBattingLines => table_schema::<BoxScoreBattingLines>(),
+            Self::BoxScorePitchingLines => table_schema::<BoxScorePitchingLines>(),
+            Self::BoxScoreFieldingLines => table_schema::<BoxScoreFieldingLines>(),
+            Self::BoxScorePinchHittingLines => table_schema::<BoxScorePinchHittingLines>(),
+            Self::BoxScorePinchRunningLines => table_schema::<BoxScorePinchRunningLines>(),
+            Self::BoxScoreTeamMiscellaneousLines => {
+                table_schema::<BoxScoreTeamMiscellaneousLines>()
+            }
+            Self::BoxScoreTeamBattingLines => table_schema::<BoxScoreTeamBattingLines>(),
+            Self::BoxScoreTeamFieldingLines => table_schema::<BoxScoreTeamFieldingLines>(),
+            Self::BoxScoreDoublePlays | Self::BoxScoreTriplePlays => {
+                table_schema::<BoxScoreFieldingPlays>()
+            }
+            Self::BoxScoreHitByPitches => table_schema::<BoxScoreHitByPitches>(),
+            Self::BoxScoreHomeRuns => table_schema::<BoxScoreHomeRuns>(),
+            Self::BoxScoreStolenBases | Self::BoxScoreCaughtStealing => {
+                table_schema::<BoxScoreStolenBaseAttempts>()
+            }
+            Self::BoxScoreComments => table_schema::<BoxScoreComments>(),
+            Self::PlayerSituationalSplits => aggregate::<SituationalSplits>(),
+            Self::SeasonCountSplits => aggregate::<CountSplits>(),
+            Self::PlayerExtraBasesTaken => aggregate::<ExtraBasesTaken>(),
+            Self::CatcherBaserunningControl => aggregate::<CatcherControl>(),
+            Self::PitcherUsage => aggregate::<PitcherUsagePatterns>(),
+            Self::BatterPitcherMatchups => aggregate::<Matchups>(),
+            Self::WinExpectancy => aggregate::<WinExpectancyTable>(),
+            Self::PlayerStreaks => aggregate::<Streaks>(),
+            Self::GameManifest => table_schema::<GameManifest>(),
+            Self::DeletedGames => table_schema::<DeletedGames>(),
+            Self::RunTelemetry => table_schema::<RunTelemetry>(),
+            Self::LintFindings => table_schema::<LintFindings>(),
+            Self::RunManifest => table_schema::<RunManifest>(),
+            Self::UnreadableFiles => table_schema::<UnreadableFiles>(),
+            Self::EventChargeReassignments => table_schema::<EventChargeReassignments>(),
+            Self::GameAdjustments => table_schema::<GameAdjustments>(),
+            Self::GameDhVacancies => table_schema::<GameDhVacancy>(),
+            Self::PinchAppearances => table_schema::<PinchAppearances>(),
+            Self::TeamSeasonRecords => aggregate::<TeamSeasons>(),
+            Self::TeamElo => aggregate::<EloRatings>(),
+            Self::SubstitutionContexts => aggregate::<SubstitutionContexts>(),
+            Self::PitchingAppearances => table_schema::<PitchingAppearances>(),
+            Self::InheritedRunners => table_schema::<InheritedRunners>(),
+            Self::ParkUsageFindings => aggregate::<ParkUsage>(),
+            Self::Doubleheaders => aggregate::<DoubleheaderPairing>(),
+            Self::ColumnProfile => table_schema::<ColumnProfile>(),
+            Self::BoxScoreReconciliation => table_schema::<BoxScoreReconciliation>(),
+            Self::ParkSeasonAttendance => aggregate::<AttendanceSummary>(),
+            Self::BatterSprayTendencies => aggregate::<SprayTendencies>(),
+            Self::ReplayReconciliation => table_schema::<ReplayReconciliation>(),
+            Self::PlayerNameResolutions => table_schema::<PlayerNameResolution>(),
+            Self::GameAccountLinks => table_schema::<GameAccountLinks>(),
+        }
+        .with_context(|| format!("Failed to trace the columns of {self}"))
+    }
+}
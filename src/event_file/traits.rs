@@ -1,6 +1,9 @@
 use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use anyhow::{anyhow, Context, Error, Result};
+use anyhow::{anyhow, bail, Context, Error, Result};
 use arrayvec::ArrayString;
 use bounded_integer::BoundedUsize;
 use csv::StringRecord;
@@ -15,12 +18,38 @@ use crate::event_file::info::{InfoRecord, Team};
 use crate::event_file::misc::digit_vec;
 use crate::event_file::parser::{MappedRecord, RecordSlice};
 
-pub const MAX_EVENTS_PER_GAME: usize = 255;
+pub const DEFAULT_MAX_EVENTS_PER_GAME: usize = 255;
 pub const MAX_GAMES_PER_FILE: usize = 1000;
-pub const EVENT_KEY_BUFFER: usize = MAX_EVENTS_PER_GAME * MAX_GAMES_PER_FILE;
+/// Upper bound on event and other sequence IDs, which any max events per game
+/// has to stay below
+pub const MAX_SEQUENCE_ID: usize = 65_535;
+
+/// Each game gets this many event keys, so a game with more events would take keys
+/// from the next one. Set it before any games are parsed.
+static MAX_EVENTS_PER_GAME: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_EVENTS_PER_GAME);
+
+pub fn max_events_per_game() -> usize {
+    MAX_EVENTS_PER_GAME.load(Ordering::Relaxed)
+}
+
+pub fn set_max_events_per_game(max_events: usize) -> Result<()> {
+    if max_events == 0 || max_events >= MAX_SEQUENCE_ID {
+        bail!(
+            "Max events per game must be between 1 and {}",
+            MAX_SEQUENCE_ID - 1
+        );
+    }
+    MAX_EVENTS_PER_GAME.store(max_events, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Event keys reserved for each file
+pub fn event_key_buffer() -> usize {
+    max_events_per_game() * MAX_GAMES_PER_FILE
+}
 
 pub type RetrosheetEventRecord = StringRecord;
-pub type SequenceId = BoundedUsize<1, MAX_EVENTS_PER_GAME>;
+pub type SequenceId = BoundedUsize<1, MAX_SEQUENCE_ID>;
 // Signed for DuckDb Parquet compatibility with delta encoding
 pub type EventKey = i32;
 
@@ -139,6 +168,13 @@ impl FieldingPosition {
         (1..11).contains(&numeric_position)
     }
 
+    pub const fn is_outfield(self) -> bool {
+        matches!(
+            self,
+            Self::LeftFielder | Self::CenterFielder | Self::RightFielder
+        )
+    }
+
     pub fn retrosheet_string(self) -> String {
         let as_u8: u8 = self.into();
         as_u8.to_string()
@@ -195,6 +231,13 @@ pub enum GameType {
     Unknown,
 }
 
+impl GameType {
+    /// Games that don't count towards any standings, like spring training
+    pub const fn is_exhibition(self) -> bool {
+        matches!(self, Self::Exhibition | Self::Preseason)
+    }
+}
+
 #[derive(
     Ord, PartialOrd, Debug, Eq, PartialEq, Copy, Clone, Hash, Serialize, Deserialize, AsRefStr,
 )]
@@ -207,7 +250,69 @@ pub enum FieldingPlayType {
 
 pub type Inning = u8;
 
-pub type Person = ArrayString<8>;
+/// Retrosheet ID for a player or umpire, e.g. `ruthb101`: four characters of the last
+/// name (padded with `-`), the first initial, and a three-digit number
+#[derive(
+    Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone, Serialize, Deserialize,
+)]
+#[serde(try_from = "String")]
+pub struct Person(ArrayString<8>);
+
+impl Person {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Keeps an ID as it was written, as long as it fits. Records that identify a
+    /// player go through this rather than `from_str`, so that a mistyped ID shows up as
+    /// a lint finding instead of failing the whole game.
+    pub fn from_raw(s: &str) -> Result<Self> {
+        ArrayString::from(s)
+            .map(Self)
+            .map_err(|_| anyhow!("Person ID {s} is longer than 8 characters"))
+    }
+
+    /// Whether the ID has the standard Retrosheet form
+    pub fn is_standard(&self) -> bool {
+        Self::is_valid(self.as_str())
+    }
+
+    fn is_valid(s: &str) -> bool {
+        s.len() == 8
+            && s
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+    }
+}
+
+impl FromStr for Person {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if !Self::is_valid(s) {
+            bail!("Invalid Retrosheet person ID: {s}")
+        }
+        ArrayString::from(s)
+            .map(Self)
+            .map_err(|_| anyhow!("Invalid Retrosheet person ID: {s}"))
+    }
+}
+
+/// Lenient, so that IDs written out as they were found can be read back in
+impl TryFrom<String> for Person {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        Self::from_raw(&s)
+    }
+}
+
+impl fmt::Display for Person {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 pub type MiscInfoString = ArrayString<16>;
 
 pub type Player = Person;
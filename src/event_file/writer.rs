@@ -0,0 +1,1038 @@
+use std::io::Write;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use arrow_array::RecordBatch;
+use arrow_ipc::writer::FileWriter;
+use arrow_json::reader::Decoder;
+use arrow_json::ReaderBuilder;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::properties::WriterProperties;
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::ser::{self, Impossible, SerializeMap, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// Where the rows of one output table go. Every table gets its own writer, which is
+/// handed the rows one at a time as the structs in `schemas` and `aggregates`, so a new
+/// output format only needs to know how to write out a serializable struct.
+pub trait SchemaWriter: Send {
+    fn serialize<R: Serialize>(&mut self, row: R) -> Result<()>;
+
+    fn flush(&mut self) -> Result<()>;
+}
+
+/// Writes a header from the field names of the first row, and then a record per row.
+/// Rows have to be flat, which all of the built-in ones are.
+impl<W: Write + Send> SchemaWriter for csv::Writer<W> {
+    fn serialize<R: Serialize>(&mut self, row: R) -> Result<()> {
+        Ok(Self::serialize(self, row)?)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(Self::flush(self)?)
+    }
+}
+
+/// Writes each row as a JSON object on its own line. Empty values are written as `null`
+/// rather than as empty strings.
+pub struct JsonLinesWriter<W: Write> {
+    output: W,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    pub const fn new(output: W) -> Self {
+        Self { output }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+}
+
+impl<W: Write + Send> SchemaWriter for JsonLinesWriter<W> {
+    fn serialize<R: Serialize>(&mut self, row: R) -> Result<()> {
+        serde_json::to_writer(&mut self.output, &row)?;
+        self.output.write_all(b"\n")?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(self.output.flush()?)
+    }
+}
+
+/// A file format that's written as Arrow record batches
+pub trait BatchSink<W: Write>: Sized + Send {
+    fn create(output: W, schema: SchemaRef) -> Result<Self>;
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()>;
+
+    /// Writes the file's footer and flushes the output
+    fn finish(&mut self) -> Result<()>;
+}
+
+impl<W: Write + Send> BatchSink<W> for ArrowWriter<W> {
+    fn create(output: W, schema: SchemaRef) -> Result<Self> {
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+        Ok(Self::try_new(output, schema, Some(properties))?)
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        Ok(Self::write(self, batch)?)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        Self::finish(self)?;
+        Ok(self.inner_mut().flush()?)
+    }
+}
+
+impl<W: Write + Send> BatchSink<W> for FileWriter<W> {
+    fn create(output: W, schema: SchemaRef) -> Result<Self> {
+        Ok(Self::try_new(output, &schema)?)
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        Ok(Self::write(self, batch)?)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        Self::finish(self)?;
+        Ok(self.get_mut().flush()?)
+    }
+}
+
+const BATCH_ROWS: usize = 8192;
+
+/// Writes rows into a columnar file with a typed column per field. The schema is fixed
+/// when the writer is created, so a table without any rows still has all of its
+/// columns. Flushing writes the file's footer, after which no more rows can be written.
+pub struct RecordBatchWriter<W: Write + Send, S: BatchSink<W>> {
+    state: Option<(Box<S>, Decoder)>,
+    output: PhantomData<W>,
+}
+
+/// Parquet, compressed with zstd
+pub type ParquetWriter<W> = RecordBatchWriter<W, ArrowWriter<W>>;
+/// The Arrow IPC file format, also known as Feather
+pub type ArrowIpcWriter<W> = RecordBatchWriter<W, FileWriter<W>>;
+
+impl<W: Write + Send, S: BatchSink<W>> RecordBatchWriter<W, S> {
+    /// Starts a file with the given columns, usually those of [`table_schema`]
+    pub fn try_new(output: W, schema: Schema) -> Result<Self> {
+        let schema = Arc::new(schema);
+        let sink = Box::new(S::create(output, Arc::clone(&schema))?);
+        let decoder = ReaderBuilder::new(schema)
+            .with_coerce_primitive(true)
+            .build_decoder()?;
+        Ok(Self {
+            state: Some((sink, decoder)),
+            output: PhantomData,
+        })
+    }
+
+    fn write_batch(&mut self) -> Result<()> {
+        if let Some((sink, decoder)) = &mut self.state {
+            if let Some(batch) = decoder.flush()? {
+                sink.write(&batch)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write + Send, S: BatchSink<W>> SchemaWriter for RecordBatchWriter<W, S> {
+    fn serialize<R: Serialize>(&mut self, row: R) -> Result<()> {
+        let Some((_, decoder)) = &mut self.state else {
+            bail!("Output file has already been finished")
+        };
+        decoder.serialize(&[row])?;
+        if decoder.len() >= BATCH_ROWS {
+            self.write_batch()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.write_batch()?;
+        if let Some((mut sink, _)) = self.state.take() {
+            sink.finish()?;
+        }
+        Ok(())
+    }
+}
+
+/// The columns of a row type, in field order. They're traced from a placeholder row
+/// rather than from any real data, so they're the same whichever values a run writes:
+/// integers, floats and booleans map to their Arrow equivalents, dates and timestamps
+/// to Arrow dates and timestamps, and everything else, including enums, to strings.
+/// Optional fields that a run leaves out of its rows, like player names, are columns
+/// of nulls.
+pub fn table_schema<R: Serialize + Deserialize<'static>>() -> Result<Schema> {
+    let mut tracer = SchemaTracer::default();
+    Sampler::sample::<R>()?.serialize(&mut tracer)?;
+    Ok(Schema::new(tracer.fields))
+}
+
+/// Stand-ins for string fields, tried in order until the field's type accepts one. The
+/// date and timestamp are told apart from other strings when the row is traced.
+const SAMPLE_STRINGS: [&str; 5] = ["", "0", SAMPLE_DATE, SAMPLE_TIMESTAMP, "A"];
+const SAMPLE_DATE: &str = "1970-01-01";
+const SAMPLE_TIMESTAMP: &str = "1970-01-01T00:00:00";
+/// Bounded integers can rule out zero
+const SAMPLE_INTEGERS: [u8; 3] = [0, 1, 2];
+
+/// A deserializer that makes up a value of any row type. Every optional field is
+/// filled in, and each leaf value gets the first placeholder its type accepts: when
+/// deserializing fails, the last leaf moves on to its next placeholder and the row is
+/// started over.
+#[derive(Default)]
+struct Sampler {
+    /// The placeholder picked for each leaf, along with how many there are to pick from
+    choices: Vec<(usize, usize)>,
+    next_leaf: usize,
+}
+
+impl Sampler {
+    fn sample<R: Deserialize<'static>>() -> Result<R> {
+        let mut sampler = Self::default();
+        loop {
+            sampler.next_leaf = 0;
+            let error = match R::deserialize(&mut sampler) {
+                Ok(row) => return Ok(row),
+                Err(e) => e,
+            };
+            let last_leaf = sampler.next_leaf.checked_sub(1);
+            match last_leaf.and_then(|i| sampler.choices.get_mut(i)) {
+                Some((choice, count)) if *choice + 1 < *count => *choice += 1,
+                _ => bail!("No placeholder row fits the row type: {error}"),
+            }
+            sampler.choices.truncate(sampler.next_leaf);
+        }
+    }
+
+    /// Index of the placeholder to use for the next leaf, out of `count`
+    fn leaf(&mut self, count: usize) -> usize {
+        let i = self.next_leaf;
+        self.next_leaf += 1;
+        if self.choices.len() <= i {
+            self.choices.push((0, count));
+        }
+        self.choices[i].0
+    }
+
+    fn integer(&mut self) -> u8 {
+        SAMPLE_INTEGERS[self.leaf(SAMPLE_INTEGERS.len())]
+    }
+
+    fn string(&mut self) -> &'static str {
+        SAMPLE_STRINGS[self.leaf(SAMPLE_STRINGS.len())]
+    }
+}
+
+/// The fields of a struct, or the elements of a tuple, each of which gets a placeholder
+struct SampleFields<'a> {
+    sampler: &'a mut Sampler,
+    fields: std::slice::Iter<'static, &'static str>,
+    remaining: usize,
+}
+
+impl<'a> SampleFields<'a> {
+    fn of_struct(sampler: &'a mut Sampler, fields: &'static [&'static str]) -> Self {
+        Self {
+            sampler,
+            fields: fields.iter(),
+            remaining: fields.len(),
+        }
+    }
+
+    fn of_tuple(sampler: &'a mut Sampler, len: usize) -> Self {
+        Self {
+            sampler,
+            fields: [].iter(),
+            remaining: len,
+        }
+    }
+}
+
+impl MapAccess<'static> for SampleFields<'_> {
+    type Error = de::value::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'static>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        self.fields
+            .next()
+            .map(|field| seed.deserialize(BorrowedStrDeserializer::new(field)))
+            .transpose()
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'static>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        seed.deserialize(&mut *self.sampler)
+    }
+}
+
+impl SeqAccess<'static> for SampleFields<'_> {
+    type Error = de::value::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'static>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.sampler).map(Some)
+    }
+}
+
+/// Picks an enum variant the same way as any other leaf
+struct SampleVariant<'a> {
+    sampler: &'a mut Sampler,
+    variant: &'static str,
+}
+
+impl<'a> EnumAccess<'static> for SampleVariant<'a> {
+    type Error = de::value::Error;
+    type Variant = &'a mut Sampler;
+
+    fn variant_seed<V: DeserializeSeed<'static>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed.deserialize(BorrowedStrDeserializer::new(self.variant))?;
+        Ok((variant, self.sampler))
+    }
+}
+
+impl VariantAccess<'static> for &mut Sampler {
+    type Error = de::value::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'static>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'static>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(SampleFields::of_tuple(self, len))
+    }
+
+    fn struct_variant<V: Visitor<'static>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(SampleFields::of_struct(self, fields))
+    }
+}
+
+impl Deserializer<'static> for &mut Sampler {
+    type Error = de::value::Error;
+
+    /// Only self-describing formats can be asked for whatever value comes next
+    fn deserialize_any<V: Visitor<'static>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("Rows can't have untyped fields"))
+    }
+
+    fn deserialize_bool<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_bool(false)
+    }
+
+    fn deserialize_i8<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i8(self.integer().try_into().unwrap_or_default())
+    }
+
+    fn deserialize_i16<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i16(self.integer().into())
+    }
+
+    fn deserialize_i32<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i32(self.integer().into())
+    }
+
+    fn deserialize_i64<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i64(self.integer().into())
+    }
+
+    fn deserialize_u8<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u8(self.integer())
+    }
+
+    fn deserialize_u16<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u16(self.integer().into())
+    }
+
+    fn deserialize_u32<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u32(self.integer().into())
+    }
+
+    fn deserialize_u64<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u64(self.integer().into())
+    }
+
+    fn deserialize_f32<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_f32(0.0)
+    }
+
+    fn deserialize_f64<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_f64(0.0)
+    }
+
+    fn deserialize_char<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_char('A')
+    }
+
+    fn deserialize_str<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(self.string())
+    }
+
+    fn deserialize_string<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_bytes(&[])
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'static>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_bytes(&[])
+    }
+
+    fn deserialize_option<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'static>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'static>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(SampleFields::of_tuple(self, 0))
+    }
+
+    fn deserialize_tuple<V: Visitor<'static>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(SampleFields::of_tuple(self, len))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'static>>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(SampleFields::of_tuple(self, len))
+    }
+
+    fn deserialize_map<V: Visitor<'static>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(SampleFields::of_struct(self, &[]))
+    }
+
+    fn deserialize_struct<V: Visitor<'static>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(SampleFields::of_struct(self, fields))
+    }
+
+    fn deserialize_enum<V: Visitor<'static>>(
+        self,
+        _: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let variant = variants
+            .get(self.leaf(variants.len()))
+            .ok_or_else(|| de::Error::custom("Rows can't have enums without variants"))?;
+        visitor.visit_enum(SampleVariant {
+            sampler: self,
+            variant,
+        })
+    }
+
+    fn deserialize_identifier<V: Visitor<'static>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'static>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+/// Collects a column per field of a flat row
+#[derive(Default)]
+struct SchemaTracer {
+    fields: Vec<Field>,
+    map_key: Option<String>,
+}
+
+impl SchemaTracer {
+    fn push<T: ?Sized + Serialize>(
+        &mut self,
+        name: &str,
+        value: &T,
+    ) -> Result<(), serde_json::Error> {
+        let data_type = value.serialize(ColumnTypeTracer)?;
+        self.fields.push(Field::new(name, data_type, true));
+        Ok(())
+    }
+
+    fn not_flat<T>(kind: &str) -> Result<T, serde_json::Error> {
+        Err(ser::Error::custom(format!(
+            "Rows have to be structs of flat fields, not {kind}"
+        )))
+    }
+}
+
+impl SerializeStruct for &mut SchemaTracer {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Flattened structs are serialized as maps
+impl SerializeMap for &mut SchemaTracer {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.map_key = match serde_json::to_value(key)? {
+            Value::String(key) => Some(key),
+            _ => return SchemaTracer::not_flat("a map with non-string keys"),
+        };
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self.map_key.take().unwrap_or_default();
+        self.push(&key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl Serializer for &mut SchemaTracer {
+    type Ok = ();
+    type Error = serde_json::Error;
+    type SerializeSeq = Impossible<(), serde_json::Error>;
+    type SerializeTuple = Impossible<(), serde_json::Error>;
+    type SerializeTupleStruct = Impossible<(), serde_json::Error>;
+    type SerializeTupleVariant = Impossible<(), serde_json::Error>;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), serde_json::Error>;
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _: bool) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("a boolean")
+    }
+
+    fn serialize_i8(self, _: i8) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("a number")
+    }
+
+    fn serialize_i16(self, _: i16) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("a number")
+    }
+
+    fn serialize_i32(self, _: i32) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("a number")
+    }
+
+    fn serialize_i64(self, _: i64) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("a number")
+    }
+
+    fn serialize_u8(self, _: u8) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("a number")
+    }
+
+    fn serialize_u16(self, _: u16) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("a number")
+    }
+
+    fn serialize_u32(self, _: u32) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("a number")
+    }
+
+    fn serialize_u64(self, _: u64) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("a number")
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("a number")
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("a number")
+    }
+
+    fn serialize_char(self, _: char) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("a string")
+    }
+
+    fn serialize_str(self, _: &str) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("a string")
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("bytes")
+    }
+
+    fn serialize_none(self) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("an empty value")
+    }
+
+    fn serialize_unit(self) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("an empty value")
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("an empty value")
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("an enum")
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), Self::Error> {
+        SchemaTracer::not_flat("an enum")
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        SchemaTracer::not_flat("a list")
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        SchemaTracer::not_flat("a tuple")
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        SchemaTracer::not_flat("a tuple")
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        SchemaTracer::not_flat("an enum")
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        SchemaTracer::not_flat("an enum")
+    }
+}
+
+/// The column type of a single field's value
+struct ColumnTypeTracer;
+
+impl Serializer for ColumnTypeTracer {
+    type Ok = DataType;
+    type Error = serde_json::Error;
+    type SerializeSeq = Impossible<DataType, serde_json::Error>;
+    type SerializeTuple = Impossible<DataType, serde_json::Error>;
+    type SerializeTupleStruct = Impossible<DataType, serde_json::Error>;
+    type SerializeTupleVariant = Impossible<DataType, serde_json::Error>;
+    type SerializeMap = Impossible<DataType, serde_json::Error>;
+    type SerializeStruct = Impossible<DataType, serde_json::Error>;
+    type SerializeStructVariant = Impossible<DataType, serde_json::Error>;
+
+    fn serialize_bool(self, _: bool) -> Result<DataType, Self::Error> {
+        Ok(DataType::Boolean)
+    }
+
+    fn serialize_i8(self, _: i8) -> Result<DataType, Self::Error> {
+        Ok(DataType::Int8)
+    }
+
+    fn serialize_i16(self, _: i16) -> Result<DataType, Self::Error> {
+        Ok(DataType::Int16)
+    }
+
+    fn serialize_i32(self, _: i32) -> Result<DataType, Self::Error> {
+        Ok(DataType::Int32)
+    }
+
+    fn serialize_i64(self, _: i64) -> Result<DataType, Self::Error> {
+        Ok(DataType::Int64)
+    }
+
+    fn serialize_u8(self, _: u8) -> Result<DataType, Self::Error> {
+        Ok(DataType::UInt8)
+    }
+
+    fn serialize_u16(self, _: u16) -> Result<DataType, Self::Error> {
+        Ok(DataType::UInt16)
+    }
+
+    fn serialize_u32(self, _: u32) -> Result<DataType, Self::Error> {
+        Ok(DataType::UInt32)
+    }
+
+    fn serialize_u64(self, _: u64) -> Result<DataType, Self::Error> {
+        Ok(DataType::UInt64)
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<DataType, Self::Error> {
+        Ok(DataType::Float32)
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<DataType, Self::Error> {
+        Ok(DataType::Float64)
+    }
+
+    fn serialize_char(self, _: char) -> Result<DataType, Self::Error> {
+        Ok(DataType::Utf8)
+    }
+
+    fn serialize_str(self, value: &str) -> Result<DataType, Self::Error> {
+        Ok(match value {
+            SAMPLE_DATE => DataType::Date32,
+            SAMPLE_TIMESTAMP => DataType::Timestamp(TimeUnit::Microsecond, None),
+            _ => DataType::Utf8,
+        })
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<DataType, Self::Error> {
+        SchemaTracer::not_flat("bytes")
+    }
+
+    /// Every optional field is filled in when the row is sampled
+    fn serialize_none(self) -> Result<DataType, Self::Error> {
+        SchemaTracer::not_flat("an empty value")
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<DataType, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<DataType, Self::Error> {
+        SchemaTracer::not_flat("an empty value")
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<DataType, Self::Error> {
+        SchemaTracer::not_flat("an empty value")
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<DataType, Self::Error> {
+        Ok(DataType::Utf8)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<DataType, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<DataType, Self::Error> {
+        SchemaTracer::not_flat("an enum")
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        SchemaTracer::not_flat("a list")
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        SchemaTracer::not_flat("a tuple")
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        SchemaTracer::not_flat("a tuple")
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        SchemaTracer::not_flat("an enum")
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        SchemaTracer::not_flat("a map")
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        SchemaTracer::not_flat("a struct")
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        SchemaTracer::not_flat("an enum")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor};
+    use std::sync::Mutex;
+
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Date32Type;
+    use arrow_array::Array;
+    use arrow_ipc::reader::FileReader;
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Row {
+        game_id: &'static str,
+        date: NaiveDate,
+        start_time: Option<NaiveDateTime>,
+        inning: Option<u8>,
+        walk_off_flag: bool,
+        run_expectancy: f64,
+    }
+
+    fn rows() -> [Row; 2] {
+        let date = NaiveDate::from_ymd_opt(2020, 4, 1).unwrap();
+        [
+            Row {
+                game_id: "BOS202004010",
+                date,
+                start_time: date.and_hms_opt(13, 5, 0),
+                inning: Some(9),
+                walk_off_flag: true,
+                run_expectancy: 0.5,
+            },
+            Row {
+                game_id: "BOS202004020",
+                date: date.succ_opt().unwrap(),
+                start_time: None,
+                inning: None,
+                walk_off_flag: false,
+                run_expectancy: 1.25,
+            },
+        ]
+    }
+
+    /// Keeps the bytes reachable after the writer takes ownership of its output
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn read_ipc(&self) -> FileReader<Cursor<Vec<u8>>> {
+            let bytes = self.0.lock().unwrap().clone();
+            FileReader::try_new(Cursor::new(bytes), None).unwrap()
+        }
+    }
+
+    #[test]
+    fn schema_comes_from_the_row_type() {
+        let schema = table_schema::<Row>().unwrap();
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|f| (f.name().as_str(), f.data_type().clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            [
+                ("game_id", DataType::Utf8),
+                ("date", DataType::Date32),
+                (
+                    "start_time",
+                    DataType::Timestamp(TimeUnit::Microsecond, None)
+                ),
+                ("inning", DataType::UInt8),
+                ("walk_off_flag", DataType::Boolean),
+                ("run_expectancy", DataType::Float64),
+            ]
+        );
+        assert!(table_schema::<String>().is_err());
+    }
+
+    #[test]
+    fn json_lines_write_empty_values_as_null() {
+        let mut writer = JsonLinesWriter::new(Vec::new());
+        for row in rows() {
+            writer.serialize(row).unwrap();
+        }
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains(r#""inning":null"#));
+    }
+
+    #[test]
+    fn arrow_ipc_round_trips_rows() {
+        let buffer = SharedBuffer::default();
+        let schema = table_schema::<Row>().unwrap();
+        let mut writer = ArrowIpcWriter::try_new(buffer.clone(), schema).unwrap();
+        for row in rows() {
+            writer.serialize(row).unwrap();
+        }
+        writer.flush().unwrap();
+        assert!(writer.serialize(&rows()[0]).is_err());
+
+        let batches = buffer.read_ipc().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 2);
+        let inning = batches[0].column_by_name("inning").unwrap();
+        assert_eq!(inning.null_count(), 1);
+        let date = batches[0].column_by_name("date").unwrap();
+        assert_eq!(
+            date.as_primitive::<Date32Type>().value_as_date(0),
+            NaiveDate::from_ymd_opt(2020, 4, 1)
+        );
+        let start_time = batches[0].column_by_name("start_time").unwrap();
+        assert_eq!(start_time.null_count(), 1);
+    }
+
+    #[test]
+    fn empty_tables_keep_their_columns() {
+        let buffer = SharedBuffer::default();
+        let schema = table_schema::<Row>().unwrap();
+        let mut writer = ArrowIpcWriter::try_new(buffer.clone(), schema.clone()).unwrap();
+        writer.flush().unwrap();
+        let reader = buffer.read_ipc();
+        assert_eq!(*reader.schema(), schema);
+        assert_eq!(reader.count(), 0);
+    }
+}
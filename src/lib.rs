@@ -0,0 +1,26 @@
+//! Parses Retrosheet event, box score and roster files into structured games. The
+//! `baseball-computer` binary writes them out as CSV, JSON lines, Parquet or Arrow IPC;
+//! [`prelude`] has what's needed to do the same from other code.
+
+#![forbid(unsafe_code)]
+#![deny(clippy::all, clippy::cargo)]
+#![warn(
+    clippy::nursery,
+    clippy::pedantic,
+    clippy::unwrap_used,
+    clippy::expect_used
+)]
+#![allow(clippy::module_name_repetitions, clippy::significant_drop_tightening)]
+// Arrow and Polars bring in their own versions of some crates that are also used elsewhere
+#![allow(clippy::multiple_crate_versions)]
+// These only fire on exported items, which the modules weren't written as
+#![allow(
+    clippy::must_use_candidate,
+    clippy::return_self_not_must_use,
+    clippy::missing_errors_doc,
+    clippy::missing_panics_doc,
+    clippy::too_long_first_doc_paragraph
+)]
+
+pub mod event_file;
+pub mod prelude;
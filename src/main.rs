@@ -8,174 +8,775 @@
     clippy::expect_used
 )]
 #![allow(clippy::module_name_repetitions, clippy::significant_drop_tightening)]
+#![allow(clippy::multiple_crate_versions)]
 
-use event_file::schemas::{BoxScoreComments, EventBaserunners, EventComments, EventPitchSequences};
-use glob::GlobError;
+use baseball_computer::event_file::schemas::{
+    BoxScoreComments, EventBaserunners, EventChargeReassignments, EventComments,
+    EventFieldingAlignments, EventOutfieldRunnerPlays, EventPitchSequences,
+};
 use itertools::Itertools;
 use serde::Serialize;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::File;
-use std::hash::Hash;
-use std::io::{BufWriter, Write};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Mutex, MutexGuard};
-use std::time::Instant;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, bail, Context, Result};
-use clap::Parser;
-use csv::{Writer, WriterBuilder};
-use either::Either;
-use fixed_map::{Key, Map};
+use arrayvec::ArrayString;
+use arrow_array::cast::AsArray;
+use arrow_ipc::reader::FileReader;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use const_format::formatcp;
+use csv::{Terminator, Writer, WriterBuilder};
+use fixed_map::Map;
 use lazy_static::lazy_static;
+use parquet::file::reader::{FileReader as _, SerializedFileReader};
 use rayon::prelude::*;
 use strum::IntoEnumIterator;
-use strum_macros::{Display, EnumIter};
-use tracing::{debug, error, info, warn, Level};
-use tracing_subscriber::FmtSubscriber;
-
-use event_file::game_state::GameContext;
-use event_file::parser::RetrosheetReader;
-
-use crate::event_file::box_score::{BoxScoreEvent, BoxScoreLine};
-use crate::event_file::misc::GameId;
-use crate::event_file::parser::{AccountType, MappedRecord, RecordSlice};
-use crate::event_file::play::print_cache_info;
-use crate::event_file::schemas::{
-    BoxScoreLineScores, BoxScoreWritableRecord, ContextToVec, EventAudit, EventFieldingPlays,
-    Events, GameEarnedRuns, Games,
-};
-use crate::event_file::traits::{GameType, EVENT_KEY_BUFFER};
+use tracing::{debug, error, info, warn};
+use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
-mod event_file;
+use baseball_computer::event_file::game_state::{GameContext, RecordLine};
+use baseball_computer::event_file::parser::RetrosheetReader;
+
+use baseball_computer::event_file::aggregates::{
+    Aggregate, AttendanceSummary, CatcherControl, CountSplits, DoubleheaderPairing, EloRatings,
+    ExtraBasesTaken, Matchups, ParkUsage, PitcherUsagePatterns, SituationalSplits, SprayTendencies,
+    Streaks, SubstitutionContexts, TeamSeasons, WinExpectancyTable,
+};
+use baseball_computer::event_file::box_score::{BoxScoreEvent, BoxScoreLine};
+use baseball_computer::event_file::derived::{on_game, registered_derived_schemas, DerivedSchema};
+use baseball_computer::event_file::known_issues::KnownIssue;
+use baseball_computer::event_file::misc::GameId;
+use baseball_computer::event_file::parks::Roof;
+use baseball_computer::event_file::parser::{
+    open_input, AccountType, MappedRecord, RecordSlice, StableHasher,
+};
+use baseball_computer::event_file::play::print_cache_info;
+use baseball_computer::event_file::roster::{Rosters, TeamLeagues};
+use baseball_computer::event_file::schemas::{
+    BoxScoreBattingLines, BoxScoreFieldingLines, BoxScoreFieldingPlays, BoxScoreHitByPitches,
+    BoxScoreHomeRuns, BoxScoreLineScores, BoxScorePinchHittingLines, BoxScorePinchRunningLines,
+    BoxScorePitchingLines, BoxScoreReconciliation, BoxScoreStolenBaseAttempts,
+    BoxScoreTeamBattingLines, BoxScoreTeamFieldingLines, BoxScoreTeamMiscellaneousLines,
+    ColumnProfile, ContextToVec, DeletedGames, EventAudit, EventFieldingPlays, Events,
+    GameAccountLinks, GameAdjustments, GameEarnedRuns, GameIdString, GameManifest, Games,
+    InheritedRunners, LintFindings, PinchAppearances, PitchingAppearances, ReplayReconciliation,
+    RunManifest, RunTelemetry, UnreadableFiles,
+};
+use baseball_computer::event_file::scorecard::Scorecard;
+use baseball_computer::event_file::summary::{GameSummary, SummaryFormat};
+use baseball_computer::event_file::tables::EventFileSchema;
+use baseball_computer::event_file::traits::{
+    event_key_buffer, set_max_events_per_game, FieldingPosition, GameType,
+    DEFAULT_MAX_EVENTS_PER_GAME,
+};
+use baseball_computer::event_file::writer::{
+    ArrowIpcWriter, JsonLinesWriter, ParquetWriter, SchemaWriter,
+};
 
 const ABOUT: &str = "Creates structured datasets from raw Retrosheet files.";
 
+/// Bumped whenever an existing column or file is renamed, removed, reordered, or
+/// changes type. Downstream consumers pinned to a major version can keep reading
+/// any output with the same one.
+const SCHEMA_MAJOR_VERSION: u16 = 2;
+/// Bumped when columns or files are added without touching existing ones
+const SCHEMA_MINOR_VERSION: u16 = 0;
+/// Version of the output format as a whole, recorded in `run_manifest.csv`
+pub const SCHEMA_VERSION: &str = formatcp!("{SCHEMA_MAJOR_VERSION}.{SCHEMA_MINOR_VERSION}");
+
+/// Set on Ctrl-C. Files that haven't started yet are skipped, and everything
+/// parsed so far is flushed as usual.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+const FILE_OPEN_ATTEMPTS: u32 = 3;
+const FILE_RETRY_BACKOFF: Duration = Duration::from_millis(200);
+
 lazy_static! {
-    static ref OUTPUT_ROOT: PathBuf = get_output_root(&Opt::parse());
-    static ref WRITER_MAP: WriterMap = WriterMap::new(&OUTPUT_ROOT);
-    static ref JSON_WRITER: ThreadSafeJsonWriter = ThreadSafeJsonWriter::new();
+    static ref OUTPUTS: Result<Outputs> = Outputs::new(opt());
+    static ref OUTPUT_ROOT: &'static Path = &outputs().root;
+    static ref WRITER_MAP: &'static WriterMap = &outputs().writers;
+    static ref FILE_SUFFIX: String = if opt().versioned_file_names {
+        format!("_v{SCHEMA_MAJOR_VERSION}")
+    } else {
+        String::new()
+    };
+    static ref WRITE_FIELDING_ALIGNMENTS: bool = opt().fielding_alignments;
+    static ref AGGREGATE_ONLY: bool = opt().aggregate_only;
+    static ref WRITE_ELO: bool = opt().elo;
+    static ref ENRICH_PITCHES: bool = opt().pitch_enrichment;
+    static ref WRITE_PLAYER_NAMES: bool = opt().player_names;
+    static ref WRITE_RAW_STRINGS: bool = opt().raw_strings;
+    static ref WRITE_FIELDING_POSITIONS: bool = opt().fielding_position_columns;
+    static ref CSV_CRLF: bool = opt().crlf;
+    static ref CSV_BOM: bool = opt().bom;
+    static ref WRITE_COLUMN_PROFILE: bool = opt().column_profile;
+    static ref REPLAY_CHECK: bool = opt().replay_check;
+    static ref SKIP_BAD_RECORDS: bool = opt().skip_bad_records;
+    static ref SKIP_EXHIBITIONS: bool = opt().skip_exhibitions;
+    static ref UNKNOWN_FIELDER_POLICY: UnknownFielderPolicy = opt().unknown_fielders;
+    static ref UNKNOWN_UMPIRE_POLICY: UnknownUmpirePolicy = opt().unknown_umpires;
+    static ref JSON_WRITER: Option<&'static ThreadSafeJsonWriter> = outputs().json.as_ref();
+    static ref AGGREGATES: Aggregates = Aggregates {
+        spray_tendencies: Mutex::new(SprayTendencies::with_rosters(&ROSTERS)),
+        ..Aggregates::default()
+    };
+    static ref DERIVED_WRITERS: &'static DerivedWriterMap = &outputs().derived;
+    static ref ROSTERS: Rosters = Rosters::read(&opt().input).unwrap_or_else(|e| {
+        error!("Failed to read rosters, skipping roster validation: {e:?}");
+        Rosters::default()
+    });
+    static ref TEAM_LEAGUES: TeamLeagues = TeamLeagues::read(&opt().input).unwrap_or_else(|e| {
+        error!("Failed to read team files, leaving leagues empty: {e:?}");
+        TeamLeagues::default()
+    });
 }
 
+/// Every output file that's known before parsing starts. These are all created together
+/// by `init_outputs`, so that an unwritable output directory fails the run up front.
+struct Outputs {
+    root: PathBuf,
+    writers: WriterMap,
+    /// Only created for `--json` runs, as its file could clash with the JSONL games table
+    json: Option<ThreadSafeJsonWriter>,
+    derived: DerivedWriterMap,
+}
+
+impl Outputs {
+    fn new(opt: &Opt) -> Result<Self> {
+        let output_dir = opt.run_output_dir();
+        std::fs::create_dir_all(&output_dir).with_context(|| {
+            format!("Failed to create output directory {}", output_dir.display())
+        })?;
+        let root = output_dir.canonicalize()?;
+        let marker = root.join(INCOMPLETE_MARKER);
+        std::fs::write(
+            &marker,
+            "The run that wrote this directory hasn't finished\n",
+        )
+        .with_context(|| format!("Failed to create {}", marker.display()))?;
+        Ok(Self {
+            writers: WriterMap::new(&root, opt.layout, opt.output_format)?,
+            json: opt
+                .json
+                .then(|| ThreadSafeJsonWriter::new(&root, opt.json_compression))
+                .transpose()?,
+            derived: DerivedWriterMap::new(&root)?,
+            root,
+        })
+    }
+}
+
+/// Left in the output directory from the moment it's created until every file has
+/// been written, so that the outputs of a run that failed or was interrupted aren't
+/// mistaken for a complete set
+const INCOMPLETE_MARKER: &str = "_INCOMPLETE";
+
+/// Creates the output files, returning the error if any of them can't be created
+fn init_outputs() -> Result<()> {
+    OUTPUTS
+        .as_ref()
+        .map(|_| ())
+        .map_err(|e| anyhow!("Failed to initialize outputs: {e:?}"))
+}
+
+/// The outputs created by `init_outputs`, which has to have succeeded before any
+/// of them are written to
+#[allow(clippy::expect_used)]
+fn outputs() -> &'static Outputs {
+    OUTPUTS
+        .as_ref()
+        .expect("Outputs were used without being initialized")
+}
+
+/// Cross-game aggregates, fed from the parallel workers and written once all files are parsed.
+#[derive(Default)]
+struct Aggregates {
+    situational_splits: Mutex<SituationalSplits>,
+    count_splits: Mutex<CountSplits>,
+    extra_bases_taken: Mutex<ExtraBasesTaken>,
+    catcher_control: Mutex<CatcherControl>,
+    pitcher_usage: Mutex<PitcherUsagePatterns>,
+    matchups: Mutex<Matchups>,
+    win_expectancy: Mutex<WinExpectancyTable>,
+    streaks: Mutex<Streaks>,
+    team_seasons: Mutex<TeamSeasons>,
+    elo_ratings: Mutex<EloRatings>,
+    substitution_contexts: Mutex<SubstitutionContexts>,
+    park_usage: Mutex<ParkUsage>,
+    doubleheaders: Mutex<DoubleheaderPairing>,
+    attendance: Mutex<AttendanceSummary>,
+    spray_tendencies: Mutex<SprayTendencies>,
+}
+
+impl Aggregates {
+    fn lock<A: Aggregate>(aggregate: &Mutex<A>) -> Result<MutexGuard<'_, A>> {
+        aggregate
+            .lock()
+            .map_err(|e| anyhow!("Failed to acquire aggregate lock: {e}"))
+    }
+
+    fn add_game(&self, game_context: &GameContext) -> Result<()> {
+        Self::lock(&self.situational_splits)?.add_game(game_context);
+        Self::lock(&self.count_splits)?.add_game(game_context);
+        Self::lock(&self.extra_bases_taken)?.add_game(game_context);
+        Self::lock(&self.catcher_control)?.add_game(game_context);
+        Self::lock(&self.pitcher_usage)?.add_game(game_context);
+        Self::lock(&self.matchups)?.add_game(game_context);
+        Self::lock(&self.win_expectancy)?.add_game(game_context);
+        Self::lock(&self.streaks)?.add_game(game_context);
+        Self::lock(&self.team_seasons)?.add_game(game_context);
+        Self::lock(&self.substitution_contexts)?.add_game(game_context);
+        Self::lock(&self.park_usage)?.add_game(game_context);
+        Self::lock(&self.doubleheaders)?.add_game(game_context);
+        Self::lock(&self.attendance)?.add_game(game_context);
+        Self::lock(&self.spray_tendencies)?.add_game(game_context);
+        if *WRITE_ELO {
+            Self::lock(&self.elo_ratings)?.add_game(game_context);
+        }
+        Ok(())
+    }
+
+    fn write_all(&self) -> Result<()> {
+        WRITER_MAP.write_rows(
+            EventFileSchema::PlayerSituationalSplits,
+            Self::lock(&self.situational_splits)?.rows(),
+        )?;
+        WRITER_MAP.write_rows(
+            EventFileSchema::SeasonCountSplits,
+            Self::lock(&self.count_splits)?.rows(),
+        )?;
+        WRITER_MAP.write_rows(
+            EventFileSchema::PlayerExtraBasesTaken,
+            Self::lock(&self.extra_bases_taken)?.rows(),
+        )?;
+        WRITER_MAP.write_rows(
+            EventFileSchema::CatcherBaserunningControl,
+            Self::lock(&self.catcher_control)?.rows(),
+        )?;
+        WRITER_MAP.write_rows(
+            EventFileSchema::PitcherUsage,
+            Self::lock(&self.pitcher_usage)?.rows(),
+        )?;
+        WRITER_MAP.write_rows(
+            EventFileSchema::BatterPitcherMatchups,
+            Self::lock(&self.matchups)?.rows(),
+        )?;
+        WRITER_MAP.write_rows(
+            EventFileSchema::WinExpectancy,
+            Self::lock(&self.win_expectancy)?.rows(),
+        )?;
+        WRITER_MAP.write_rows(
+            EventFileSchema::PlayerStreaks,
+            Self::lock(&self.streaks)?.rows(),
+        )?;
+        WRITER_MAP.write_rows(
+            EventFileSchema::TeamSeasonRecords,
+            Self::lock(&self.team_seasons)?.rows(),
+        )?;
+        WRITER_MAP.write_rows(
+            EventFileSchema::SubstitutionContexts,
+            Self::lock(&self.substitution_contexts)?.rows(),
+        )?;
+        WRITER_MAP.write_rows(
+            EventFileSchema::ParkUsageFindings,
+            Self::lock(&self.park_usage)?.rows(),
+        )?;
+        WRITER_MAP.write_rows(
+            EventFileSchema::Doubleheaders,
+            Self::lock(&self.doubleheaders)?.rows(),
+        )?;
+        WRITER_MAP.write_rows(
+            EventFileSchema::ParkSeasonAttendance,
+            Self::lock(&self.attendance)?.rows(),
+        )?;
+        WRITER_MAP.write_rows(
+            EventFileSchema::BatterSprayTendencies,
+            Self::lock(&self.spray_tendencies)?.rows(),
+        )?;
+        if *WRITE_ELO {
+            WRITER_MAP.write_rows(
+                EventFileSchema::TeamElo,
+                Self::lock(&self.elo_ratings)?.rows(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+const JSON_CHUNK_SIZE: usize = 1 << 20;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default, ValueEnum)]
+enum JsonCompression {
+    #[default]
+    None,
+    Zstd,
+}
+
+enum JsonOutput {
+    Plain(BufWriter<File>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl Write for JsonOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Plain(w) => w.write(buf),
+            Self::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Plain(w) => w.flush(),
+            Self::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+/// Games are serialized into a buffer per worker thread, and the shared output is only
+/// locked to write out a buffer once it fills up. This keeps both the lock hold time and
+/// memory use bounded regardless of how large a single file is.
 struct ThreadSafeJsonWriter {
-    json: Mutex<BufWriter<File>>,
+    buffers: Vec<Mutex<Vec<u8>>>,
+    output: Mutex<Option<JsonOutput>>,
 }
 
 impl ThreadSafeJsonWriter {
-    #[allow(clippy::expect_used)]
-    pub fn new() -> Self {
-        let output_path = OUTPUT_ROOT.join("games.jsonl");
+    pub fn new(output_root: &Path, compression: JsonCompression) -> Result<Self> {
+        let extension = match compression {
+            JsonCompression::None => "jsonl",
+            JsonCompression::Zstd => "jsonl.zst",
+        };
+        let output_path = output_root.join(format!("games{}.{extension}", *FILE_SUFFIX));
         debug!("Creating file {}", output_path.display());
-        let file = BufWriter::new(File::create(output_path).expect("Failed to create file"));
-        Self {
-            json: Mutex::new(file),
-        }
+        let file = BufWriter::new(
+            File::create(&output_path)
+                .with_context(|| format!("Failed to create {}", output_path.display()))?,
+        );
+        let output = match compression {
+            JsonCompression::None => JsonOutput::Plain(file),
+            JsonCompression::Zstd => JsonOutput::Zstd(zstd::Encoder::new(file, 0)?),
+        };
+        // One buffer per worker in the global pool, plus one for callers outside of it
+        let buffers = (0..=rayon::current_num_threads())
+            .map(|_| Mutex::default())
+            .collect();
+        Ok(Self {
+            buffers,
+            output: Mutex::new(Some(output)),
+        })
     }
 
-    pub fn json(&self) -> Result<MutexGuard<BufWriter<File>>> {
-        self.json
+    fn buffer(&self) -> Result<MutexGuard<'_, Vec<u8>>> {
+        let index = rayon::current_thread_index().unwrap_or(self.buffers.len() - 1);
+        self.buffers
+            .get(index)
+            .context("No JSON buffer for thread")?
             .lock()
-            .map_err(|e| anyhow!("Failed to acquire writer lock: {}", e))
+            .map_err(|e| anyhow!("Failed to acquire buffer lock: {e}"))
+    }
+
+    fn write_chunk(&self, buffer: &mut Vec<u8>) -> Result<()> {
+        self.output
+            .lock()
+            .map_err(|e| anyhow!("Failed to acquire writer lock: {e}"))?
+            .as_mut()
+            .context("JSON output has already been finished")?
+            .write_all(buffer)?;
+        buffer.clear();
+        Ok(())
+    }
+
+    pub fn write_game(&self, game_context: &GameContext) -> Result<()> {
+        let mut buffer = self.buffer()?;
+        serde_json::to_writer(&mut *buffer, game_context)?;
+        buffer.push(b'\n');
+        if buffer.len() >= JSON_CHUNK_SIZE {
+            self.write_chunk(&mut buffer)?;
+        }
+        Ok(())
     }
 
-    pub fn flush(&self) -> Result<()> {
-        let mut json = self.json()?;
-        json.flush()?;
+    /// Writes out whatever is left in the buffers and ends the compressed stream, if any.
+    /// Nothing more can be written afterwards.
+    pub fn finish(&self) -> Result<()> {
+        for buffer in &self.buffers {
+            let mut buffer = buffer
+                .lock()
+                .map_err(|e| anyhow!("Failed to acquire buffer lock: {e}"))?;
+            self.write_chunk(&mut buffer)?;
+        }
+        let output = self
+            .output
+            .lock()
+            .map_err(|e| anyhow!("Failed to acquire writer lock: {e}"))?
+            .take();
+        match output {
+            Some(JsonOutput::Plain(mut w)) => w.flush()?,
+            Some(JsonOutput::Zstd(w)) => w.finish()?.flush()?,
+            None => {}
+        }
         Ok(())
     }
 }
 
-struct ThreadSafeCsvWriter {
-    csv: Mutex<Writer<File>>,
-    has_header_written: AtomicBool,
+/// Creates a CSV output file, using CRLF line endings and starting it with a UTF-8 byte
+/// order mark if requested, since Excel and some other Windows tools expect them
+fn create_csv_file(path: &Path, has_headers: bool) -> Result<Writer<File>> {
+    let mut file = File::create(path)?;
+    if *CSV_BOM {
+        file.write_all(b"\xEF\xBB\xBF")?;
+    }
+    let terminator = if *CSV_CRLF {
+        Terminator::CRLF
+    } else {
+        Terminator::Any(b'\n')
+    };
+    Ok(WriterBuilder::new()
+        .has_headers(has_headers)
+        .terminator(terminator)
+        .from_writer(file))
 }
-impl ThreadSafeCsvWriter {
-    #[allow(clippy::expect_used)]
-    pub fn new(schema: EventFileSchema) -> Self {
-        let file_name = format!("{schema}.csv");
-        let output_path = OUTPUT_ROOT.join(file_name);
-        debug!("Creating file {}", output_path.display());
-        let csv = WriterBuilder::new()
-            .has_headers(!schema.uses_custom_header())
-            .from_path(output_path)
-            .expect("Failed to create file");
-        Self {
-            csv: Mutex::new(csv),
-            has_header_written: AtomicBool::new(!schema.uses_custom_header()),
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default, ValueEnum)]
+enum OutputFormat {
+    #[default]
+    Csv,
+    /// One JSON object per row, in a `.jsonl` file per schema
+    Jsonl,
+    /// A `.parquet` file per schema, with a typed column per field of the schema's rows
+    Parquet,
+    /// An Arrow IPC (Feather) `.arrow` file per schema, typed the same way as Parquet
+    Arrow,
+}
+
+impl OutputFormat {
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+            Self::Parquet => "parquet",
+            Self::Arrow => "arrow",
         }
     }
+}
 
-    pub fn csv(&self) -> Result<MutexGuard<Writer<File>>> {
-        self.csv
+/// A writer for whichever output format the run uses. Adding a format means adding a
+/// `SchemaWriter` for it and a variant here.
+enum FormatWriter {
+    Csv(Writer<File>),
+    Jsonl(JsonLinesWriter<BufWriter<File>>),
+    Parquet(ParquetWriter<BufWriter<File>>),
+    Arrow(ArrowIpcWriter<BufWriter<File>>),
+}
+
+impl FormatWriter {
+    fn create(path: &Path, format: OutputFormat, schema: EventFileSchema) -> Result<Self> {
+        Ok(match format {
+            OutputFormat::Csv => Self::Csv(create_csv_file(path, true)?),
+            OutputFormat::Jsonl => {
+                Self::Jsonl(JsonLinesWriter::new(BufWriter::new(File::create(path)?)))
+            }
+            OutputFormat::Parquet => {
+                let output = BufWriter::new(File::create(path)?);
+                Self::Parquet(ParquetWriter::try_new(output, schema.columns()?)?)
+            }
+            OutputFormat::Arrow => {
+                let output = BufWriter::new(File::create(path)?);
+                Self::Arrow(ArrowIpcWriter::try_new(output, schema.columns()?)?)
+            }
+        })
+    }
+}
+
+impl SchemaWriter for FormatWriter {
+    fn serialize<R: Serialize>(&mut self, row: R) -> Result<()> {
+        match self {
+            Self::Csv(w) => SchemaWriter::serialize(w, row),
+            Self::Jsonl(w) => w.serialize(row),
+            Self::Parquet(w) => w.serialize(row),
+            Self::Arrow(w) => w.serialize(row),
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        match self {
+            Self::Csv(w) => SchemaWriter::flush(w),
+            Self::Jsonl(w) => w.flush(),
+            Self::Parquet(w) => w.flush(),
+            Self::Arrow(w) => w.flush(),
+        }
+    }
+}
+
+struct ThreadSafeWriter {
+    writer: Mutex<FormatWriter>,
+}
+impl ThreadSafeWriter {
+    pub fn new(schema: EventFileSchema, output_dir: &Path, format: OutputFormat) -> Result<Self> {
+        std::fs::create_dir_all(output_dir).with_context(|| {
+            format!("Failed to create output directory {}", output_dir.display())
+        })?;
+        let output_path =
+            output_dir.join(format!("{schema}{}.{}", *FILE_SUFFIX, format.extension()));
+        debug!("Creating file {}", output_path.display());
+        let writer = FormatWriter::create(&output_path, format, schema)
+            .with_context(|| format!("Failed to create {}", output_path.display()))?;
+        Ok(Self {
+            writer: Mutex::new(writer),
+        })
+    }
+
+    pub fn writer(&self) -> Result<MutexGuard<'_, FormatWriter>> {
+        self.writer
             .lock()
             .map_err(|e| anyhow!("Failed to acquire writer lock: {}", e))
     }
 }
 
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default, ValueEnum)]
+enum Layout {
+    /// One file per schema in the output directory
+    #[default]
+    Flat,
+    /// Game-level schemas are split into `schema/season=YYYY/game_type=.../` directories
+    Hive,
+}
+
+impl Layout {
+    /// Directory of a schema that isn't partitioned
+    fn schema_dir(self, output_prefix: &Path, schema: EventFileSchema) -> PathBuf {
+        match self {
+            Self::Flat => output_prefix.to_path_buf(),
+            Self::Hive => output_prefix.join(schema.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+struct Partition {
+    season: u16,
+    game_type: GameType,
+}
+
+impl Partition {
+    fn path(self, schema: EventFileSchema) -> PathBuf {
+        PathBuf::from(schema.to_string())
+            .join(format!("season={}", self.season))
+            .join(format!("game_type={}", self.game_type.as_ref()))
+    }
+}
+
+impl From<&GameContext> for Partition {
+    fn from(gc: &GameContext) -> Self {
+        Self {
+            season: gc.setting.season.0,
+            game_type: gc.setting.game_type,
+        }
+    }
+}
+
+/// Partitioned writers are created as new partitions show up. The map owns them, and a
+/// game holds its own handle only while it writes, since the map's lock can't be held that long.
+type PartitionedWriters = Mutex<HashMap<(EventFileSchema, Partition), Arc<ThreadSafeWriter>>>;
+
 struct WriterMap {
     output_prefix: PathBuf,
-    map: Map<EventFileSchema, ThreadSafeCsvWriter>,
+    format: OutputFormat,
+    map: Map<EventFileSchema, Arc<ThreadSafeWriter>>,
+    partitions: Option<PartitionedWriters>,
 }
 
 impl WriterMap {
-    #[allow(clippy::expect_used)]
-    fn new(output_prefix: &Path) -> Self {
+    fn new(output_prefix: &Path, layout: Layout, format: OutputFormat) -> Result<Self> {
         let mut map = Map::new();
         for schema in EventFileSchema::iter() {
-            map.insert(schema, ThreadSafeCsvWriter::new(schema));
+            if layout == Layout::Hive && schema.is_game_level() {
+                // Partitions are only known once games are parsed, so check now that
+                // their parent directory is writable rather than failing mid-run
+                let schema_dir = layout.schema_dir(output_prefix, schema);
+                std::fs::create_dir_all(&schema_dir).with_context(|| {
+                    format!(
+                        "Failed to create partition directory {}",
+                        schema_dir.display()
+                    )
+                })?;
+                continue;
+            }
+            let output_dir = layout.schema_dir(output_prefix, schema);
+            let writer = ThreadSafeWriter::new(schema, &output_dir, format)?;
+            map.insert(schema, Arc::new(writer));
         }
-        Self {
+        Ok(Self {
             output_prefix: output_prefix.to_path_buf(),
+            format,
             map,
-        }
+            partitions: (layout == Layout::Hive).then(PartitionedWriters::default),
+        })
     }
 
-    fn flush_all(&self) -> Result<Vec<()>> {
-        self.map
+    /// Flushes one file at a time in schema order, so that games are on disk before the
+    /// rows that belong to them. A run that stops partway through can still leave a
+    /// games file without its events, which is what the incomplete marker is for.
+    fn flush_all(&self) -> Result<()> {
+        let partitions = match &self.partitions {
+            Some(p) => p
+                .lock()
+                .map_err(|e| anyhow!("Failed to acquire partition lock: {e}"))?
+                .iter()
+                .map(|((schema, _), writer)| (*schema, Arc::clone(writer)))
+                .collect(),
+            None => vec![],
+        };
+        let writers = self
+            .map
             .iter()
-            .par_bridge()
-            .map(|(_, writer)| {
-                writer
-                    .csv()?
-                    .flush()
-                    .map_err(|e| anyhow!("Failed to flush writer: {}", e))
-            })
-            .collect::<Result<Vec<()>>>()
+            .chain(partitions.iter().map(|(schema, writer)| (*schema, writer)))
+            .sorted_by_key(|(schema, _)| *schema);
+        for (_, writer) in writers {
+            writer
+                .writer()?
+                .flush()
+                .map_err(|e| anyhow!("Failed to flush writer: {}", e))?;
+        }
+        Ok(())
     }
 
-    fn get_csv(&self, schema: EventFileSchema) -> Result<MutexGuard<Writer<File>>> {
+    fn get_writer(&self, schema: EventFileSchema) -> Result<MutexGuard<'_, FormatWriter>> {
         self.map
             .get(schema)
             .context("Failed to initialize writer for schema")?
-            .csv()
+            .writer()
     }
 
-    fn write_csv<'a, C: ContextToVec<'a>>(
+    /// Writer for a schema with one or more rows per game, which may be partitioned
+    fn game_writer(
+        &self,
+        schema: EventFileSchema,
+        game_context: &GameContext,
+    ) -> Result<Arc<ThreadSafeWriter>> {
+        let Some(partitions) = self.partitions.as_ref().filter(|_| schema.is_game_level()) else {
+            return self
+                .map
+                .get(schema)
+                .cloned()
+                .context("Failed to initialize writer for schema");
+        };
+        let partition = Partition::from(game_context);
+        let mut partitions = partitions
+            .lock()
+            .map_err(|e| anyhow!("Failed to acquire partition lock: {e}"))?;
+        if let Some(writer) = partitions.get(&(schema, partition)) {
+            return Ok(Arc::clone(writer));
+        }
+        let output_dir = self.output_prefix.join(partition.path(schema));
+        let writer = Arc::new(ThreadSafeWriter::new(schema, &output_dir, self.format)?);
+        partitions.insert((schema, partition), Arc::clone(&writer));
+        Ok(writer)
+    }
+
+    /// Writes rows for a schema with one or more rows per game, which may be partitioned.
+    /// The lock is held for all of the rows so that a game's rows stay together.
+    fn write_game_rows<R: Serialize>(
+        &self,
+        schema: EventFileSchema,
+        game_context: &GameContext,
+        rows: impl IntoIterator<Item = R>,
+    ) -> Result<()> {
+        let writer = self.game_writer(schema, game_context)?;
+        let mut w = writer.writer()?;
+        for row in rows {
+            w.serialize(row)?;
+        }
+        Ok(())
+    }
+
+    fn write_game_row<R: Serialize>(
+        &self,
+        schema: EventFileSchema,
+        game_context: &GameContext,
+        row: R,
+    ) -> Result<()> {
+        self.write_game_rows(schema, game_context, [row])
+    }
+
+    fn write_context<'a, C: ContextToVec<'a>>(
         &self,
         schema: EventFileSchema,
         game_context: &'a GameContext,
     ) -> Result<()> {
-        let writer = self
-            .map
-            .get(schema)
-            .context("Failed to initialize writer for schema")?;
-        let mut csv = writer.csv()?;
-        for row in C::from_game_context(game_context) {
-            csv.serialize(row)?;
+        self.write_game_rows(schema, game_context, C::from_game_context(game_context))
+    }
+
+    fn write_rows<R: Serialize>(
+        &self,
+        schema: EventFileSchema,
+        rows: impl IntoIterator<Item = R>,
+    ) -> Result<()> {
+        let mut w = self.get_writer(schema)?;
+        for row in rows {
+            w.serialize(row)?;
         }
         Ok(())
     }
+}
+
+type DerivedWriter = (Arc<dyn DerivedSchema>, Mutex<Writer<File>>);
 
-    fn write_box_score_line(&self, line: &BoxScoreWritableRecord) -> Result<()> {
-        let schema = EventFileSchema::box_score_schema(line)?;
-        let writer = self.map.get(schema).context("Failed to get writer")?;
-        let mut csv = writer.csv()?;
-        if !writer.has_header_written.load(Ordering::Relaxed) {
-            let header = line.generate_header()?;
-            csv.serialize(header)?;
-            writer.has_header_written.store(true, Ordering::Relaxed);
+/// Writers for any schemas registered through `register_derived_schema`
+struct DerivedWriterMap {
+    writers: Vec<DerivedWriter>,
+}
+
+impl DerivedWriterMap {
+    fn new(output_prefix: &Path) -> Result<Self> {
+        let writers = registered_derived_schemas()?
+            .into_iter()
+            .map(|schema| {
+                if EventFileSchema::iter().any(|s| s.to_string() == schema.name()) {
+                    bail!(
+                        "Derived schema {} conflicts with a built-in schema",
+                        schema.name()
+                    );
+                }
+                let output_path =
+                    output_prefix.join(format!("{}{}.csv", schema.name(), *FILE_SUFFIX));
+                debug!("Creating file {}", output_path.display());
+                let mut csv = create_csv_file(&output_path, false)
+                    .with_context(|| format!("Failed to create {}", output_path.display()))?;
+                csv.write_record(schema.header())?;
+                Ok((schema, Mutex::new(csv)))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { writers })
+    }
+
+    fn write_all(&self, game_context: &GameContext) -> Result<()> {
+        for (schema, writer) in &self.writers {
+            let rows = schema.game_rows(game_context)?;
+            let mut csv = writer
+                .lock()
+                .map_err(|e| anyhow!("Failed to acquire writer lock: {e}"))?;
+            for row in rows {
+                csv.write_record(row)?;
+            }
         }
-        csv.serialize(line).context("Failed to write line")
+        Ok(())
+    }
+
+    fn flush_all(&self) -> Result<()> {
+        for (_, writer) in &self.writers {
+            writer
+                .lock()
+                .map_err(|e| anyhow!("Failed to acquire writer lock: {e}"))?
+                .flush()?;
+        }
+        Ok(())
     }
 }
 
@@ -187,143 +788,228 @@ struct FileInfo {
     pub file_index: usize,
 }
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone, Ord, PartialOrd, Hash, Display, EnumIter, Key)]
-#[strum(serialize_all = "snake_case")]
-enum EventFileSchema {
-    Games,
-    GameLineupAppearances,
-    GameFieldingAppearances,
-    GameEarnedRuns,
-    Events,
-    EventAudit,
-    EventBaserunners,
-    EventFieldingPlay,
-    EventPitchSequences,
-    EventFlags,
-    EventComments,
-    BoxScoreGames,
-    BoxScoreLineScores,
-    BoxScoreBattingLines,
-    BoxScorePitchingLines,
-    BoxScoreFieldingLines,
-    BoxScorePinchHittingLines,
-    BoxScorePinchRunningLines,
-    BoxScoreTeamMiscellaneousLines,
-    BoxScoreTeamBattingLines,
-    BoxScoreTeamFieldingLines,
-    BoxScoreDoublePlays,
-    BoxScoreTriplePlays,
-    BoxScoreHitByPitches,
-    BoxScoreHomeRuns,
-    BoxScoreStolenBases,
-    BoxScoreCaughtStealing,
-    BoxScoreComments,
-}
-
-impl EventFileSchema {
-    const fn uses_custom_header(self) -> bool {
-        matches!(
-            self,
-            Self::BoxScoreBattingLines
-                | Self::BoxScorePitchingLines
-                | Self::BoxScoreFieldingLines
-                | Self::BoxScorePinchHittingLines
-                | Self::BoxScorePinchRunningLines
-                | Self::BoxScoreTeamMiscellaneousLines
-                | Self::BoxScoreTeamBattingLines
-                | Self::BoxScoreTeamFieldingLines
-                | Self::BoxScoreDoublePlays
-                | Self::BoxScoreTriplePlays
-                | Self::BoxScoreHitByPitches
-                | Self::BoxScoreHomeRuns
-                | Self::BoxScoreStolenBases
-                | Self::BoxScoreCaughtStealing
-        )
-    }
+/// Writes each game of a file to the output tables
+struct GameWriter;
 
+impl GameWriter {
     fn write(
-        reader: RetrosheetReader,
+        mut reader: RetrosheetReader,
         parsed_games: Option<&HashSet<GameId>>,
+        sample: Option<Sample>,
+        previous_manifest: Option<&PreviousManifest>,
         use_json: bool,
-    ) -> Result<Vec<GameId>> {
+    ) -> Result<(Vec<GameAccountLinks>, RunTelemetry)> {
         let file_info = reader.file_info;
         debug!("Processing file {}", file_info.filename);
 
-        let mut game_ids = Vec::with_capacity(81);
+        let mut accounts = Vec::with_capacity(81);
+        let mut telemetry = RunTelemetry::new(file_info);
 
-        for (game_num, record_vec_result) in reader.enumerate() {
+        for (game_num, record_vec_result) in reader.by_ref().enumerate() {
             if let Err(e) = record_vec_result {
                 error!("{:?}", e);
+                telemetry.error_count += 1;
                 continue;
             }
             let record_vec = record_vec_result?;
             let record_slice = &record_vec.record_vec;
 
-            let game_context_result =
-                GameContext::new(record_slice, file_info, record_vec.line_offset, game_num);
+            let game_context_result = GameContext::new(
+                record_slice,
+                file_info,
+                record_vec.line_offset,
+                game_num,
+                record_vec.content_hash,
+            )
+            .and_then(|gc| Self::check_unknown_fielders(&gc).map(|()| gc));
             if let Err(e) = game_context_result {
                 let game_id = if let Some(MappedRecord::GameId(id)) = record_slice.get(0) {
                     id.id.as_str()
                 } else { "unknown" };
                 let filename = file_info.filename.as_str();
                 error!("Error initializing game {game_id} in file {filename}: {:?}", e);
+                telemetry.error_count += 1;
                 continue;
             }
             let game_context = game_context_result?;
-            game_ids.push(game_context.game_id);
-            if parsed_games
+            telemetry.game_count += 1;
+            telemetry.event_count += game_context.events.len();
+            let is_duplicate = parsed_games
                 .map(|pg| pg.contains(&game_context.game_id))
-                .unwrap_or_default()
-            {
+                .unwrap_or_default();
+            accounts.push(GameAccountLinks::new(&game_context, !is_duplicate));
+            if is_duplicate {
                 warn!(
                     "File {} contains already-processed game {}, ignoring",
                     file_info.filename, &game_context.game_id.id
                 );
                 continue;
             }
-            if use_json {
-                let mut json_writer = JSON_WRITER.json()?;
-                serde_json::to_writer(&mut *json_writer, &game_context)?;
-                json_writer.write("\n".as_bytes())?;
-            } else if game_context.file_info.account_type == AccountType::BoxScore {
-                Self::write_box_score_files(&game_context, record_slice)?;
+            if sample.is_some_and(|s| !s.includes(&game_context.game_id)) {
+                continue;
+            }
+            if *SKIP_EXHIBITIONS && game_context.setting.game_type.is_exhibition() {
+                debug!("Game {} is an exhibition, skipping", game_context.game_id.id);
+                continue;
+            }
+            let manifest = GameManifest::from(&game_context);
+            WRITER_MAP.write_rows(EventFileSchema::GameManifest, [manifest])?;
+            if previous_manifest
+                .and_then(|m| m.get(&manifest.game_id))
+                .is_some_and(|h| *h == manifest.content_hash)
+            {
+                debug!("Game {} is unchanged, skipping", manifest.game_id);
+                // Aggregates are written whole, so they still need every game
+                AGGREGATES.add_game(&game_context)?;
+                continue;
+            }
+            Self::write_game(&game_context, record_slice, use_json)?;
+        }
+        telemetry.non_utf8_line_count = reader.non_utf8_lines();
+        Ok((accounts, telemetry))
+    }
+
+    /// Rejects games that credit a play to an unknown fielder, if requested
+    fn check_unknown_fielders(game_context: &GameContext) -> Result<()> {
+        if *UNKNOWN_FIELDER_POLICY != UnknownFielderPolicy::Error {
+            return Ok(());
+        }
+        let unknown_fielder_event = game_context.events.iter().find(|e| {
+            e.results
+                .fielding_plays
+                .iter()
+                .any(|fp| fp.fielding_position == FieldingPosition::Unknown)
+        });
+        if let Some(e) = unknown_fielder_event {
+            bail!("Unknown fielder in event {}", e.event_id);
+        }
+        Ok(())
+    }
+
+    fn write_game(
+        game_context: &GameContext,
+        record_slice: &RecordSlice,
+        use_json: bool,
+    ) -> Result<()> {
+        on_game(game_context)?;
+        if *AGGREGATE_ONLY {
+            return if game_context.file_info.account_type == AccountType::BoxScore {
+                Ok(())
             } else {
-                Self::write_play_by_play_files(&game_context)?;
+                AGGREGATES.add_game(game_context)
+            };
+        }
+        let mut findings = LintFindings::batting_order(game_context);
+        findings.extend(LintFindings::fielders_choices(game_context));
+        findings.extend(LintFindings::fielding_credits(game_context));
+        findings.extend(LintFindings::nonstandard_ids(game_context));
+        findings.extend(LintFindings::designated_hitter_mismatches(
+            game_context,
+            &TEAM_LEAGUES,
+        ));
+        if !ROSTERS.is_empty() {
+            findings.extend(LintFindings::roster_mismatches(game_context, &ROSTERS));
+        }
+        WRITER_MAP.write_game_rows(EventFileSchema::LintFindings, game_context, findings)?;
+        if *REPLAY_CHECK && game_context.file_info.account_type != AccountType::BoxScore {
+            WRITER_MAP.write_game_rows(
+                EventFileSchema::ReplayReconciliation,
+                game_context,
+                ReplayReconciliation::from_record_slice(game_context, record_slice),
+            )?;
+        }
+        if let Some(adjustments) = GameAdjustments::from_record_slice(game_context, record_slice) {
+            WRITER_MAP.write_game_row(EventFileSchema::GameAdjustments, game_context, adjustments)?;
+        }
+        if let Some(json_writer) = JSON_WRITER.filter(|_| use_json) {
+            json_writer.write_game(game_context)?;
+        } else if game_context.file_info.account_type == AccountType::BoxScore {
+            Self::write_box_score_files(game_context, record_slice)?;
+        } else {
+            Self::write_play_by_play_files(game_context)?;
+        }
+        Ok(())
+    }
+
+    fn write_box_score_line(game_context: &GameContext, line: &BoxScoreLine) -> Result<()> {
+        let game_id = game_context.game_id.id;
+        let out = |schema| WRITER_MAP.game_writer(schema, game_context);
+        match line {
+            BoxScoreLine::BattingLine(l) => out(EventFileSchema::BoxScoreBattingLines)?
+                .writer()?
+                .serialize(BoxScoreBattingLines::new(game_id, l))?,
+            BoxScoreLine::PinchHittingLine(l) => out(EventFileSchema::BoxScorePinchHittingLines)?
+                .writer()?
+                .serialize(BoxScorePinchHittingLines::new(game_id, l))?,
+            BoxScoreLine::PinchRunningLine(l) => out(EventFileSchema::BoxScorePinchRunningLines)?
+                .writer()?
+                .serialize(BoxScorePinchRunningLines::new(game_id, l))?,
+            BoxScoreLine::PitchingLine(l) => out(EventFileSchema::BoxScorePitchingLines)?
+                .writer()?
+                .serialize(BoxScorePitchingLines::new(game_id, l))?,
+            BoxScoreLine::DefenseLine(l) => out(EventFileSchema::BoxScoreFieldingLines)?
+                .writer()?
+                .serialize(BoxScoreFieldingLines::new(game_id, l))?,
+            BoxScoreLine::TeamMiscellaneousLine(l) => out(EventFileSchema::BoxScoreTeamMiscellaneousLines)?
+                .writer()?
+                .serialize(BoxScoreTeamMiscellaneousLines::new(game_id, l))?,
+            BoxScoreLine::TeamBattingLine(l) => out(EventFileSchema::BoxScoreTeamBattingLines)?
+                .writer()?
+                .serialize(BoxScoreTeamBattingLines::new(game_id, l))?,
+            BoxScoreLine::TeamDefenseLine(l) => out(EventFileSchema::BoxScoreTeamFieldingLines)?
+                .writer()?
+                .serialize(BoxScoreTeamFieldingLines::new(game_id, l))?,
+            BoxScoreLine::Unrecognized => bail!("Unrecognized box score line"),
+        }
+        Ok(())
+    }
+
+    fn write_box_score_event(game_context: &GameContext, event: &BoxScoreEvent) -> Result<()> {
+        let game_id = game_context.game_id.id;
+        let out = |schema| WRITER_MAP.game_writer(schema, game_context);
+        match event {
+            BoxScoreEvent::DoublePlay(e) => out(EventFileSchema::BoxScoreDoublePlays)?
+                .writer()?
+                .serialize(BoxScoreFieldingPlays::new(game_id, e))?,
+            BoxScoreEvent::TriplePlay(e) => out(EventFileSchema::BoxScoreTriplePlays)?
+                .writer()?
+                .serialize(BoxScoreFieldingPlays::new(game_id, e))?,
+            BoxScoreEvent::HitByPitch(e) => out(EventFileSchema::BoxScoreHitByPitches)?
+                .writer()?
+                .serialize(BoxScoreHitByPitches::new(game_id, e))?,
+            BoxScoreEvent::HomeRun(e) => {
+                out(EventFileSchema::BoxScoreHomeRuns)?
+                    .writer()?
+                    .serialize(BoxScoreHomeRuns::new(game_id, e))?;
             }
+            BoxScoreEvent::StolenBase(e) => out(EventFileSchema::BoxScoreStolenBases)?
+                .writer()?
+                .serialize(BoxScoreStolenBaseAttempts::new(game_id, e))?,
+            BoxScoreEvent::CaughtStealing(e) => out(EventFileSchema::BoxScoreCaughtStealing)?
+                .writer()?
+                .serialize(BoxScoreStolenBaseAttempts::new(game_id, e))?,
+            BoxScoreEvent::Unrecognized => bail!("Unrecognized box score event"),
+        }
+        Ok(())
+    }
+
+    /// The `Games` row for either kind of account, with the run's options applied
+    fn games_row(game_context: &GameContext) -> Games<'_> {
+        let games = Games::from(game_context)
+            .with_leagues(&TEAM_LEAGUES, game_context.setting.season.0);
+        match *UNKNOWN_UMPIRE_POLICY {
+            UnknownUmpirePolicy::Keep => games,
+            UnknownUmpirePolicy::Vacant => games.without_unknown_umpires(),
         }
-        Ok(game_ids)
-    }
-
-    fn box_score_schema(line: &BoxScoreWritableRecord) -> Result<Self> {
-        Ok(match line.record {
-            Either::Left(bsl) => match bsl {
-                BoxScoreLine::BattingLine(_) => Self::BoxScoreBattingLines,
-                BoxScoreLine::PinchHittingLine(_) => Self::BoxScorePinchHittingLines,
-                BoxScoreLine::PinchRunningLine(_) => Self::BoxScorePinchRunningLines,
-                BoxScoreLine::PitchingLine(_) => Self::BoxScorePitchingLines,
-                BoxScoreLine::DefenseLine(_) => Self::BoxScoreFieldingLines,
-                BoxScoreLine::TeamMiscellaneousLine(_) => Self::BoxScoreTeamMiscellaneousLines,
-                BoxScoreLine::TeamBattingLine(_) => Self::BoxScoreTeamBattingLines,
-                BoxScoreLine::TeamDefenseLine(_) => Self::BoxScoreTeamFieldingLines,
-                BoxScoreLine::Unrecognized => bail!("Unrecognized box score line"),
-            },
-            Either::Right(bse) => match bse {
-                BoxScoreEvent::DoublePlay(_) => Self::BoxScoreDoublePlays,
-                BoxScoreEvent::TriplePlay(_) => Self::BoxScoreTriplePlays,
-                BoxScoreEvent::HitByPitch(_) => Self::BoxScoreHitByPitches,
-                BoxScoreEvent::HomeRun(_) => Self::BoxScoreHomeRuns,
-                BoxScoreEvent::StolenBase(_) => Self::BoxScoreStolenBases,
-                BoxScoreEvent::CaughtStealing(_) => Self::BoxScoreCaughtStealing,
-                BoxScoreEvent::Unrecognized => bail!("Unrecognized box score event"),
-            },
-        })
     }
 
     fn write_box_score_files(game_context: &GameContext, record_slice: &RecordSlice) -> Result<()> {
         // Write Game
-        WRITER_MAP
-            .get_csv(Self::BoxScoreGames)?
-            .serialize(Games::from(game_context))?;
+        WRITER_MAP.write_game_row(
+            EventFileSchema::BoxScoreGames,
+            game_context,
+            Self::games_row(game_context),
+        )?;
         // Write Linescores
         let line_scores = record_slice
             .iter()
@@ -332,70 +1018,220 @@ impl EventFileSchema {
                 _ => None,
             })
             .flat_map(|ls| BoxScoreLineScores::transform_line_score(game_context.game_id.id, ls));
-        let mut w = WRITER_MAP.get_csv(Self::BoxScoreLineScores)?;
-        for row in line_scores {
-            w.serialize(row)?;
-        }
+        WRITER_MAP.write_game_rows(EventFileSchema::BoxScoreLineScores, game_context, line_scores)?;
         // Write Comments
-        let mut w = WRITER_MAP.get_csv(Self::BoxScoreComments)?;
-        for row in BoxScoreComments::from_record_slice(&game_context.game_id.id, record_slice) {
-            w.serialize(row)?;
-        }
+        WRITER_MAP.write_game_rows(
+            EventFileSchema::BoxScoreComments,
+            game_context,
+            BoxScoreComments::from_record_slice(&game_context.game_id.id, record_slice),
+        )?;
+        WRITER_MAP.write_context::<BoxScoreReconciliation>(EventFileSchema::BoxScoreReconciliation, game_context)?;
         // Write Lines/Events
-        let game_id = game_context.game_id.id;
-        let box_score_lines = record_slice
-            .iter()
-            .filter_map(|mr| match mr {
-                MappedRecord::BoxScoreLine(bsl) => Some(Either::Left(bsl)),
-                MappedRecord::BoxScoreEvent(bse) => Some(Either::Right(bse)),
-                _ => None,
-            })
-            .map(|record| BoxScoreWritableRecord { game_id, record });
-
-        for line in box_score_lines {
-            WRITER_MAP.write_box_score_line(&line)?;
+        for record in record_slice {
+            match record {
+                MappedRecord::BoxScoreLine(bsl) => Self::write_box_score_line(game_context, bsl)?,
+                MappedRecord::BoxScoreEvent(bse) => {
+                    Self::write_box_score_event(game_context, bse)?;
+                }
+                _ => {}
+            }
         }
         Ok(())
     }
 
     fn write_play_by_play_files(game_context: &GameContext) -> Result<()> {
         // Write schemas directly serializable from GameContext
-        WRITER_MAP.write_csv::<GameEarnedRuns>(Self::GameEarnedRuns, game_context)?;
-        WRITER_MAP.write_csv::<Events>(Self::Events, game_context)?;
-        WRITER_MAP.write_csv::<EventAudit>(Self::EventAudit, game_context)?;
-        WRITER_MAP.write_csv::<EventFieldingPlays>(Self::EventFieldingPlay, game_context)?;
-        WRITER_MAP.write_csv::<EventPitchSequences>(Self::EventPitchSequences, game_context)?;
-        WRITER_MAP.write_csv::<EventComments>(Self::EventComments, game_context)?;
-        WRITER_MAP.write_csv::<EventBaserunners>(Self::EventBaserunners, game_context)?;
+        WRITER_MAP.write_context::<GameEarnedRuns>(EventFileSchema::GameEarnedRuns, game_context)?;
+        let season = game_context.setting.season.0;
+        let events = Events::from_game_context(game_context).map(|mut row| {
+            row = row.with_hands(&ROSTERS, season);
+            if *WRITE_PLAYER_NAMES {
+                row = row.with_names(&ROSTERS, season);
+            }
+            if *WRITE_RAW_STRINGS {
+                row = row.with_raw_strings(game_context);
+            }
+            if *WRITE_FIELDING_POSITIONS {
+                row = row.with_fielding_positions(game_context);
+            }
+            row
+        });
+        WRITER_MAP.write_game_rows(EventFileSchema::Events, game_context, events)?;
+        WRITER_MAP.write_context::<EventAudit>(EventFileSchema::EventAudit, game_context)?;
+        let fielding_plays = EventFieldingPlays::from_game_context(game_context).map(|row| {
+            if *UNKNOWN_FIELDER_POLICY == UnknownFielderPolicy::Null {
+                row.without_unknown_fielder()
+            } else {
+                row
+            }
+        });
+        WRITER_MAP.write_game_rows(EventFileSchema::EventFieldingPlay, game_context, fielding_plays)?;
+        if *ENRICH_PITCHES {
+            WRITER_MAP.write_game_rows(
+                EventFileSchema::EventPitchSequences,
+                game_context,
+                EventPitchSequences::enriched(game_context),
+            )?;
+        } else {
+            WRITER_MAP.write_context::<EventPitchSequences>(EventFileSchema::EventPitchSequences, game_context)?;
+        }
+        WRITER_MAP.write_context::<EventComments>(EventFileSchema::EventComments, game_context)?;
+        WRITER_MAP.write_context::<EventBaserunners>(EventFileSchema::EventBaserunners, game_context)?;
+        WRITER_MAP.write_context::<EventChargeReassignments>(
+            EventFileSchema::EventChargeReassignments,
+            game_context,
+        )?;
+        WRITER_MAP.write_context::<EventOutfieldRunnerPlays>(
+            EventFileSchema::EventOutfieldRunnerPlays,
+            game_context,
+        )?;
+        if *WRITE_FIELDING_ALIGNMENTS {
+            WRITER_MAP.write_context::<EventFieldingAlignments>(
+                EventFileSchema::EventFieldingAlignments,
+                game_context,
+            )?;
+        }
         // Write Game
-        WRITER_MAP
-            .get_csv(Self::Games)?
-            .serialize(Games::from(game_context))?;
+        let games = Self::games_row(game_context);
+        let games = if *WRITE_PLAYER_NAMES {
+            games.with_names(&ROSTERS, season)
+        } else {
+            games
+        };
+        WRITER_MAP.write_game_row(EventFileSchema::Games, game_context, games)?;
         // Write GameLineupAppearance
-        let mut w = WRITER_MAP.get_csv(Self::GameLineupAppearances)?;
-        for row in &game_context.lineup_appearances {
-            w.serialize(row)?;
-        }
+        WRITER_MAP.write_game_rows(
+            EventFileSchema::GameLineupAppearances,
+            game_context,
+            &game_context.lineup_appearances,
+        )?;
         // Write GameFieldingAppearance
-        let mut w = WRITER_MAP.get_csv(Self::GameFieldingAppearances)?;
-        for row in &game_context.fielding_appearances {
-            w.serialize(row)?;
-        }
+        WRITER_MAP.write_game_rows(
+            EventFileSchema::GameFieldingAppearances,
+            game_context,
+            &game_context.fielding_appearances,
+        )?;
+        WRITER_MAP.write_game_rows(
+            EventFileSchema::GameDhVacancies,
+            game_context,
+            &game_context.dh_vacancies,
+        )?;
+        WRITER_MAP.write_context::<PinchAppearances>(EventFileSchema::PinchAppearances, game_context)?;
+        WRITER_MAP.write_context::<PitchingAppearances>(EventFileSchema::PitchingAppearances, game_context)?;
+        WRITER_MAP.write_context::<InheritedRunners>(EventFileSchema::InheritedRunners, game_context)?;
         //Write EventFlag
-        let mut w = WRITER_MAP.get_csv(Self::EventFlags)?;
         let event_flags = game_context
             .events
             .iter()
             .flat_map(|e| &e.results.play_info);
-        for row in event_flags {
-            w.serialize(row)?;
-        }
-        Ok(())
+        WRITER_MAP.write_game_rows(EventFileSchema::EventFlags, game_context, event_flags)?;
+        DERIVED_WRITERS.write_all(game_context)?;
+        AGGREGATES.add_game(game_context)
     }
 }
 
 #[derive(Parser, Debug)]
-#[command(name = "pbp-to-box", about = ABOUT)]
+#[command(name = "baseball-computer", about = ABOUT)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Log filter directives, e.g. `debug` or `info,baseball_computer::event_file=trace`.
+    /// Falls back to `RUST_LOG`, then to `info`.
+    #[arg(long, global = true)]
+    log_level: Option<String>,
+
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Parse event files into the full set of output tables
+    Parse(Opt),
+    /// Parse event files, only writing the cross-game aggregate tables
+    Aggregate(Opt),
+    /// Check that every game in the input can be parsed, without writing any output
+    Validate {
+        #[arg(short, long)]
+        input: PathBuf,
+    },
+    /// Strictly check a single event file while it's being transcribed, printing each
+    /// problem next to the line it was found on
+    LintFile {
+        /// The event file to check. Roster files in the same directory are used to
+        /// check player IDs.
+        input: PathBuf,
+    },
+    /// Print the parsed state of a single game as JSON
+    Inspect {
+        /// The event file containing the game
+        #[arg(short, long)]
+        input: PathBuf,
+
+        #[arg(long)]
+        game_id: String,
+    },
+    /// Print a readable recap of each game in an event file: line score, scoring plays,
+    /// pitching lines, and notable events
+    Summarize {
+        /// The event file containing the games
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Only summarize this game
+        #[arg(long)]
+        game_id: Option<String>,
+
+        #[arg(long, value_enum, default_value_t = SummaryFormat::Text)]
+        format: SummaryFormat,
+    },
+    /// Write an HTML scorecard (`{game_id}.html`) for each game in an event file
+    Scorecard {
+        /// The event file containing the games
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Directory to write the scorecards to
+        #[arg(short, long)]
+        output_dir: PathBuf,
+
+        /// Only write the scorecard for this game
+        #[arg(long)]
+        game_id: Option<String>,
+    },
+    /// List the tables written by `parse`
+    Schema,
+    /// Compare the row counts of every table in two output directories
+    Diff { old: PathBuf, new: PathBuf },
+}
+
+impl Command {
+    /// Options for the commands that parse files into output tables
+    fn into_opt(self) -> Option<Opt> {
+        match self {
+            Self::Parse(opt) => Some(opt),
+            Self::Aggregate(opt) => Some(Opt {
+                aggregate_only: true,
+                ..opt
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// The output options of a parsing command, set once the command line has been parsed
+static OPT: OnceLock<Opt> = OnceLock::new();
+
+/// The output options, which are read wherever they're needed rather than passed down
+#[allow(clippy::expect_used)]
+fn opt() -> &'static Opt {
+    OPT.get()
+        .expect("Output options are only available to parsing commands")
+}
+
+#[derive(Args, Debug)]
+#[allow(clippy::struct_excessive_bools)]
 struct Opt {
     #[arg(short, long)]
     input: PathBuf,
@@ -403,30 +1239,332 @@ struct Opt {
     #[arg(short, long)]
     output_dir: PathBuf,
 
+    /// Write to a subdirectory of the output directory with this name, to keep
+    /// the output of separate runs apart
+    #[arg(long, visible_alias = "output-subdir")]
+    run_id: Option<String>,
+
+    /// Allow writing into an output directory that isn't empty
+    #[arg(long)]
+    overwrite: bool,
+
+    /// Format of the output files, other than the games JSON written with `--json`
+    #[arg(long, visible_alias = "format", value_enum, default_value_t = OutputFormat::Csv)]
+    output_format: OutputFormat,
+
     #[arg(short, long)]
     json: bool,
+
+    /// Compression for the JSON output
+    #[arg(long, value_enum, default_value_t = JsonCompression::None)]
+    json_compression: JsonCompression,
+
+    /// Only parse the game with this ID. The input must be the event file containing it.
+    #[arg(long)]
+    game_id: Option<String>,
+
+    /// Fraction of games to process, between 0 and 1
+    #[arg(long)]
+    sample: Option<f64>,
+
+    /// Seed for choosing the sampled games. The same seed always picks the same games.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Output directory of a previous run. Only games that are new or have changed since
+    /// then are written, and games that have disappeared are listed in `deleted_games.csv`.
+    /// Cross-game aggregates are still made from every game in the input.
+    #[arg(long)]
+    backfill: Option<PathBuf>,
+
+    /// Directory layout of the output files, whatever their format
+    #[arg(long, value_enum, default_value_t = Layout::Flat)]
+    layout: Layout,
+
+    /// Add the schema major version to every output file name, e.g. `games_v2.csv`,
+    /// so that readers of an incompatible layout fail loudly instead of misreading it
+    #[arg(long)]
+    versioned_file_names: bool,
+
+    /// Write the full defensive alignment for every event. Off by default since it
+    /// adds a row per event.
+    #[arg(long)]
+    fielding_alignments: bool,
+
+    /// Add count-based analytical columns (in-zone proxy, first pitch, payoff pitch,
+    /// 3-0 take) to the pitch sequence output
+    #[arg(long)]
+    pitch_enrichment: bool,
+
+    /// Add player name columns (e.g. `batter_name`) next to the IDs in the events and
+    /// games output, using the roster files found in the input directory. A player
+    /// whose name is spelled differently across rosters gets the same name everywhere,
+    /// and `player_name_resolutions` lists each spelling and the name it resolved to.
+    #[arg(long)]
+    player_names: bool,
+
+    /// Add the play and pitch sequence strings from the event file to the events output,
+    /// for checking parsed values against the source
+    #[arg(long)]
+    raw_strings: bool,
+
+    /// Add `putout_positions`, `assist_positions` and `error_positions` to the events
+    /// output, each a `;`-separated list of the fielding positions involved
+    #[arg(long)]
+    fielding_position_columns: bool,
+
+    /// Replay each play-by-play game with an independent count of runs and outs, and
+    /// write any disagreements with the game state or line score to
+    /// `replay_reconciliation.csv`
+    #[arg(long)]
+    replay_check: bool,
+
+    /// Once everything else is written, read the output back and write the null and
+    /// zero rates of every column by season to `column_profile.csv`. Only works with
+    /// CSV output.
+    #[arg(long)]
+    column_profile: bool,
+
+    /// End lines of the CSV output with CRLF rather than LF
+    #[arg(long)]
+    crlf: bool,
+
+    /// Start each CSV output file with a UTF-8 byte order mark, which Excel needs in
+    /// order to detect the encoding
+    #[arg(long)]
+    bom: bool,
+
+    /// Skip record lines that can't be parsed (with a warning) instead of failing
+    /// the whole game they're in
+    #[arg(long)]
+    skip_bad_records: bool,
+
+    /// Leave out exhibition and spring training games. These are recognized by the
+    /// `gametype` info record, or by being in an `exhibition` or `spring` directory.
+    #[arg(long)]
+    skip_exhibitions: bool,
+
+    /// Write game-by-game Elo ratings for each franchise, replaying all parsed games
+    /// in date order. Relocated franchises keep their rating under the current team ID.
+    #[arg(long)]
+    elo: bool,
+
+    /// How to treat fielders recorded as `?` or `99` in play strings
+    #[arg(long, value_enum, default_value_t = UnknownFielderPolicy::Keep)]
+    unknown_fielders: UnknownFielderPolicy,
+
+    /// How to treat umpires listed as `unknown` (or another unrecognized value)
+    #[arg(long, value_enum, default_value_t = UnknownUmpirePolicy::Keep)]
+    unknown_umpires: UnknownUmpirePolicy,
+
+    /// Event keys set aside for each game. Games with more events than this fail to
+    /// parse rather than reuse the next game's keys, and raising it shrinks the number
+    /// of files that fit in the key space.
+    #[arg(long, default_value_t = DEFAULT_MAX_EVENTS_PER_GAME)]
+    max_events_per_game: usize,
+
+    /// Set by the `aggregate` command
+    #[arg(skip)]
+    aggregate_only: bool,
 }
 
-#[allow(clippy::expect_used)]
-fn get_output_root(opt: &Opt) -> PathBuf {
-    std::fs::create_dir_all(&opt.output_dir).expect("Error occurred on output dir check");
-    opt.output_dir
-        .canonicalize()
-        .expect("Error occurred on output dir canonicalization")
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default, ValueEnum)]
+enum UnknownFielderPolicy {
+    /// Credit the play to fielding position 0
+    #[default]
+    Keep,
+    /// Leave the fielding position of the play empty
+    Null,
+    /// Fail any game with an unknown fielder
+    Error,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default, ValueEnum)]
+enum UnknownUmpirePolicy {
+    /// Count them as umpires with an empty ID, and set `umpire_known_missing_flag`
+    #[default]
+    Keep,
+    /// Treat the position as if nobody umpired there
+    Vacant,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default, ValueEnum)]
+enum LogFormat {
+    #[default]
+    Text,
+    /// One JSON object per line, for log aggregation tools
+    Json,
+}
+
+impl Opt {
+    fn run_output_dir(&self) -> PathBuf {
+        self.run_id
+            .as_ref()
+            .map_or_else(|| self.output_dir.clone(), |id| self.output_dir.join(id))
+    }
+
+    fn sample(&self) -> Result<Option<Sample>> {
+        match self.sample {
+            Some(rate) if rate > 0.0 && rate <= 1.0 => Ok(Some(Sample {
+                rate,
+                seed: self.seed,
+            })),
+            Some(rate) => bail!("Sample rate must be in (0, 1], got {rate}"),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Selects a subset of games by hashing each game ID with the seed
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    rate: f64,
+    seed: u64,
+}
+
+impl Sample {
+    fn includes(self, game_id: &GameId) -> bool {
+        let mut hasher = StableHasher::default();
+        hasher.write(&self.seed.to_le_bytes());
+        hasher.write(game_id.id.as_bytes());
+        let bucket = u32::try_from(hasher.finish() >> 32).unwrap_or_default();
+        f64::from(bucket) / f64::from(u32::MAX) < self.rate
+    }
+}
+
+/// Refuses to write into a directory that already has output in it, unless asked to
+fn check_output_dir(opt: &Opt) -> Result<()> {
+    let output_dir = opt.run_output_dir();
+    if opt.overwrite || !output_dir.exists() {
+        return Ok(());
+    }
+    if std::fs::read_dir(&output_dir)?.next().is_some() {
+        bail!(
+            "Output directory {} is not empty. Use --overwrite to replace its contents, \
+            or --run-id to write to a new subdirectory",
+            output_dir.display()
+        );
+    }
+    Ok(())
+}
+
+type PreviousManifest = HashMap<GameIdString, ArrayString<16>>;
+
+/// Reads the manifest of a previous run, in whichever format and layout that run wrote it
+fn read_previous_manifest(previous_output: &Path) -> Result<PreviousManifest> {
+    let manifest_path = |format: OutputFormat| {
+        let file_name = format!(
+            "{}{}.{}",
+            EventFileSchema::GameManifest,
+            *FILE_SUFFIX,
+            format.extension()
+        );
+        [Layout::Flat, Layout::Hive]
+            .into_iter()
+            .map(|layout| {
+                layout
+                    .schema_dir(previous_output, EventFileSchema::GameManifest)
+                    .join(&file_name)
+            })
+            .find(|path| path.exists())
+            .unwrap_or_else(|| previous_output.join(&file_name))
+    };
+    let path = manifest_path(OutputFormat::Parquet);
+    if path.exists() {
+        let file = File::open(&path)
+            .with_context(|| format!("Failed to open manifest {}", path.display()))?;
+        return SerializedFileReader::new(file)?
+            .into_iter()
+            .map(|row| {
+                let manifest: GameManifest = serde_json::from_value(row?.to_json_value())?;
+                Ok((manifest.game_id, manifest.content_hash))
+            })
+            .collect::<Result<PreviousManifest>>()
+            .with_context(|| format!("Failed to read manifest {}", path.display()));
+    }
+    let path = manifest_path(OutputFormat::Arrow);
+    if path.exists() {
+        let file = File::open(&path)
+            .with_context(|| format!("Failed to open manifest {}", path.display()))?;
+        let mut manifest = PreviousManifest::new();
+        for batch in FileReader::try_new(file, None)? {
+            let batch = batch?;
+            let column = |name: &str| {
+                batch
+                    .column_by_name(name)
+                    .and_then(|c| c.as_string_opt::<i32>())
+                    .with_context(|| format!("Manifest {} has no {name} column", path.display()))
+            };
+            for (game_id, content_hash) in column("game_id")?.iter().zip(column("content_hash")?) {
+                let (Some(game_id), Some(content_hash)) = (game_id, content_hash) else {
+                    continue;
+                };
+                manifest.insert(
+                    GameIdString::from(game_id).unwrap_or_default(),
+                    ArrayString::from(content_hash).unwrap_or_default(),
+                );
+            }
+        }
+        return Ok(manifest);
+    }
+    let path = manifest_path(OutputFormat::Jsonl);
+    if path.exists() {
+        let file = File::open(&path)
+            .with_context(|| format!("Failed to open manifest {}", path.display()))?;
+        return BufReader::new(file)
+            .lines()
+            .map(|line| {
+                let manifest: GameManifest = serde_json::from_str(&line?)?;
+                Ok((manifest.game_id, manifest.content_hash))
+            })
+            .collect::<Result<PreviousManifest>>()
+            .with_context(|| format!("Failed to read manifest {}", path.display()));
+    }
+    let path = manifest_path(OutputFormat::Csv);
+    let mut reader = csv::Reader::from_path(&path)
+        .with_context(|| format!("Failed to open manifest {}", path.display()))?;
+    reader
+        .deserialize::<GameManifest>()
+        .map_ok(|m| (m.game_id, m.content_hash))
+        .collect::<Result<PreviousManifest, csv::Error>>()
+        .with_context(|| format!("Failed to read manifest {}", path.display()))
+}
+
+enum FileOutcome {
+    Parsed {
+        accounts: Vec<GameAccountLinks>,
+        /// Games that couldn't be read or parsed and were left out
+        games_dropped: usize,
+    },
+    /// Not started because the run was interrupted
+    Skipped,
+    Unreadable,
 }
 
 struct FileProcessor {
     index: usize,
-    opt: Opt,
+    opt: &'static Opt,
     game_ids: HashSet<GameId>,
+    game_accounts: Vec<GameAccountLinks>,
+    previous_manifest: Option<PreviousManifest>,
+    files_processed: usize,
+    files_skipped: usize,
+    files_unreadable: usize,
+    games_dropped: usize,
 }
 
 impl FileProcessor {
-    pub fn new(opt: Opt) -> Self {
+    pub fn new(opt: &'static Opt) -> Self {
         Self {
             index: 0,
             opt,
             game_ids: HashSet::with_capacity(200_000),
+            game_accounts: Vec::with_capacity(200_000),
+            previous_manifest: None,
+            files_processed: 0,
+            files_skipped: 0,
+            files_unreadable: 0,
+            games_dropped: 0,
         }
     }
 
@@ -434,15 +1572,64 @@ impl FileProcessor {
         input_path: &PathBuf,
         parsed_games: Option<&HashSet<GameId>>,
         file_index: usize,
+        sample: Option<Sample>,
+        previous_manifest: Option<&PreviousManifest>,
         use_json: bool,
-    ) -> Result<Vec<GameId>> {
-        let reader = RetrosheetReader::new(input_path, file_index)?;
-        EventFileSchema::write(reader, parsed_games, use_json)
+    ) -> Result<FileOutcome> {
+        let start = Instant::now();
+        let reader = match Self::open_file(input_path, file_index) {
+            Ok(reader) => reader,
+            Err(e) => {
+                error!("Skipping unreadable file {}: {e:?}", input_path.display());
+                Self::write_unreadable_file(input_path, &e)?;
+                return Ok(FileOutcome::Unreadable);
+            }
+        };
+        let (accounts, mut telemetry) =
+            GameWriter::write(reader, parsed_games, sample, previous_manifest, use_json)?;
+        telemetry.parse_duration_seconds = start.elapsed().as_secs_f64();
+        WRITER_MAP.write_rows(EventFileSchema::RunTelemetry, [telemetry])?;
+        Ok(FileOutcome::Parsed {
+            accounts,
+            games_dropped: telemetry.error_count,
+        })
+    }
+
+    /// Opens the file, retrying with backoff on IO errors in case they're transient
+    /// (e.g. on a network drive)
+    fn open_file(input_path: &PathBuf, file_index: usize) -> Result<RetrosheetReader> {
+        let mut attempt = 1;
+        loop {
+            let reader = RetrosheetReader::new(input_path, file_index);
+            match reader.map(|r| r.skip_bad_records(*SKIP_BAD_RECORDS)) {
+                Err(e)
+                    if attempt < FILE_OPEN_ATTEMPTS && e.root_cause().is::<std::io::Error>() =>
+                {
+                    warn!(
+                        "Failed to open {} on attempt {attempt}, retrying: {e}",
+                        input_path.display()
+                    );
+                    std::thread::sleep(FILE_RETRY_BACKOFF * 2_u32.pow(attempt - 1));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn write_unreadable_file(path: &Path, error: &impl std::fmt::Display) -> Result<()> {
+        WRITER_MAP.write_rows(
+            EventFileSchema::UnreadableFiles,
+            [UnreadableFiles {
+                path: path.display().to_string(),
+                error: error.to_string(),
+            }],
+        )
     }
 
     fn contains_nlb_dupes(path: &PathBuf) -> bool {
         let s = path.to_str().unwrap_or_default();
-        if s.ends_with(".EVR") {
+        if s.trim_end_matches(".gz").ends_with(".EVR") {
             s.contains("allas") || s.contains("allpost")
         } else {
             false
@@ -456,32 +1643,92 @@ impl FileProcessor {
         } else {
             Some(&self.game_ids)
         };
-        let mut files = account_type
-            .glob(&self.opt.input)?
-            // TODO: Remove once we remove NLB AS dupes
-            .filter_ok(|p| !Self::contains_nlb_dupes(p))
-            .collect::<Result<Vec<PathBuf>, GlobError>>()?;
+        let mut files = Vec::new();
+        for path in account_type.glob(&self.opt.input)? {
+            match path {
+                // TODO: Remove once we remove NLB AS dupes
+                Ok(p) if Self::contains_nlb_dupes(&p) => {}
+                Ok(p) => files.push(p),
+                Err(e) => {
+                    error!("Skipping unreadable path {}: {e}", e.path().display());
+                    Self::write_unreadable_file(e.path(), e.error())?;
+                    self.files_unreadable += 1;
+                }
+            }
+        }
         files.par_sort();
         let file_count = files.len();
-        let games = files
+        let sample = self.opt.sample()?;
+        let outcomes = files
             .into_par_iter()
             .enumerate()
             .map(|(i, f)| {
+                if INTERRUPTED.load(Ordering::Relaxed) {
+                    return Ok(FileOutcome::Skipped);
+                }
                 Self::process_file(
                     &f,
                     parsed_games,
-                    (self.index + i) * EVENT_KEY_BUFFER,
+                    (self.index + i) * event_key_buffer(),
+                    sample,
+                    self.previous_manifest.as_ref(),
                     self.opt.json,
                 )
             })
-            .collect::<Result<Vec<Vec<GameId>>>>()?;
+            .collect::<Result<Vec<FileOutcome>>>()?;
         self.index += file_count;
-        let games = games.iter().flatten();
-        self.game_ids.extend(games);
+        for outcome in outcomes {
+            match outcome {
+                FileOutcome::Parsed {
+                    accounts,
+                    games_dropped,
+                } => {
+                    self.files_processed += 1;
+                    self.games_dropped += games_dropped;
+                    self.game_ids.extend(accounts.iter().map(|a| GameId { id: a.game_id }));
+                    self.game_accounts.extend(accounts);
+                }
+                FileOutcome::Skipped => self.files_skipped += 1,
+                FileOutcome::Unreadable => self.files_unreadable += 1,
+            }
+        }
         Ok(())
     }
 
+    fn process_single_game(&self, game_id: &str) -> Result<()> {
+        let (file_info, record_vec) = RetrosheetReader::read_game(&self.opt.input, 0, game_id)?;
+        let game_context = GameContext::new(
+            &record_vec.record_vec,
+            file_info,
+            record_vec.line_offset,
+            0,
+            record_vec.content_hash,
+        )?;
+        GameWriter::check_unknown_fielders(&game_context)?;
+        GameWriter::write_game(&game_context, &record_vec.record_vec, self.opt.json)
+    }
+
     pub fn process_files(&mut self) -> Result<()> {
+        check_output_dir(self.opt)?;
+        set_max_events_per_game(self.opt.max_events_per_game)?;
+        if self.opt.column_profile && self.opt.output_format != OutputFormat::Csv {
+            bail!("Column profiles can only be made from CSV output");
+        }
+        if self.opt.json && self.opt.output_format == OutputFormat::Jsonl {
+            bail!("--json writes games.jsonl, so it can't be combined with JSONL output");
+        }
+        init_outputs()?;
+        KnownIssue::init()?;
+        Roof::init()?;
+        if let Some(game_id) = &self.opt.game_id {
+            info!("Parsing game {game_id}");
+            self.process_single_game(game_id)?;
+            return Self::flush(false);
+        }
+        if let Some(previous_output) = &self.opt.backfill {
+            info!("Reading manifest from {}", previous_output.display());
+            self.previous_manifest = Some(read_previous_manifest(previous_output)?);
+        }
         info!("Parsing conventional play-by-play files");
         self.par_process_files(AccountType::PlayByPlay)?;
 
@@ -491,27 +1738,524 @@ impl FileProcessor {
         info!("Parsing box score files");
         self.par_process_files(AccountType::BoxScore)?;
 
+        let partial = INTERRUPTED.load(Ordering::Relaxed);
+        if partial {
+            warn!("Run was interrupted, {} files were skipped", self.files_skipped);
+        }
+        WRITER_MAP.write_rows(
+            EventFileSchema::RunManifest,
+            [RunManifest {
+                schema_version: SCHEMA_VERSION.to_string(),
+                partial,
+                files_processed: self.files_processed,
+                files_skipped: self.files_skipped,
+                files_unreadable: self.files_unreadable,
+            }],
+        )?;
+
+        // Games in skipped or unreadable files, and games that failed to parse, would
+        // all look deleted
+        let incomplete = partial || self.files_unreadable > 0 || self.games_dropped > 0;
+        if incomplete && self.previous_manifest.is_some() {
+            warn!(
+                "Not listing deleted games, since {} files were unreadable and {} games \
+                 failed to parse",
+                self.files_unreadable, self.games_dropped
+            );
+        }
+        if let (Some(previous_manifest), false) = (&self.previous_manifest, incomplete) {
+            let deleted_games = previous_manifest
+                .keys()
+                .filter(|id| !self.game_ids.contains(&GameId { id: **id }))
+                .sorted()
+                .map(|id| DeletedGames { game_id: *id });
+            WRITER_MAP.write_rows(EventFileSchema::DeletedGames, deleted_games)?;
+        }
+        WRITER_MAP.write_rows(
+            EventFileSchema::GameAccountLinks,
+            GameAccountLinks::link(std::mem::take(&mut self.game_accounts)),
+        )?;
+
+        Self::flush(partial)
+    }
+
+    fn flush(partial: bool) -> Result<()> {
+        info!("Writing aggregates");
+        AGGREGATES.write_all()?;
+        if *WRITE_PLAYER_NAMES {
+            WRITER_MAP.write_rows(
+                EventFileSchema::PlayerNameResolutions,
+                ROSTERS.name_resolutions(),
+            )?;
+        }
+
         WRITER_MAP.flush_all()?;
-        JSON_WRITER.flush()?;
+        DERIVED_WRITERS.flush_all()?;
+        if let Some(json_writer) = *JSON_WRITER {
+            json_writer.finish()?;
+        }
+
+        // Profiles are only made from CSV output (checked in `process_files`), which
+        // can still be written to after being flushed, unlike the columnar formats
+        if *WRITE_COLUMN_PROFILE {
+            info!("Profiling output columns");
+            WRITER_MAP.write_rows(EventFileSchema::ColumnProfile, column_profile(*OUTPUT_ROOT)?)?;
+            WRITER_MAP.flush_all()?;
+        }
+        if !partial {
+            std::fs::remove_file(OUTPUT_ROOT.join(INCOMPLETE_MARKER))?;
+        }
         Ok(())
     }
 }
 
-#[allow(clippy::expect_used)]
-fn main() {
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber).expect("Failed to initialize trace");
+fn init_tracing(cli: &Cli) -> Result<()> {
+    let filter = match &cli.log_level {
+        Some(directives) => EnvFilter::try_new(directives)?,
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    };
+    let builder = FmtSubscriber::builder().with_env_filter(filter);
+    match cli.log_format {
+        LogFormat::Text => tracing::subscriber::set_global_default(builder.finish())?,
+        LogFormat::Json => tracing::subscriber::set_global_default(builder.json().finish())?,
+    }
+    Ok(())
+}
 
-    let start = Instant::now();
-    let opt: Opt = Opt::parse();
+fn handle_interrupt() {
+    if INTERRUPTED.swap(true, Ordering::Relaxed) {
+        // Second Ctrl-C: give up on flushing
+        std::process::exit(130);
+    }
+    warn!("Interrupted, finishing the files in progress. Press Ctrl-C again to exit now.");
+}
+
+/// Parses every game in the input without writing anything, logging the games that
+/// fail and the entries in the known issue registry that don't match any parsed game or
+/// event. Returns whether all games parsed and all entries matched.
+fn validate(input: &Path) -> Result<bool> {
+    KnownIssue::init()?;
+    let mut files = Vec::new();
+    for account_type in [AccountType::PlayByPlay, AccountType::Deduced, AccountType::BoxScore] {
+        files.extend(account_type.glob(input)?.collect::<Result<Vec<PathBuf>, _>>()?);
+    }
+    let results = files
+        .par_iter()
+        .map(|path| {
+            let reader = match RetrosheetReader::new(path, 0) {
+                Ok(reader) => reader,
+                Err(e) => {
+                    error!("Failed to read {}: {e:?}", path.display());
+                    return (0, 1, vec![]);
+                }
+            };
+            let file_info = reader.file_info;
+            let (mut games, mut errors, mut event_counts) = (0, 0, vec![]);
+            for (game_num, record_vec) in reader.enumerate() {
+                games += 1;
+                let result = record_vec.and_then(|rv| {
+                    GameContext::new(
+                        &rv.record_vec,
+                        file_info,
+                        rv.line_offset,
+                        game_num,
+                        rv.content_hash,
+                    )
+                });
+                match result {
+                    Ok(gc) => event_counts.push((gc.game_id.id, gc.events.len())),
+                    Err(e) => {
+                        error!("Error in file {}: {e:?}", file_info.filename);
+                        errors += 1;
+                    }
+                }
+            }
+            (games, errors, event_counts)
+        })
+        .collect::<Vec<_>>();
+    let (mut game_count, mut error_count) = (0, 0);
+    let mut event_counts: HashMap<GameIdString, usize> = HashMap::new();
+    for (games, errors, counts) in results {
+        game_count += games;
+        error_count += errors;
+        for (game_id, count) in counts {
+            let max_count = event_counts.entry(game_id).or_default();
+            *max_count = count.max(*max_count);
+        }
+    }
+    info!("Parsed {game_count} games with {error_count} errors");
+    let unresolved = KnownIssue::unresolved(&event_counts);
+    for issue in &unresolved {
+        match issue.event_id {
+            Some(event_id) => error!(
+                "Known issue {} refers to event {event_id} of {}, which doesn't exist",
+                issue.id, issue.game_id
+            ),
+            None => error!(
+                "Known issue {} refers to game {}, which wasn't found",
+                issue.id, issue.game_id
+            ),
+        }
+    }
+    Ok(error_count == 0 && unresolved.is_empty())
+}
 
-    FileProcessor::new(opt)
-        .process_files()
-        .expect("Error occurred while processing files");
+fn inspect(input: &PathBuf, game_id: &str) -> Result<()> {
+    let (file_info, record_vec) = RetrosheetReader::read_game(input, 0, game_id)?;
+    let game_context = GameContext::new(
+        &record_vec.record_vec,
+        file_info,
+        record_vec.line_offset,
+        0,
+        record_vec.content_hash,
+    )?;
+    serde_json::to_writer_pretty(std::io::stdout().lock(), &game_context)?;
+    println!();
+    Ok(())
+}
 
-    let end = start.elapsed();
-    info!("Elapsed: {:?}", end);
+/// Returns whether the file was free of problems
+fn lint_file(input: &PathBuf) -> Result<bool> {
+    let lines = std::io::read_to_string(open_input(input)?)?
+        .lines()
+        .map(String::from)
+        .collect_vec();
+    let input_dir = input.parent().unwrap_or_else(|| Path::new("."));
+    let rosters = Rosters::read(input_dir)?;
+    let leagues = TeamLeagues::read(input_dir)?;
+    let reader = RetrosheetReader::new(input, 0)?.skip_bad_records(true);
+    let file_info = reader.file_info;
+    let mut problem_count = 0;
+    for (game_num, record_vec) in reader.enumerate() {
+        let record_vec = match record_vec {
+            Ok(rv) => rv,
+            Err(e) => {
+                println!("{}: {e:#}", input.display());
+                problem_count += 1;
+                continue;
+            }
+        };
+        let game_context = match GameContext::new(
+            &record_vec.record_vec,
+            file_info,
+            record_vec.line_offset,
+            game_num,
+            record_vec.content_hash,
+        ) {
+            Ok(gc) => gc,
+            Err(e) => {
+                let line_number = e
+                    .downcast_ref::<RecordLine>()
+                    .map_or(record_vec.line_offset, |l| l.0);
+                println!(
+                    "{}:{line_number}: Game could not be parsed: {}",
+                    input.display(),
+                    e.root_cause()
+                );
+                if let Some(line) = lines.get(line_number.saturating_sub(1)) {
+                    println!("    {line}");
+                }
+                problem_count += 1;
+                continue;
+            }
+        };
+        let mut findings = LintFindings::batting_order(&game_context);
+        findings.extend(LintFindings::count_mismatches(&game_context));
+        findings.extend(LintFindings::reentries(&game_context));
+        findings.extend(LintFindings::fielders_choices(&game_context));
+        findings.extend(LintFindings::fielding_credits(&game_context));
+        findings.extend(LintFindings::nonstandard_ids(&game_context));
+        findings.extend(LintFindings::designated_hitter_mismatches(
+            &game_context,
+            &leagues,
+        ));
+        if !rosters.is_empty() {
+            findings.extend(LintFindings::roster_mismatches(&game_context, &rosters));
+        }
+        findings.sort_by_key(|f| f.event_id);
+        for finding in findings {
+            let line_number = finding
+                .event_id
+                .and_then(|id| game_context.events.iter().find(|e| e.event_id == id))
+                .map_or(record_vec.line_offset, |e| e.line_number);
+            println!(
+                "{}:{line_number}: {:?}: {}",
+                input.display(),
+                finding.rule,
+                finding.message
+            );
+            if let Some(line) = lines.get(line_number.saturating_sub(1)) {
+                println!("    {line}");
+            }
+            problem_count += 1;
+        }
+    }
+    if problem_count > 0 {
+        println!("{problem_count} problems found in {}", input.display());
+    }
+    Ok(problem_count == 0)
+}
+
+fn summarize(input: &PathBuf, game_id: Option<&str>, format: SummaryFormat) -> Result<()> {
+    let reader = RetrosheetReader::new(input, 0)?;
+    let file_info = reader.file_info;
+    let separator = match format {
+        SummaryFormat::Text => "\n",
+        SummaryFormat::Markdown => "\n---\n\n",
+    };
+    let mut first = true;
+    for (game_num, record_vec) in reader.enumerate() {
+        let record_vec = record_vec?;
+        let game_context = GameContext::new(
+            &record_vec.record_vec,
+            file_info,
+            record_vec.line_offset,
+            game_num,
+            record_vec.content_hash,
+        )?;
+        if game_id.is_some_and(|id| game_context.game_id.id.as_str() != id) {
+            continue;
+        }
+        if !first {
+            print!("{separator}");
+        }
+        first = false;
+        print!(
+            "{}",
+            GameSummary::new(&game_context, &record_vec.record_vec, format).render()?
+        );
+    }
+    if first {
+        bail!("No matching games found in {}", input.display());
+    }
+    Ok(())
+}
+
+fn scorecards(input: &PathBuf, output_dir: &Path, game_id: Option<&str>) -> Result<()> {
+    std::fs::create_dir_all(output_dir)?;
+    let reader = RetrosheetReader::new(input, 0)?;
+    let file_info = reader.file_info;
+    let mut written = 0;
+    for (game_num, record_vec) in reader.enumerate() {
+        let record_vec = record_vec?;
+        let game_context = GameContext::new(
+            &record_vec.record_vec,
+            file_info,
+            record_vec.line_offset,
+            game_num,
+            record_vec.content_hash,
+        )?;
+        let id = game_context.game_id.id;
+        if game_id.is_some_and(|g| id.as_str() != g) {
+            continue;
+        }
+        let html = Scorecard::new(&game_context, &record_vec.record_vec).to_html()?;
+        std::fs::write(output_dir.join(format!("{id}.html")), html)?;
+        written += 1;
+    }
+    if written == 0 {
+        bail!("No matching games found in {}", input.display());
+    }
+    info!("Wrote {written} scorecards to {}", output_dir.display());
+    Ok(())
+}
+
+/// The schema a CSV output belongs to, from its path relative to the output directory.
+/// With the hive layout, that's the top-level directory.
+fn dataset_name(relative_path: &Path) -> String {
+    relative_path
+        .iter()
+        .next()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .trim_end_matches(".csv")
+        .trim_end_matches(FILE_SUFFIX.as_str())
+        .to_string()
+}
+
+/// Row, null and zero counts of every column of every CSV output under the directory,
+/// by season. Rows without a `season` column are matched to their game's season
+/// through `games`.
+fn column_profile(dir: &Path) -> Result<Vec<ColumnProfile>> {
+    let mut files = Vec::new();
+    for path in glob::glob(&dir.join("**/*.csv").to_string_lossy())? {
+        let path = path?;
+        let dataset = dataset_name(path.strip_prefix(dir)?);
+        if dataset != EventFileSchema::ColumnProfile.to_string() {
+            files.push((path, dataset));
+        }
+    }
+    let games = EventFileSchema::Games.to_string();
+    let mut game_seasons: HashMap<String, u16> = HashMap::new();
+    for (path, _) in files.iter().filter(|(_, dataset)| *dataset == games) {
+        let mut reader = csv::Reader::from_path(path)?;
+        let headers = reader.headers()?.clone();
+        let index = |name| headers.iter().position(|h| h == name);
+        let (Some(game_id_index), Some(season_index)) = (index("game_id"), index("season")) else {
+            continue;
+        };
+        for record in reader.records() {
+            let record = record?;
+            if let (Some(game_id), Some(Ok(season))) = (
+                record.get(game_id_index),
+                record.get(season_index).map(str::parse),
+            ) {
+                game_seasons.insert(game_id.to_string(), season);
+            }
+        }
+    }
+
+    let mut totals: BTreeMap<(String, String, Option<u16>), [u64; 3]> = BTreeMap::new();
+    for (path, dataset) in &files {
+        let mut reader = csv::Reader::from_path(path)?;
+        let headers = reader.headers()?.clone();
+        let index = |name| headers.iter().position(|h| h == name);
+        let (season_index, game_id_index) = (index("season"), index("game_id"));
+        let mut file_totals: HashMap<Option<u16>, Vec<[u64; 3]>> = HashMap::new();
+        for record in reader.records() {
+            let record = record?;
+            let season = season_index
+                .and_then(|i| record.get(i)?.parse().ok())
+                .or_else(|| game_seasons.get(record.get(game_id_index?)?).copied());
+            let columns = file_totals
+                .entry(season)
+                .or_insert_with(|| vec![[0; 3]; headers.len()]);
+            for (counts, value) in columns.iter_mut().zip(&record) {
+                counts[0] += 1;
+                counts[1] += u64::from(value.is_empty());
+                counts[2] += u64::from(value.parse::<f64>().is_ok_and(|v| v.abs() < f64::EPSILON));
+            }
+        }
+        for (season, columns) in file_totals {
+            for (column_name, counts) in headers.iter().zip(columns) {
+                let key = (dataset.clone(), column_name.to_string(), season);
+                let total = totals.entry(key).or_default();
+                for (total, count) in total.iter_mut().zip(counts) {
+                    *total += count;
+                }
+            }
+        }
+    }
+    Ok(totals
+        .into_iter()
+        .map(|(key, counts)| ColumnProfile::new(key, counts))
+        .collect())
+}
+
+/// Rows in an output table, in any of the formats a run can write. Marker files like
+/// `_INCOMPLETE` aren't tables and come back as `None`.
+fn row_count(path: &Path) -> Result<Option<u64>> {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let count_lines = |reader: &mut dyn BufRead| {
+        reader
+            .lines()
+            .try_fold(0_u64, |count, line| line.map(|_| count + 1))
+    };
+    let count = if file_name.ends_with(".csv") {
+        csv::Reader::from_path(path)?
+            .records()
+            .try_fold(0_u64, |count, record| record.map(|_| count + 1))?
+    } else if file_name.ends_with(".jsonl") {
+        count_lines(&mut BufReader::new(File::open(path)?))?
+    } else if file_name.ends_with(".jsonl.zst") {
+        count_lines(&mut BufReader::new(zstd::Decoder::new(File::open(path)?)?))?
+    } else if file_name.ends_with(".parquet") {
+        let reader = SerializedFileReader::new(File::open(path)?)?;
+        u64::try_from(reader.metadata().file_metadata().num_rows())?
+    } else if file_name.ends_with(".arrow") {
+        FileReader::try_new(File::open(path)?, None)?.try_fold(0_u64, |count, batch| {
+            Ok::<_, anyhow::Error>(count + u64::try_from(batch?.num_rows())?)
+        })?
+    } else if file_name.starts_with('_') {
+        return Ok(None);
+    } else {
+        bail!("Unrecognized output file {}", path.display())
+    };
+    Ok(Some(count))
+}
+
+/// Row counts of every table under the directory, keyed by relative path
+fn row_counts(dir: &Path) -> Result<HashMap<PathBuf, u64>> {
+    let pattern = dir.join("**/*");
+    let mut counts = HashMap::new();
+    for path in glob::glob(&pattern.to_string_lossy())? {
+        let path = path?;
+        if !path.is_file() {
+            continue;
+        }
+        let count = row_count(&path)
+            .with_context(|| format!("Failed to count rows in {}", path.display()))?;
+        if let Some(count) = count {
+            counts.insert(path.strip_prefix(dir)?.to_path_buf(), count);
+        }
+    }
+    if counts.is_empty() {
+        bail!("No output tables found in {}", dir.display());
+    }
+    Ok(counts)
+}
+
+fn diff(old: &Path, new: &Path) -> Result<()> {
+    let old_counts = row_counts(old)?;
+    let new_counts = row_counts(new)?;
+    let paths = old_counts.keys().chain(new_counts.keys()).unique().sorted();
+    for path in paths {
+        let (old_count, new_count) = (old_counts.get(path), new_counts.get(path));
+        if old_count == new_count {
+            continue;
+        }
+        let show = |c: Option<&u64>| c.map_or_else(|| String::from("-"), ToString::to_string);
+        println!("{}\t{}\t{}", path.display(), show(old_count), show(new_count));
+    }
+    Ok(())
+}
+
+fn parse(opt: Opt) -> Result<()> {
+    let opt = OPT.get_or_init(|| opt);
+    ctrlc::set_handler(handle_interrupt)?;
+    let start = Instant::now();
+    FileProcessor::new(opt).process_files()?;
+    info!("Elapsed: {:?}", start.elapsed());
     print_cache_info();
+    Ok(())
+}
+
+#[allow(clippy::expect_used)]
+fn main() {
+    let cli = Cli::parse();
+    init_tracing(&cli).expect("Failed to initialize trace");
+
+    let result = match cli.command {
+        command @ (Command::Parse(_) | Command::Aggregate(_)) => {
+            command.into_opt().map_or(Ok(()), parse)
+        }
+        Command::Validate { input } => match validate(&input) {
+            Ok(false) => std::process::exit(1),
+            result => result.map(|_| ()),
+        },
+        Command::LintFile { input } => match lint_file(&input) {
+            Ok(false) => std::process::exit(1),
+            result => result.map(|_| ()),
+        },
+        Command::Inspect { input, game_id } => inspect(&input, &game_id),
+        Command::Summarize {
+            input,
+            game_id,
+            format,
+        } => summarize(&input, game_id.as_deref(), format),
+        Command::Scorecard {
+            input,
+            output_dir,
+            game_id,
+        } => scorecards(&input, &output_dir, game_id.as_deref()),
+        Command::Schema => {
+            EventFileSchema::iter().for_each(|schema| println!("{schema}"));
+            Ok(())
+        }
+        Command::Diff { old, new } => diff(&old, &new),
+    };
+    result.expect("Error occurred while running command");
 }
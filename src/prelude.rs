@@ -0,0 +1,73 @@
+//! The types needed to parse Retrosheet files from other Rust code, without having to
+//! know which module each one lives in.
+//!
+//! A file is read one game at a time with [`RetrosheetReader`], and each game is turned
+//! into a [`GameContext`], which holds the game's metadata alongside every [`Event`]:
+//!
+//! ```no_run
+//! use baseball_computer::prelude::*;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let reader = RetrosheetReader::new(&"2023NYA.EVA".into(), 0)?;
+//! let file_info = reader.file_info;
+//! for (game_num, record_vec) in reader.enumerate() {
+//!     let record_vec = record_vec?;
+//!     let gc = GameContext::new(
+//!         &record_vec.record_vec,
+//!         file_info,
+//!         record_vec.line_offset,
+//!         game_num,
+//!         record_vec.content_hash,
+//!     )?;
+//!     for event in &gc.events {
+//!         println!(
+//!             "{} inning {}: {}",
+//!             gc.game_id.id, event.context.inning, event.raw_play
+//!         );
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! To follow the state of a game while its records are read, rather than working from
+//! the finished events, [`GameState`] can be updated one record at a time.
+//!
+//! Rows of any of the output tables can be built from a game through [`ContextToVec`],
+//! and serialized however the caller likes, including through a [`SchemaWriter`]:
+//!
+//! ```no_run
+//! use baseball_computer::prelude::*;
+//! use baseball_computer::prelude::schemas::Events;
+//!
+//! # fn write_events(gc: &GameContext) -> anyhow::Result<()> {
+//! let mut csv = csv::Writer::from_writer(std::io::stdout());
+//! for row in Events::from_game_context(gc) {
+//!     csv.serialize(row)?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub use crate::event_file::aggregates::Aggregate;
+pub use crate::event_file::builder::GameBuilder;
+#[cfg(feature = "polars")]
+pub use crate::event_file::dataframe::{to_dataframes, DataFrameWriter};
+pub use crate::event_file::game_state::{
+    BaseState, Event, EventContext, EventResults, GameContext, GameSetting, GameState,
+    GameStateMachine,
+};
+pub use crate::event_file::misc::GameId;
+pub use crate::event_file::parser::{
+    open_input, AccountType, FileInfo, MappedRecord, RecordSlice, RecordVec, RetrosheetReader,
+};
+pub use crate::event_file::play::{ParsedPlay, PlayRecord};
+pub use crate::event_file::rules::RulesEra;
+pub use crate::event_file::schemas::{self, ContextToVec};
+pub use crate::event_file::tables::EventFileSchema;
+pub use crate::event_file::traits::{
+    FieldingPosition, GameType, LineupPosition, Matchup, Player, Side,
+};
+pub use crate::event_file::writer::{
+    ArrowIpcWriter, JsonLinesWriter, ParquetWriter, RecordBatchWriter, SchemaWriter,
+};